@@ -0,0 +1,25 @@
+//! Rows as they're entered when solving: `^^ 0 0 ~ 1`, a row of emoji, or
+//! either with colors copied from the terminal
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ptndle_core::guess::Guess;
+
+fuzz_target!(|row: &str| {
+    let Ok(guess) = row.parse::<Guess>() else {
+        return;
+    };
+    assert_eq!(
+        guess.to_ascii().parse(),
+        Ok(guess),
+        "`{row}` doesn't read back from `{}`",
+        guess.to_ascii()
+    );
+    assert_eq!(
+        Guess::from_emoji(&guess.to_emoji()),
+        Some(guess),
+        "`{row}` doesn't read back from `{}`",
+        guess.to_emoji()
+    );
+});
@@ -0,0 +1,20 @@
+//! The guesses given to `solve`, like `L.L.: ~ 0 0 v 0, Hella: vv 0 0 = 0`
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ptndle_core::play::{NameAndGuess, NameAndGuesses};
+
+fuzz_target!(|guesses: &str| {
+    let Ok(NameAndGuesses(guesses)) = guesses.parse() else {
+        return;
+    };
+    for NameAndGuess { name, guess } in guesses {
+        let line = format!("{name}: {}", guess.to_ascii());
+        let read = line.parse::<NameAndGuess>();
+        assert!(
+            read.is_ok_and(|x| x.name == name && x.guess == guess),
+            "`{line}` doesn't read back"
+        );
+    }
+});
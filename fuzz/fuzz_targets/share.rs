@@ -0,0 +1,28 @@
+//! Shared results: rows of emoji, and the challenge code printed at the end of
+//! a game
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ptndle_core::challenge::Challenge;
+use ptndle_core::guess::Guess;
+
+fuzz_target!(|text: &str| {
+    for line in text.lines() {
+        if let Some(guess) = Guess::from_emoji(line) {
+            assert_eq!(
+                Guess::from_emoji(&guess.to_emoji()),
+                Some(guess),
+                "`{line}` doesn't read back"
+            );
+        }
+        if let Ok(challenge) = line.parse::<Challenge>() {
+            let code = challenge.to_string();
+            assert_eq!(
+                code.parse::<Challenge>().ok().map(|x| x.to_string()),
+                Some(code.clone()),
+                "`{line}` doesn't read back from {code}"
+            );
+        }
+    }
+});
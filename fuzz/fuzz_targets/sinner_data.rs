@@ -0,0 +1,13 @@
+//! Sinner data, in the website's format or the one this program writes. It
+//! only has to be read without panicking or getting stuck, since the data is
+//! fetched from the network.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ptndle_core::data::load_sinners_from_json;
+use ptndle_core::verbosity::Silent;
+
+fuzz_target!(|data: &[u8]| {
+    _ = load_sinners_from_json(data, &Silent);
+});
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::error::Error;
+use crate::fuzzy::fold;
+use crate::paths::config_dir;
+
+/// Nicknames the community uses for sinners, as `(alias, name)`
+const BUILTIN_ALIASES: &[(&str, &str)] = &[
+    ("99", "Ninety-Nine"),
+    ("chelsea", "Countess Chelsea"),
+    ("countess", "Countess Chelsea"),
+    ("fox", "Mr. Fox"),
+    ("gekka", "Gekkabijin"),
+    ("kawa", "Kawa-Kawa"),
+    ("luvia", "Luvia Ray"),
+    ("mac", "Macchiato"),
+    ("oak", "Oak Casket"),
+    ("pearl", "Lady Pearl"),
+    ("vautour", "Vautour Bleu"),
+];
+
+fn aliases_path() -> Option<PathBuf> { config_dir().map(|x| x.join("aliases.json")) }
+
+/// Reads the user's aliases, a JSON object from alias to sinner name
+fn load_user_aliases() -> eyre::Result<HashMap<String, String>> {
+    let Some(path) = aliases_path().filter(|x| x.exists()) else {
+        return Ok(HashMap::new());
+    };
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| Error::Io(format!("Failed to read {}: {e}", path.display())))?;
+    facet_json::from_str(&json)
+        .map_err(|e| Error::InvalidInput(format!("Failed to parse {}: {e}", path.display())).into())
+}
+
+/// Nicknames sinners can be looked up by, as `(alias, name)`
+#[derive(Debug, Clone)]
+pub struct Aliases(Vec<(String, String)>);
+
+impl Default for Aliases {
+    fn default() -> Self { Self::builtin() }
+}
+
+impl Aliases {
+    /// The nicknames the community uses
+    pub fn builtin() -> Self {
+        Self(
+            BUILTIN_ALIASES
+                .iter()
+                .map(|(alias, name)| ((*alias).to_owned(), (*name).to_owned()))
+                .collect(),
+        )
+    }
+
+    /// The user's aliases from `aliases.json` in the config directory,
+    /// followed by the built-in ones so that the user's take precedence
+    pub fn load() -> eyre::Result<Self> {
+        let mut aliases = load_user_aliases()?.into_iter().collect::<Vec<_>>();
+        aliases.extend(Self::builtin().0);
+        Ok(Self(aliases))
+    }
+
+    /// The name of the sinner `alias` refers to, ignoring case and accents
+    pub fn resolve(&self, alias: &str) -> Option<&str> {
+        let alias = fold(alias);
+        self.0
+            .iter()
+            .find(|(x, _)| fold(x) == alias)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Every alias for the sinner named `name`
+    pub fn of<'aliases>(&'aliases self, name: &str) -> impl Iterator<Item = &'aliases str> {
+        let name = fold(name);
+        self.0
+            .iter()
+            .filter(move |(_, x)| fold(x) == name)
+            .map(|(alias, _)| -> &str { alias })
+    }
+}
@@ -0,0 +1,216 @@
+//! The `analyze` command, for finding out what makes some sinners hard to
+//! find
+
+use ptndle_core::attribute::{Attribute, Cell};
+use ptndle_core::data::{load_sinners, DataSource, Filter};
+use ptndle_core::matrix::GuessMatrix;
+
+use crate::flags::{Analyze, AnalyzeCmd, Confusions};
+use crate::output::{print_json, AttributeReport, ConfusionReport};
+use crate::settings;
+
+/// The number of pairs `analyze confusions` prints by default
+const DEFAULT_PAIRS: usize = 20;
+
+/// The number of values [`cell_number`] can give
+const CELL_VALUES: usize = 7;
+
+/// `cell` as a number below [`CELL_VALUES`]
+fn cell_number(cell: Cell) -> usize {
+    match cell {
+        | Cell::Exact(same) => same.into(),
+        | Cell::Numeric(comparison) => comparison.map_or(0, |x| x as usize + 1),
+    }
+}
+
+/// How alike the sinners at `first` and `second` in `matrix` are: the number
+/// of guesses they give the same result against, and the attribute that
+/// tells them apart against the most guesses, if any does
+fn confusion(matrix: &GuessMatrix, first: usize, second: usize) -> ConfusionReport {
+    let results = (0..matrix.len())
+        .map(|guess| (matrix.get(guess, first), matrix.get(guess, second)))
+        .collect::<Vec<_>>();
+    let same = results.iter().filter(|(a, b)| a == b).count();
+    // Guessing either of them tells them apart by winning, not by an attribute
+    let told_apart = |attribute: Attribute| {
+        results
+            .iter()
+            .enumerate()
+            .filter(|&(guess, &(a, b))| {
+                guess != first && guess != second && a.cell(attribute) != b.cell(attribute)
+            })
+            .count()
+    };
+    let best = Attribute::ALL
+        .into_iter()
+        .map(|x| (x, told_apart(x)))
+        .filter(|(_, count)| *count > 0)
+        .max_by_key(|(_, count)| *count);
+    let sinners = matrix.sinners();
+    ConfusionReport {
+        first: sinners[first].name.clone(),
+        second: sinners[second].name.clone(),
+        same_guesses: same,
+        guesses: matrix.len(),
+        told_apart_by: best.map(|(x, _)| x.to_str().to_owned()),
+        values: best.map_or_else(Vec::new, |(x, _)| {
+            vec![x.value(&sinners[first]), x.value(&sinners[second])]
+        }),
+    }
+}
+
+/// Prints the pairs as a table, most alike first
+#[expect(clippy::float_arithmetic, reason = "statistics")]
+fn print_confusions(pairs: &[ConfusionReport]) {
+    let Some(guesses) = pairs.first().map(|x| x.guesses) else {
+        println!("There are fewer than 2 sinners in the pool.");
+        return;
+    };
+    println!(
+        "The pairs of sinners that give the same result against the most of the {guesses} guesses:"
+    );
+    let names = pairs
+        .iter()
+        .map(|x| format!("{}, {}", x.first, x.second))
+        .collect::<Vec<_>>();
+    let width = names
+        .iter()
+        .map(|x| x.chars().count())
+        .chain(std::iter::once("Pair".len()))
+        .max()
+        .unwrap_or_default();
+    println!("Same  {:width$}  Told apart by", "Pair");
+    for (pair, name) in pairs.iter().zip(names) {
+        #[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
+        let share = pair.same_guesses as f64 * 100. / pair.guesses as f64;
+        let told_apart_by = match &pair.told_apart_by {
+            | Some(attribute) => format!("{attribute} ({})", pair.values.join(" vs ")),
+            | None => "only guessing one of them".to_owned(),
+        };
+        println!("{share:>3.0}%  {name:width$}  {told_apart_by}");
+    }
+}
+
+/// Prints the `top` pairs of sinners in the pool that give the same result
+/// against the most guesses
+fn confusions(
+    Confusions { top }: Confusions,
+    source: &DataSource,
+    filter: &Filter,
+    json: bool,
+) -> eyre::Result<()> {
+    let matrix = GuessMatrix::new(
+        load_sinners(&settings::context(), source, filter)?,
+        filter.thresholds,
+    );
+    let mut pairs = (0..matrix.len())
+        .flat_map(|first| (first + 1..matrix.len()).map(move |second| (first, second)))
+        .map(|(first, second)| confusion(&matrix, first, second))
+        .collect::<Vec<_>>();
+    // Stable, so that ties stay in the order of the sinners
+    pairs.sort_by(|a, b| b.same_guesses.cmp(&a.same_guesses));
+    pairs.truncate(top.unwrap_or(DEFAULT_PAIRS));
+    if json {
+        print_json(&pairs);
+    } else {
+        print_confusions(&pairs);
+    }
+    Ok(())
+}
+
+/// The information gained from the groups of `sizes` that `total` sinners
+/// are split into, in bits, and the expected number of sinners left
+#[expect(clippy::float_arithmetic, reason = "statistics")]
+#[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
+fn split_scores(sizes: impl Iterator<Item = usize>, total: usize) -> (f64, f64) {
+    let total = total as f64;
+    sizes
+        .filter(|x| *x > 0)
+        .map(|x| x as f64 / total)
+        .fold((0., 0.), |(bits, left), share| {
+            (bits - share * share.log2(), left + share * share * total)
+        })
+}
+
+/// Scores `attribute` as a guess's only cell: the information it gains and
+/// the sinners it leaves, averaged over every guess in `matrix`. With `None`,
+/// the whole result is scored instead.
+#[expect(clippy::float_arithmetic, reason = "statistics")]
+#[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
+fn score(matrix: &GuessMatrix, attribute: Option<Attribute>) -> AttributeReport {
+    let (bits, left) = (0..matrix.len())
+        .map(|guess| {
+            let results = (0..matrix.len()).map(|target| matrix.get(guess, target));
+            match attribute {
+                | Some(attribute) => {
+                    let mut sizes = [0; CELL_VALUES];
+                    for result in results {
+                        sizes[cell_number(result.cell(attribute))] += 1;
+                    }
+                    split_scores(sizes.into_iter(), matrix.len())
+                },
+                | None => {
+                    let mut sizes = std::collections::HashMap::<_, usize>::new();
+                    for result in results {
+                        *sizes.entry(result).or_default() += 1;
+                    }
+                    split_scores(sizes.into_values(), matrix.len())
+                },
+            }
+        })
+        .fold((0., 0.), |(bits, left), (x, y)| (bits + x, left + y));
+    let guesses = matrix.len().max(1) as f64;
+    AttributeReport {
+        attribute: attribute.map_or("all", Attribute::to_str).to_owned(),
+        entropy: bits / guesses,
+        expected_remaining: left / guesses,
+    }
+}
+
+/// Prints how much each attribute narrows down the pool as a table, most
+/// first
+fn print_attributes(attributes: &[AttributeReport], sinners: usize) {
+    println!(
+        "What each cell of a result says about the target among {sinners} sinners, on average:"
+    );
+    println!("Attribute   Entropy  Expected");
+    for attribute in attributes {
+        println!(
+            "{:10}  {:>7.3}  {:>8.2}",
+            attribute.attribute, attribute.entropy, attribute.expected_remaining
+        );
+    }
+}
+
+/// Prints how much each attribute narrows down the pool on its own, and the
+/// whole result for comparison
+fn attributes(source: &DataSource, filter: &Filter, json: bool) -> eyre::Result<()> {
+    let matrix = GuessMatrix::new(
+        load_sinners(&settings::context(), source, filter)?,
+        filter.thresholds,
+    );
+    let mut reports = Attribute::ALL
+        .into_iter()
+        .map(|x| score(&matrix, Some(x)))
+        .collect::<Vec<_>>();
+    reports.sort_by(|a, b| b.entropy.total_cmp(&a.entropy));
+    reports.push(score(&matrix, None));
+    if json {
+        print_json(&reports);
+    } else {
+        print_attributes(&reports, matrix.len());
+    }
+    Ok(())
+}
+
+pub fn run(
+    Analyze { subcommand }: Analyze,
+    source: &DataSource,
+    filter: &Filter,
+    json: bool,
+) -> eyre::Result<()> {
+    match subcommand {
+        | AnalyzeCmd::Confusions(flags) => confusions(flags, source, filter, json),
+        | AnalyzeCmd::Attributes(_) => attributes(source, filter, json),
+    }
+}
@@ -0,0 +1,339 @@
+//! The attributes of a sinner that are compared when guessing, each with a
+//! cell of its own in a [`Guess`](crate::guess::Guess)
+//!
+//! Everything that goes over the attributes in turn, such as
+//! [`Sinner::guess`], [`Sinner::matches_result`] and reading and showing a
+//! guess, goes over [`Attribute::ALL`]. A new attribute is a variant of
+//! [`Attribute`] with an arm in each of its methods and free bits for its
+//! cell.
+
+use owo_colors::Styled;
+
+use crate::compare::{Comparison, Threshold, ThresholdConfig};
+use crate::data::Sinner;
+use crate::guess::{HumanBool, MaybeComparison};
+use crate::i18n::Message;
+use crate::theme::{Role, Theme};
+
+/// An attribute of a sinner that is compared with the target's
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Attribute {
+    Code,
+    Alignment,
+    Tendency,
+    Height,
+    Birthplace,
+}
+
+/// How the values of an attribute are compared
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// The values are either the same or not
+    Exact,
+    /// The values are numbers, and the result is an arrow pointing from the
+    /// guess's value towards the target's, as far as the target's
+    /// [`Threshold`] goes
+    Numeric,
+}
+
+/// The result of comparing one attribute of a guess with the target's
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    /// Whether the values of a [`Kind::Exact`] attribute are the same
+    Exact(bool),
+    /// The arrow for a [`Kind::Numeric`] attribute, or `None` if only one of
+    /// the values is a number, like when NOX's code is compared with another
+    Numeric(Option<Comparison>),
+}
+
+/// Where an attribute's cell is kept in the bits of a
+/// [`Guess`](crate::guess::Guess)
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Bits {
+    /// The lowest bit of the cell, which takes 1 bit if the attribute is
+    /// [`Kind::Exact`] and 3 if it's [`Kind::Numeric`]
+    pub offset: u8,
+    /// The bit that is set when a numeric cell has an arrow, for attributes
+    /// that can have none
+    pub valid: Option<u8>,
+}
+
+impl Attribute {
+    /// The number of attributes
+    pub const COUNT: usize = 5;
+
+    /// Every attribute, in the order of the columns on the website
+    pub const ALL: [Self; Self::COUNT] = [
+        Self::Code,
+        Self::Alignment,
+        Self::Tendency,
+        Self::Height,
+        Self::Birthplace,
+    ];
+
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            | Self::Code => "code",
+            | Self::Alignment => "alignment",
+            | Self::Tendency => "tendency",
+            | Self::Height => "height",
+            | Self::Birthplace => "birthplace",
+        }
+    }
+
+    /// The header of the attribute's column in tables
+    pub const fn header(self) -> &'static str {
+        match self {
+            | Self::Code => "Code",
+            | Self::Alignment => "Alignment",
+            | Self::Tendency => "Tendency",
+            | Self::Height => "Height",
+            | Self::Birthplace => "Birthplace",
+        }
+    }
+
+    /// The header of the attribute's column in the results of a game, which is
+    /// translated
+    pub const fn message(self) -> Message {
+        match self {
+            | Self::Code => Message::Code,
+            | Self::Alignment => Message::Alignment,
+            | Self::Tendency => Message::Tendency,
+            | Self::Height => Message::Height,
+            | Self::Birthplace => Message::Birthplace,
+        }
+    }
+
+    pub const fn kind(self) -> Kind {
+        match self {
+            | Self::Code | Self::Height => Kind::Numeric,
+            | Self::Alignment | Self::Tendency | Self::Birthplace => Kind::Exact,
+        }
+    }
+
+    /// The attribute's cell when the values are the same, as they are when the
+    /// target is guessed
+    pub const fn correct(self) -> Cell {
+        match self.kind() {
+            | Kind::Exact => Cell::Exact(true),
+            | Kind::Numeric => Cell::Numeric(Some(Comparison::Correct)),
+        }
+    }
+
+    /// Whether a cell of the attribute can be [`Cell::Numeric`] without an
+    /// arrow, because some sinners don't have a number for it
+    pub const fn optional(self) -> bool { matches!(self, Self::Code) }
+
+    /// Every cell the attribute can have
+    pub fn cells(self) -> Vec<Cell> {
+        match self.kind() {
+            | Kind::Exact => vec![Cell::Exact(true), Cell::Exact(false)],
+            | Kind::Numeric => {
+                Comparison::ALL
+                    .into_iter()
+                    .map(Some)
+                    .chain(self.optional().then_some(None))
+                    .map(Cell::Numeric)
+                    .collect()
+            },
+        }
+    }
+
+    pub(crate) const fn bits(self) -> Bits {
+        let (offset, valid) = match self {
+            | Self::Code => (0, Some(7)),
+            | Self::Height => (3, None),
+            | Self::Alignment => (6, None),
+            | Self::Tendency => (8, None),
+            | Self::Birthplace => (9, None),
+        };
+        Bits { offset, valid }
+    }
+
+    /// The attribute's value for `sinner` as it's shown in tables
+    pub fn value(self, sinner: &Sinner) -> String {
+        match self {
+            | Self::Code => {
+                sinner
+                    .code
+                    .map_or_else(|| "NOX".to_owned(), |x| x.to_string())
+            },
+            | Self::Alignment => format!("{:?}", sinner.alignment),
+            | Self::Tendency => format!("{:?}", sinner.tendency),
+            | Self::Height => format!("{}cm", sinner.height),
+            | Self::Birthplace => format!("{:?}", sinner.birthplace),
+        }
+    }
+
+    /// The attribute's value for `sinner` as a number, if it's
+    /// [`Kind::Numeric`] and the sinner has one
+    pub fn number(self, sinner: &Sinner) -> Option<u16> {
+        match self {
+            | Self::Code => sinner.code,
+            | Self::Height => Some(sinner.height.into()),
+            | Self::Alignment | Self::Tendency | Self::Birthplace => None,
+        }
+    }
+
+    /// The threshold a guess's value is compared with `target`'s value with,
+    /// with the formulas in `config`
+    pub fn threshold(self, target: &Sinner, config: &ThresholdConfig) -> Option<Threshold> {
+        match self {
+            | Self::Code => target.code.map(|x| config.code(x)),
+            | Self::Height => Some(config.height(target.height)),
+            | Self::Alignment | Self::Tendency | Self::Birthplace => None,
+        }
+    }
+
+    /// Whether `a` and `b` have the same value for the attribute
+    pub fn same(self, a: &Sinner, b: &Sinner) -> bool {
+        match self {
+            | Self::Code | Self::Height => self.number(a) == self.number(b),
+            | Self::Alignment => a.alignment == b.alignment,
+            | Self::Tendency => a.tendency == b.tendency,
+            | Self::Birthplace => a.birthplace == b.birthplace,
+        }
+    }
+
+    /// The attribute's cell when `guess` is guessed and `target` is the
+    /// target, with the thresholds in `config`
+    pub fn compare(self, target: &Sinner, guess: &Sinner, config: &ThresholdConfig) -> Cell {
+        match self.kind() {
+            | Kind::Exact => Cell::Exact(self.same(target, guess)),
+            | Kind::Numeric => {
+                let numbers = (self.number(target), self.number(guess));
+                Cell::Numeric(match (numbers, self.threshold(target, config)) {
+                    | ((Some(expected), Some(guessed)), Some(threshold)) => {
+                        Some(threshold.compare(expected, guessed))
+                    },
+                    // The only sinner without a numeric code is NOX, and
+                    // guessing NOX when it's the target gives =
+                    | ((None, None), _) => Some(Comparison::Correct),
+                    | _ => None,
+                })
+            },
+        }
+    }
+
+    /// Whether guessing `guess` when `target` is the target would give `cell`
+    /// for the attribute. This is worked out from [`Threshold::inverse`]
+    /// rather than by comparing, but agrees with [`Attribute::compare`].
+    pub fn matches(
+        self,
+        target: &Sinner,
+        guess: &Sinner,
+        cell: Cell,
+        config: &ThresholdConfig,
+    ) -> bool {
+        match (self.kind(), cell) {
+            | (Kind::Exact, Cell::Exact(same)) => self.same(target, guess) == same,
+            | (Kind::Numeric, Cell::Numeric(comparison)) => {
+                let numbers = (self.number(target), self.number(guess));
+                match (numbers, self.threshold(target, config), comparison) {
+                    | ((Some(expected), Some(guessed)), Some(threshold), Some(comparison)) => {
+                        threshold.matches(expected, guessed, comparison)
+                    },
+                    | ((None, None), _, comparison) => comparison == Some(Comparison::Correct),
+                    | ((Some(_), None) | (None, Some(_)), _, comparison) => comparison.is_none(),
+                    | _ => false,
+                }
+            },
+            | _ => false,
+        }
+    }
+}
+
+impl Cell {
+    /// Whether the cell says the values are the same
+    pub fn same(self) -> bool {
+        match self {
+            | Self::Exact(same) => same,
+            | Self::Numeric(comparison) => comparison == Some(Comparison::Correct),
+        }
+    }
+
+    /// The arrow of a numeric cell, if it has one
+    pub const fn arrow(self) -> Option<Comparison> {
+        match self {
+            | Self::Exact(_) => None,
+            | Self::Numeric(comparison) => comparison,
+        }
+    }
+
+    /// Reads `attribute`'s cell as it is entered when solving, such as `^^`
+    /// or `1`
+    pub fn parse(attribute: Attribute, s: &str) -> Option<Self> {
+        match attribute.kind() {
+            | Kind::Exact => Some(Self::Exact(s.parse::<HumanBool>().ok()?.0)),
+            | Kind::Numeric => {
+                let comparison = s.parse::<MaybeComparison>().ok()?.0;
+                (comparison.is_some() || attribute.optional()).then_some(Self::Numeric(comparison))
+            },
+        }
+    }
+
+    /// Reads `attribute`'s cell from the emoji it is shared as
+    pub fn from_emoji(attribute: Attribute, emoji: char) -> Option<Self> {
+        match (attribute.kind(), emoji) {
+            | (Kind::Exact, '🟩') => Some(Self::Exact(true)),
+            | (Kind::Exact, '🟥') => Some(Self::Exact(false)),
+            | (Kind::Numeric, '⬛') if attribute.optional() => Some(Self::Numeric(None)),
+            | (Kind::Numeric, emoji) => {
+                Comparison::from_emoji(emoji).map(|x| Self::Numeric(Some(x)))
+            },
+            | _ => None,
+        }
+    }
+
+    /// The cell as it is displayed without colors, two characters wide
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            | Self::Exact(true) => " 1",
+            | Self::Exact(false) => " 0",
+            | Self::Numeric(None) => " x",
+            | Self::Numeric(Some(comparison)) => comparison.to_str(),
+        }
+    }
+
+    /// The cell as it is displayed, in the colors of `theme`
+    pub fn paint(self, theme: &Theme) -> Styled<&'static str> {
+        theme.paint(self.to_str(), self.role())
+    }
+
+    /// How the cell is styled
+    pub const fn role(self) -> Role {
+        match self {
+            | Self::Exact(true) => Role::Correct,
+            | Self::Exact(false) | Self::Numeric(None) => Role::Wrong,
+            | Self::Numeric(Some(comparison)) => comparison.role(),
+        }
+    }
+
+    /// The cell as it is entered when solving, such as `^^` or `1`
+    pub fn to_ascii(self) -> &'static str {
+        match self {
+            | Self::Exact(same) => {
+                if same {
+                    "1"
+                } else {
+                    "0"
+                }
+            },
+            | Self::Numeric(comparison) => comparison.map_or("x", Comparison::to_ascii),
+        }
+    }
+
+    /// The cell as an emoji for sharing results
+    pub fn to_emoji(self) -> &'static str {
+        match self {
+            | Self::Exact(same) => {
+                if same {
+                    "🟩"
+                } else {
+                    "🟥"
+                }
+            },
+            | Self::Numeric(comparison) => comparison.map_or("⬛", Comparison::to_emoji),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! The `bench` command, for measuring how fast the solver is so that
+//! slowdowns show up without external tools
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use eyre::eyre;
+use ptndle_core::data::{load_sinners, DataSource, Filter, Sinner};
+use ptndle_core::error::Error;
+use ptndle_core::matrix::GuessMatrix;
+use ptndle_core::play::{Game, Player};
+use ptndle_core::rng::Rng;
+use ptndle_core::solver::{SolverConfig, Strategy};
+
+use crate::console::status;
+use crate::flags::{Bench, Sizes, Strategies};
+use crate::output::{print_json, BenchReport};
+use crate::settings;
+
+/// The times taken by one strategy to play every game on one pool
+struct Timings {
+    strategy: String,
+    sinners: usize,
+    /// The time taken to play every game, including setting up the solver
+    total: Duration,
+    /// The time taken by each `next_guess`, for every game
+    turns: Vec<Vec<Duration>>,
+}
+
+impl Timings {
+    fn turn_count(&self) -> usize { self.turns.iter().map(Vec::len).sum() }
+
+    fn mean_turn(&self) -> Duration {
+        let sum = self.turns.iter().flatten().sum::<Duration>();
+        mean(sum, self.turn_count())
+    }
+
+    fn max_turn(&self) -> Duration {
+        self.turns
+            .iter()
+            .flatten()
+            .max()
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn first_guess(&self) -> Duration {
+        let sum = self
+            .turns
+            .iter()
+            .filter_map(|x| x.first())
+            .sum::<Duration>();
+        mean(sum, self.turns.len())
+    }
+
+    fn games_per_second(&self) -> u64 {
+        let games = u128::try_from(self.turns.len()).unwrap_or(u128::MAX);
+        let per_second = games * 1_000_000_000 / self.total.as_nanos().max(1);
+        u64::try_from(per_second).unwrap_or(u64::MAX)
+    }
+}
+
+impl From<&Timings> for BenchReport {
+    fn from(timings: &Timings) -> Self {
+        let micros = |x: Duration| u64::try_from(x.as_micros()).unwrap_or(u64::MAX);
+        Self {
+            strategy: timings.strategy.clone(),
+            sinners: timings.sinners,
+            turns: timings.turn_count(),
+            total: micros(timings.total),
+            games_per_second: timings.games_per_second(),
+            mean_turn: micros(timings.mean_turn()),
+            max_turn: micros(timings.max_turn()),
+            first_guess: micros(timings.first_guess()),
+        }
+    }
+}
+
+fn mean(sum: Duration, count: usize) -> Duration {
+    u32::try_from(count).map_or(Duration::ZERO, |x| sum.checked_div(x).unwrap_or_default())
+}
+
+/// Picks `size` sinners from `sinners` using `rng`, keeping them in the same
+/// order
+fn pick_pool(sinners: &[Sinner], size: usize, rng: &mut Rng) -> Vec<Sinner> {
+    let mut indices = (0..sinners.len()).collect::<Vec<_>>();
+    for i in 0..size {
+        let j = i + rng.below(indices.len() - i);
+        indices.swap(i, j);
+    }
+    indices.truncate(size);
+    indices.sort_unstable();
+    indices.into_iter().map(|x| sinners[x].clone()).collect()
+}
+
+/// Plays every game on `matrix` one at a time, timing each guess
+fn time_games(config: SolverConfig, matrix: &Arc<GuessMatrix>) -> eyre::Result<Timings> {
+    let start = Instant::now();
+    let mut turns = Vec::with_capacity(matrix.len());
+    for target in matrix.sinners() {
+        let mut solver = config.solver(Arc::clone(matrix));
+        let mut game = Game::new(target, *matrix.thresholds());
+        let mut times = Vec::new();
+        loop {
+            let guess_start = Instant::now();
+            let guess = solver
+                .next_guess()
+                .ok_or_else(|| eyre!("The {} solver gave up on {}", config.strategy, target.name))?
+                .clone();
+            times.push(guess_start.elapsed());
+            match game.guess(&guess) {
+                | Some(result) => solver.update(result, &guess),
+                | None => break,
+            }
+        }
+        turns.push(times);
+    }
+    Ok(Timings {
+        strategy: config.strategy.to_string(),
+        sinners: matrix.len(),
+        total: start.elapsed(),
+        turns,
+    })
+}
+
+/// Prints a table of the timings, one row for each strategy and pool
+fn print_timings(timings: &[Timings]) {
+    let name_width = timings
+        .iter()
+        .map(|x| x.strategy.len())
+        .chain(std::iter::once("Strategy".len()))
+        .max()
+        .unwrap_or_default();
+    println!(
+        "{:name_width$}  Sinners  Turns  {:>10}  {:>8}  {:>10}  {:>10}  {:>10}",
+        "Strategy", "Total", "Games/s", "Mean turn", "Max turn", "First"
+    );
+    for timing in timings {
+        println!(
+            "{:name_width$}  {:>7}  {:>5}  {:>10}  {:>8}  {:>10}  {:>10}  {:>10}",
+            timing.strategy,
+            timing.sinners,
+            timing.turn_count(),
+            format!("{:.2?}", timing.total),
+            timing.games_per_second(),
+            format!("{:.2?}", timing.mean_turn()),
+            format!("{:.2?}", timing.max_turn()),
+            format!("{:.2?}", timing.first_guess()),
+        );
+    }
+}
+
+/// Times every strategy in `strategies` playing every game on pools of each
+/// size in `sizes`, and prints how fast they were
+pub fn run(
+    Bench {
+        strategy: strategies,
+        depth,
+        probe,
+        sizes,
+        seed,
+    }: Bench,
+    source: &DataSource,
+    filter: &Filter,
+    json: bool,
+) -> eyre::Result<()> {
+    let sinners = load_sinners(&settings::context(), source, filter)?;
+    let strategies = strategies.map_or_else(|| Strategy::ALL.to_vec(), |Strategies(x)| x);
+    let mut sizes = sizes.map_or_else(
+        || vec![sinners.len() / 4, sinners.len() / 2, sinners.len()],
+        |Sizes(x)| x,
+    );
+    sizes.retain(|&x| x > 0);
+    sizes.dedup();
+    if let Some(size) = sizes.iter().find(|&&x| x > sinners.len()) {
+        return Err(Error::InvalidInput(format!(
+            "Can't time a pool of {size} sinners when there are only {} in the pool",
+            sinners.len()
+        ))
+        .into());
+    }
+    let seed = seed.unwrap_or_default();
+    let mut timings = Vec::new();
+    for &size in &sizes {
+        let pool = pick_pool(&sinners, size, &mut Rng::new(seed));
+        let matrix = Arc::new(GuessMatrix::new(pool, filter.thresholds));
+        for &strategy in &strategies {
+            status!("Timing {strategy} on {size} sinners");
+            let config = SolverConfig {
+                strategy,
+                depth: depth.unwrap_or(1),
+                probe,
+                seed,
+                tie_break: settings::tie_break(),
+            };
+            timings.push(time_games(config, &matrix)?);
+        }
+    }
+    if json {
+        print_json(&timings.iter().map(BenchReport::from).collect::<Vec<_>>());
+    } else {
+        print_timings(&timings);
+    }
+    Ok(())
+}
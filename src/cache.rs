@@ -0,0 +1,117 @@
+//! The `cache` command, for inspecting and clearing the cache directory
+
+use std::fs;
+use std::path::Path;
+
+use ptndle_core::data::{cached_data_url, describe_age, is_cache_outdated, load_sinners,
+                        sinner_cache_path, CacheTtl, DataSource};
+use ptndle_core::error::Error;
+
+use crate::flags::{Cache, CacheAction};
+use crate::settings;
+
+/// The files in the cache directory with their sizes in bytes, sorted by name
+fn cached_files(dir: &Path) -> eyre::Result<Vec<(String, u64)>> {
+    let mut files = match fs::read_dir(dir) {
+        | Ok(entries) => {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| {
+                    let meta = entry.metadata().ok().filter(fs::Metadata::is_file)?;
+                    Some((entry.file_name().to_string_lossy().into_owned(), meta.len()))
+                })
+                .collect::<Vec<_>>()
+        },
+        | Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        | Err(e) => {
+            return Err(Error::Io(format!("Failed to read {}: {e}", dir.display())).into());
+        },
+    };
+    files.sort();
+    Ok(files)
+}
+
+/// Describes a number of bytes, such as `12.3 KiB`
+#[expect(
+    clippy::cast_precision_loss,
+    clippy::float_arithmetic,
+    reason = "The size is only shown to one decimal place"
+)]
+fn describe_size(bytes: u64) -> String {
+    match bytes {
+        | 0..1024 => format!("{bytes} B"),
+        | 1024..1_048_576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        | _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+fn status(ttl: CacheTtl) -> eyre::Result<()> {
+    let dir = settings::cache_dir();
+    println!("Cache directory: {}", dir.display());
+    let path = sinner_cache_path(settings::game());
+    match path.metadata().and_then(|x| x.modified()) {
+        | Ok(modified) => {
+            let age = describe_age(modified).unwrap_or_else(|| "an unknown time".to_owned());
+            let state = if is_cache_outdated(&path, ttl) {
+                "outdated, and will be fetched again when next needed"
+            } else {
+                "up to date"
+            };
+            println!("Sinner data: fetched {age} ago, {state} (TTL {ttl})");
+            if let Some(url) = cached_data_url(settings::game()) {
+                println!("Source: {url}");
+            }
+            match load_sinners(
+                &settings::context(),
+                &DataSource::File(path),
+                &<_>::default(),
+            ) {
+                | Ok(sinners) => println!("Sinners: {}", sinners.len()),
+                | Err(e) => println!("Sinners: unreadable ({e})"),
+            }
+        },
+        | Err(_) => println!("Sinner data: not cached"),
+    }
+    let files = cached_files(&dir)?;
+    let total = files.iter().map(|(_, size)| size).sum();
+    println!("Files: {} ({})", files.len(), describe_size(total));
+    for (name, size) in files {
+        println!("    {name:<32} {:>10}", describe_size(size));
+    }
+    Ok(())
+}
+
+fn clear() -> eyre::Result<()> {
+    let dir = settings::cache_dir();
+    let files = cached_files(&dir)?;
+    if files.is_empty() {
+        println!("The cache is already empty");
+        return Ok(());
+    }
+    for (name, _) in &files {
+        let path = dir.join(name);
+        fs::remove_file(&path)
+            .map_err(|e| Error::Io(format!("Failed to remove {}: {e}", path.display())))?;
+    }
+    let total = files.iter().map(|(_, size)| size).sum();
+    println!(
+        "Removed {} files ({}) from {}",
+        files.len(),
+        describe_size(total),
+        dir.display()
+    );
+    Ok(())
+}
+
+/// Runs the `cache` command, using `ttl` to tell whether the sinner data is
+/// outdated
+pub fn run(Cache { action }: Cache, ttl: CacheTtl) -> eyre::Result<()> {
+    match action.unwrap_or_default() {
+        | CacheAction::Status => status(ttl),
+        | CacheAction::Clear => clear(),
+        | CacheAction::Path => {
+            println!("{}", settings::cache_dir().display());
+            Ok(())
+        },
+    }
+}
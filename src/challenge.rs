@@ -0,0 +1,257 @@
+//! Challenge codes, short codes for a game's target and settings, so that
+//! everyone who plays a code plays the same puzzle
+//!
+//! A code is a string of bits written in Crockford's base32, in groups of 4
+//! characters separated by dashes. The bits are, in order:
+//! - the version of the format, in 2 bits
+//! - whether guesses that were ruled out are refused, in 1 bit
+//! - whether the pool is narrowed down, in 1 bit
+//! - the number of guesses allowed, or 0 for no limit, in 8 bits
+//! - a hash of the target's name, in 24 bits
+//! - if the pool is narrowed down, its alignment, tendency and birthplace as
+//!   the index of the variant plus one, or 0 for any, in 5, 3 and 2 bits, then
+//!   the lowest and highest height in 8 bits each, or 0 for no bound
+//! - a checksum of the bits before it, in 4 bits, to catch typos
+//!
+//! The bits are padded with zeroes to a whole number of characters, so a code
+//! without a pool has 8 characters and one with a pool has 14.
+
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+
+use crate::data::{variant_names, Alignment, BirthPlace, Sinner, Tendency};
+use crate::query::{HeightRange, Query};
+
+/// The digits of Crockford's base32, which leaves out I, L, O and U so that
+/// codes are easy to read out
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// The version of the format codes are made in
+const VERSION: u128 = 1;
+
+/// The number of characters between dashes
+const GROUP: usize = 4;
+
+/// A game that can be shared as a code
+#[derive(Debug, Clone, Copy)]
+pub struct Challenge {
+    /// A hash of the target's name, which is all the code has room for
+    target: u32,
+    max_guesses: Option<u8>,
+    strict: bool,
+    pool: Option<Query>,
+}
+
+/// A 24-bit FNV-1a hash of `name`
+fn name_hash(name: &str) -> u32 {
+    let hash = name.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+    hash & 0xff_ffff
+}
+
+/// The checksum of the first `len` bits of `bits`
+fn checksum(bits: u128, len: u32) -> u128 {
+    let hash = bits
+        .to_le_bytes()
+        .into_iter()
+        .chain(len.to_le_bytes())
+        .fold(0x811c_9dc5_u32, |hash, byte| {
+            (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+        });
+    u128::from(hash & 0xf)
+}
+
+/// The index of `value` in its enum plus one, or 0 for `None`
+fn variant_number<T: Debug>(value: Option<T>, names: &[&str]) -> u128 {
+    value
+        .and_then(|x| names.iter().position(|name| *name == format!("{x:?}")))
+        .map_or(0, |x| x as u128 + 1)
+}
+
+/// The variant numbered `number` by [`variant_number`]
+fn variant<T: FromStr>(number: u128, names: &[&str]) -> Result<Option<T>, ()> {
+    if number == 0 {
+        return Ok(None);
+    }
+    let index = usize::try_from(number - 1).map_err(|_| ())?;
+    let name = names.get(index).ok_or(())?;
+    name.parse().map(Some).map_err(|_| ())
+}
+
+/// Writes numbers into the bits of a code, most significant first
+#[derive(Debug, Default)]
+struct Writer {
+    bits: u128,
+    len: u32,
+}
+
+impl Writer {
+    fn write(&mut self, value: u128, width: u32) {
+        self.bits = self.bits << width | value & ((1 << width) - 1);
+        self.len += width;
+    }
+}
+
+/// Reads the numbers written by a [`Writer`] back out
+#[derive(Debug)]
+struct Reader {
+    bits: u128,
+    /// The number of bits not read yet
+    left: u32,
+}
+
+impl Reader {
+    fn read(&mut self, width: u32) -> Result<u128, ()> {
+        self.left = self.left.checked_sub(width).ok_or(())?;
+        Ok(self.bits >> self.left & ((1 << width) - 1))
+    }
+}
+
+impl Challenge {
+    pub fn new(
+        target: &Sinner,
+        max_guesses: Option<u8>,
+        strict: bool,
+        pool: Option<Query>,
+    ) -> Self {
+        Self {
+            target: name_hash(&target.name),
+            max_guesses,
+            strict,
+            pool,
+        }
+    }
+
+    /// The target of the challenge among `sinners`, or `None` if it isn't one
+    /// of them, such as when the code was made with other sinner data
+    pub fn target<'sinners>(&self, sinners: &'sinners [Sinner]) -> Option<&'sinners Sinner> {
+        sinners.iter().find(|x| name_hash(&x.name) == self.target)
+    }
+
+    /// The number of guesses allowed, or `None` for no limit
+    pub const fn max_guesses(&self) -> Option<u8> { self.max_guesses }
+
+    /// Whether guesses that were ruled out are refused
+    pub const fn strict(&self) -> bool { self.strict }
+
+    /// The query the sinners that can be guessed have to match, if the pool
+    /// is narrowed down
+    pub const fn pool(&self) -> Option<Query> { self.pool }
+}
+
+impl Display for Challenge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut writer = Writer::default();
+        writer.write(VERSION, 2);
+        writer.write(self.strict.into(), 1);
+        writer.write(self.pool.is_some().into(), 1);
+        writer.write(self.max_guesses.unwrap_or(0).into(), 8);
+        writer.write(self.target.into(), 24);
+        if let Some(pool) = &self.pool {
+            let height = pool.height.unwrap_or_default();
+            writer.write(
+                variant_number(pool.alignment, &variant_names::<Alignment>()),
+                5,
+            );
+            writer.write(
+                variant_number(pool.tendency, &variant_names::<Tendency>()),
+                3,
+            );
+            writer.write(
+                variant_number(pool.birthplace, &variant_names::<BirthPlace>()),
+                2,
+            );
+            writer.write(height.min.unwrap_or(0).into(), 8);
+            writer.write(height.max.unwrap_or(0).into(), 8);
+        }
+        writer.write(checksum(writer.bits, writer.len), 4);
+        let padding = writer.len.next_multiple_of(5) - writer.len;
+        writer.write(0, padding);
+        let digits = writer.len / 5;
+        for (index, digit) in (0..digits).rev().enumerate() {
+            if index > 0 && index.is_multiple_of(GROUP) {
+                f.write_str("-")?;
+            }
+            let value = usize::try_from(writer.bits >> (digit * 5) & 31).unwrap_or_default();
+            write!(f, "{}", char::from(ALPHABET[value]))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidChallengeError(String);
+
+impl FromStr for Challenge {
+    type Err = InvalidChallengeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || InvalidChallengeError(s.to_owned());
+        let mut bits = 0u128;
+        let mut len = 0u32;
+        for c in s.chars().filter(|x| *x != '-' && !x.is_whitespace()) {
+            // Letters that look like digits are read as those digits
+            let c = match c.to_ascii_uppercase() {
+                | 'O' => '0',
+                | 'I' | 'L' => '1',
+                | c => c,
+            };
+            let value = ALPHABET
+                .iter()
+                .position(|x| char::from(*x) == c)
+                .ok_or_else(error)?;
+            if len + 5 > u128::BITS {
+                return Err(error());
+            }
+            bits = bits << 5 | value as u128;
+            len += 5;
+        }
+        let mut reader = Reader { bits, left: len };
+        let mut parse = || -> Result<Self, ()> {
+            if reader.read(2)? != VERSION {
+                return Err(());
+            }
+            let strict = reader.read(1)? == 1;
+            let pooled = reader.read(1)? == 1;
+            let max_guesses = u8::try_from(reader.read(8)?).map_err(|_| ())?;
+            let target = u32::try_from(reader.read(24)?).map_err(|_| ())?;
+            let pool = if pooled {
+                let alignment = variant(reader.read(5)?, &variant_names::<Alignment>())?;
+                let tendency = variant(reader.read(3)?, &variant_names::<Tendency>())?;
+                let birthplace = variant(reader.read(2)?, &variant_names::<BirthPlace>())?;
+                let bound = |x: u128| u8::try_from(x).ok().filter(|&x| x > 0);
+                let (min, max) = (bound(reader.read(8)?), bound(reader.read(8)?));
+                Some(Query {
+                    alignment,
+                    tendency,
+                    birthplace,
+                    height: (min.is_some() || max.is_some()).then_some(HeightRange { min, max }),
+                    ..Query::default()
+                })
+            } else {
+                None
+            };
+            let (content, read) = (bits >> reader.left, len - reader.left);
+            // Whatever is left after the checksum is padding
+            let sum = reader.read(4)?;
+            if sum != checksum(content, read) || reader.left >= 5 || reader.read(reader.left)? != 0
+            {
+                return Err(());
+            }
+            Ok(Self {
+                target,
+                max_guesses: (max_guesses > 0).then_some(max_guesses),
+                strict,
+                pool,
+            })
+        };
+        parse().map_err(|()| error())
+    }
+}
+impl Display for InvalidChallengeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Invalid challenge code: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected a code printed at the end of a game, like `1A2B-3C4D`")
+    }
+}
@@ -0,0 +1,234 @@
+//! Bar charts, drawn with unicode blocks in the terminal, such as the
+//! distribution of guesses in `gather` and `stats`, or written to SVG files
+
+use std::fmt::Write;
+use std::path::Path;
+
+use ptndle_core::error::Error;
+use ptndle_core::play::GatherResults;
+use unicode_width::UnicodeWidthStr;
+
+/// The blocks of a bar's last character, by how many eighths of it are filled
+const EIGHTHS: [&str; 8] = ["", "▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+
+/// The bounds of the width of the longest bar, however wide the terminal is
+const MIN_WIDTH: usize = 10;
+const MAX_WIDTH: usize = 60;
+
+/// A bar of a chart, labelled on the left and followed by `note`, such as the
+/// count the bar stands for
+#[derive(Debug)]
+pub struct Bar {
+    pub label: String,
+    pub value: usize,
+    pub note: String,
+}
+
+/// The width of the terminal, or 80 if it isn't a terminal
+pub fn terminal_width() -> usize {
+    ratatui::crossterm::terminal::size()
+        .ok()
+        .filter(|(columns, _)| *columns > 0)
+        .map_or(80, |(columns, _)| usize::from(columns))
+}
+
+/// A bar `eighths` eighths of a character long
+fn bar(eighths: usize) -> String {
+    let mut bar = "█".repeat(eighths / 8);
+    bar.push_str(EIGHTHS[eighths % 8]);
+    bar
+}
+
+/// Prints `bars` as a horizontal bar chart indented by 4, with the longest
+/// bar as wide as fits in the terminal next to the labels and notes
+pub fn print_bars(bars: &[Bar]) {
+    let label_width = bars
+        .iter()
+        .map(|x| x.label.width())
+        .max()
+        .unwrap_or_default();
+    let note_width = bars
+        .iter()
+        .map(|x| x.note.width())
+        .max()
+        .unwrap_or_default();
+    let width = terminal_width()
+        .saturating_sub(4 + label_width + 3 + 1 + note_width)
+        .clamp(MIN_WIDTH, MAX_WIDTH);
+    let most = bars
+        .iter()
+        .map(|x| x.value)
+        .max()
+        .unwrap_or_default()
+        .max(1);
+    for Bar { label, value, note } in bars {
+        // Any value above 0 gets at least an eighth, so it can be told apart
+        let eighths = (value * width * 8).div_ceil(most);
+        let mut line = bar(eighths);
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(note);
+        let padding = " ".repeat(label_width - label.width());
+        println!("    {padding}{label} │ {line}");
+    }
+}
+
+/// The colors of each strategy's bars in an SVG chart, in order
+const PALETTE: [&str; 6] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#b07aa1",
+];
+
+/// The width of an SVG chart, and the space around its plots
+const SVG_WIDTH: f64 = 800.;
+const SVG_MARGIN: f64 = 60.;
+
+/// The height of the plot of the distribution, and of each strategy's row in
+/// the comparison under it
+const PLOT_HEIGHT: f64 = 260.;
+const ROW_HEIGHT: f64 = 28.;
+
+/// The width of the strategies' names in the comparison
+const NAME_WIDTH: f64 = 120.;
+
+/// Escapes `text` to be put in an SVG file
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Writes an SVG chart of `results` to `path`, with the share of sinners that
+/// take each number of guesses for each strategy side by side, and the mean
+/// and maximum number of guesses of each strategy under it
+#[expect(clippy::float_arithmetic, reason = "drawing")]
+#[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
+pub fn write_svg(path: &Path, results: &[GatherResults<'_>]) -> eyre::Result<()> {
+    let max_rounds = results
+        .iter()
+        .map(GatherResults::max_guesses)
+        .max()
+        .unwrap_or_default();
+    let percent = |result: &GatherResults<'_>, rounds: u8| {
+        result.count(rounds) as f64 * 100. / result.games.len().max(1) as f64
+    };
+    let highest = results
+        .iter()
+        .flat_map(|result| (1..=max_rounds).map(|rounds| percent(result, rounds)))
+        .fold(0., f64::max);
+    // The scale goes up in steps of 10% or 20% to just above the highest bar
+    let step = if highest > 50. { 20. } else { 10. };
+    let top = (highest / step).ceil().max(1.) * step;
+
+    let plot_top = 70.;
+    let plot_bottom = plot_top + PLOT_HEIGHT;
+    let plot_width = SVG_WIDTH - SVG_MARGIN * 2.;
+    let comparison_top = plot_bottom + 100.;
+    let height = comparison_top + 30. + ROW_HEIGHT * results.len() as f64 + 20.;
+    let mut svg = String::new();
+    _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{SVG_WIDTH}" height="{height}" font-family="sans-serif" font-size="13">"#
+    );
+    _ = writeln!(
+        svg,
+        r#"<rect width="100%" height="100%" fill="white"/>
+<text x="{}" y="30" font-size="18" text-anchor="middle">Path to Nowordle solver statistics</text>
+<text x="{SVG_MARGIN}" y="55">Sinners that take each number of guesses (%)</text>"#,
+        SVG_WIDTH / 2.
+    );
+
+    let mut line = 0.;
+    while line <= top {
+        let y = plot_bottom - line / top * PLOT_HEIGHT;
+        _ = writeln!(
+            svg,
+            r##"<line x1="{SVG_MARGIN}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="#ddd"/>
+<text x="{:.1}" y="{:.1}" text-anchor="end">{line}</text>"##,
+            SVG_MARGIN + plot_width,
+            SVG_MARGIN - 8.,
+            y + 4.
+        );
+        line += step;
+    }
+    let group = plot_width / f64::from(max_rounds.max(1));
+    let bar = group * 0.8 / results.len().max(1) as f64;
+    for rounds in 1..=max_rounds {
+        let left = SVG_MARGIN + group * f64::from(rounds - 1) + group * 0.1;
+        for (index, result) in results.iter().enumerate() {
+            let bar_height = percent(result, rounds) / top * PLOT_HEIGHT;
+            _ = writeln!(
+                svg,
+                r#"<rect x="{:.1}" y="{:.1}" width="{bar:.1}" height="{bar_height:.1}" fill="{}"/>"#,
+                left + bar * index as f64,
+                plot_bottom - bar_height,
+                PALETTE[index % PALETTE.len()]
+            );
+        }
+        _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{rounds}</text>"#,
+            left + group * 0.4,
+            plot_bottom + 18.
+        );
+    }
+    _ = writeln!(
+        svg,
+        r#"<line x1="{SVG_MARGIN}" y1="{plot_bottom}" x2="{:.1}" y2="{plot_bottom}" stroke="black"/>
+<text x="{:.1}" y="{:.1}" text-anchor="middle">Guesses</text>"#,
+        SVG_MARGIN + plot_width,
+        SVG_WIDTH / 2.,
+        plot_bottom + 38.
+    );
+    let mut legend = SVG_MARGIN;
+    for (index, result) in results.iter().enumerate() {
+        _ = writeln!(
+            svg,
+            r#"<rect x="{legend:.1}" y="{:.1}" width="12" height="12" fill="{}"/>
+<text x="{:.1}" y="{:.1}">{}</text>"#,
+            plot_bottom + 52.,
+            PALETTE[index % PALETTE.len()],
+            legend + 18.,
+            plot_bottom + 63.,
+            escape_xml(&result.name)
+        );
+        legend += 40. + result.name.width() as f64 * 8.;
+    }
+
+    draw_comparison(&mut svg, results, max_rounds, comparison_top);
+    svg.push_str("</svg>\n");
+    std::fs::write(path, svg)
+        .map_err(|e| Error::Io(format!("Failed to write {}: {e}", path.display())).into())
+}
+
+/// Draws a bar for each strategy in `results` in `svg`, from `top` down, as
+/// long as its mean number of guesses out of `max_rounds`
+#[expect(clippy::float_arithmetic, reason = "drawing")]
+#[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
+fn draw_comparison(svg: &mut String, results: &[GatherResults<'_>], max_rounds: u8, top: f64) {
+    _ = writeln!(
+        svg,
+        r#"<text x="{SVG_MARGIN}" y="{top}">Mean and maximum number of guesses</text>"#
+    );
+    let bars_left = SVG_MARGIN + NAME_WIDTH;
+    let bars_width = SVG_WIDTH - SVG_MARGIN * 2. - NAME_WIDTH - 100.;
+    for (index, result) in results.iter().enumerate() {
+        let y = top + 20. + ROW_HEIGHT * index as f64;
+        let width = result.mean() / f64::from(max_rounds.max(1)) * bars_width;
+        _ = writeln!(
+            svg,
+            r#"<text x="{SVG_MARGIN}" y="{:.1}">{}</text>
+<rect x="{bars_left}" y="{y:.1}" width="{width:.1}" height="{:.1}" fill="{}"/>
+<text x="{:.1}" y="{:.1}">{:.2} mean, {} max</text>"#,
+            y + 14.,
+            escape_xml(&result.name),
+            ROW_HEIGHT - 8.,
+            PALETTE[index % PALETTE.len()],
+            bars_left + width + 8.,
+            y + 14.,
+            result.mean(),
+            result.max_guesses()
+        );
+    }
+}
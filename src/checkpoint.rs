@@ -0,0 +1,165 @@
+//! Checkpoints of `gather`, which keep the game against each target as soon
+//! as it ends, so that a long run that was interrupted can pick up where it
+//! left off with `gather --resume`
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use facet::Facet;
+
+use crate::compare::ThresholdConfig;
+use crate::context::Context;
+use crate::data::{data_hash, make_and_get_cache_dir};
+use crate::matrix::GuessMatrix;
+use crate::solver::{SolverConfig, TieBreak};
+use crate::{status, verbose};
+
+/// The first line of a checkpoint, with what its games were played with
+#[derive(Debug, Facet)]
+struct CheckpointHeader {
+    version: String,
+    /// The [`data_hash`] of the sinners, mixed with their weights
+    data_hash: String,
+    thresholds: ThresholdConfig,
+    tie_break: TieBreak,
+}
+
+/// A game in a checkpoint, on a line of its own after the header
+#[derive(Debug, Clone, Facet)]
+pub struct CheckpointGame {
+    pub target: String,
+    /// The number of guesses it took, or `None` if the player gave up
+    pub guesses: Option<u8>,
+    /// The sinners guessed, in order, including the target
+    pub sequence: Vec<String>,
+    /// The progress of the game as it was written when it was played
+    pub log: String,
+}
+
+/// The games played so far by one solver in `gather`
+#[derive(Debug)]
+pub struct Checkpoint {
+    path: PathBuf,
+    /// The games from before the run was interrupted, by target
+    done: HashMap<String, CheckpointGame>,
+    /// The file games are appended to as they end
+    file: Mutex<File>,
+}
+
+/// The hash the header of a checkpoint for the sinners in `matrix` has
+fn checkpoint_hash(matrix: &GuessMatrix) -> String {
+    // FNV-1a, continued from the hash of the sinners
+    let hash = matrix
+        .weights()
+        .unwrap_or_default()
+        .iter()
+        .flat_map(|x| x.to_bits().to_le_bytes())
+        .fold(data_hash(matrix.sinners()), |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{hash:016x}")
+}
+
+impl Checkpoint {
+    /// Opens the checkpoint of gathering with the solvers described by
+    /// `config` on the sinners in `matrix`. With `resume`, the games in the
+    /// checkpoint are kept if they were played with the same sinners, weights,
+    /// thresholds and [`TieBreak`]. Otherwise the checkpoint starts empty.
+    pub fn open(
+        context: &Context,
+        config: SolverConfig,
+        matrix: &GuessMatrix,
+        resume: bool,
+    ) -> eyre::Result<Self> {
+        let path = make_and_get_cache_dir(context.game)?.join(format!(
+            "gather-{}-{}{}.jsonl",
+            config.strategy,
+            config.depth.max(1),
+            if config.probe { "-probe" } else { "" }
+        ));
+        let header = CheckpointHeader {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            data_hash: checkpoint_hash(matrix),
+            thresholds: *matrix.thresholds(),
+            tie_break: config.tie_break,
+        };
+        let mut done = HashMap::new();
+        if resume {
+            let text = std::fs::read_to_string(&path).unwrap_or_default();
+            let mut lines = text.lines();
+            let matches = lines
+                .next()
+                .and_then(|x| facet_json::from_str::<CheckpointHeader>(x).ok())
+                .is_some_and(|x| {
+                    x.version == header.version &&
+                        x.data_hash == header.data_hash &&
+                        x.thresholds == header.thresholds &&
+                        x.tie_break == header.tie_break
+                });
+            if matches {
+                // A line cut off by the interruption ends the games
+                done = lines
+                    .map_while(|x| facet_json::from_str::<CheckpointGame>(x).ok())
+                    .map(|x| (x.target.clone(), x))
+                    .collect();
+                status!(
+                    context,
+                    "Resuming {} with {} of {} games already played",
+                    config.strategy,
+                    done.len(),
+                    matrix.len()
+                );
+            } else if !text.is_empty() {
+                status!(
+                    context,
+                    "The checkpoint of {} was made with other sinner data or settings, so every \
+                     game is played again",
+                    config.strategy
+                );
+            }
+        }
+        // The file is written again so that games are appended after the last
+        // whole line
+        let mut file = File::create(&path)?;
+        writeln!(file, "{}", facet_json::to_string(&header))?;
+        for game in done.values() {
+            writeln!(file, "{}", facet_json::to_string(game))?;
+        }
+        verbose!(
+            context,
+            "Saving the games of {} to {}",
+            config.strategy,
+            path.display()
+        );
+        Ok(Self {
+            path,
+            done,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// The game against `target` from before the run was interrupted, if it
+    /// was played
+    pub fn get(&self, target: &str) -> Option<&CheckpointGame> { self.done.get(target) }
+
+    /// Saves `game`, which just ended
+    pub fn record(&self, game: &CheckpointGame) -> std::io::Result<()> {
+        // Written at once, so that an interruption cuts off at most this line
+        let line = format!("{}\n", facet_json::to_string(game));
+        let mut file = self
+            .file
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        file.write_all(line.as_bytes())
+    }
+
+    /// Removes the checkpoint once every game has been played
+    pub fn remove(self) -> eyre::Result<()> {
+        drop(self.file);
+        std::fs::remove_file(&self.path)?;
+        Ok(())
+    }
+}
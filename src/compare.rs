@@ -1,7 +1,13 @@
-use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::path::Path;
 
+use eyre::Context;
 use facet::Facet;
-use owo_colors::OwoColorize;
+use owo_colors::Styled;
+
+use crate::data::MOST_COMMON_HEIGHT;
+use crate::error::Error;
+use crate::theme::{Role, Theme};
 
 /// A comparison result of comparing 2 numerical values
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Facet)]
@@ -16,6 +22,21 @@ pub enum Comparison {
 }
 
 impl Comparison {
+    pub const ALL: [Self; 6] = [
+        Self::Correct,
+        Self::FarLess,
+        Self::Less,
+        Self::Near,
+        Self::Greater,
+        Self::FarGreater,
+    ];
+
+    /// The comparison whose discriminant is `bits`, as it's packed in a
+    /// [`Guess`](crate::guess::Guess)
+    pub fn from_bits(bits: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|x| *x as u16 == bits)
+    }
+
     pub const fn to_str(self) -> &'static str {
         match self {
             | Self::Correct => " =",
@@ -26,38 +47,81 @@ impl Comparison {
             | Self::FarGreater => "↑↑",
         }
     }
-}
+    /// The comparison as it is displayed, in the colors of `theme`
+    pub fn paint(self, theme: &Theme) -> Styled<&'static str> {
+        theme.paint(self.to_str(), self.role())
+    }
 
-impl Display for Comparison {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    /// How the comparison is styled
+    pub const fn role(self) -> Role {
+        match self {
+            | Self::Correct => Role::Correct,
+            | Self::Near => Role::Near,
+            | _ => Role::Wrong,
+        }
+    }
+    /// The comparison as an emoji for sharing results
+    pub const fn to_emoji(self) -> &'static str {
+        match self {
+            | Self::Correct => "🟩",
+            | Self::FarLess => "⏬",
+            | Self::Less => "🔽",
+            | Self::Near => "🟨",
+            | Self::Greater => "🔼",
+            | Self::FarGreater => "⏫",
+        }
+    }
+    /// Reads a comparison from the emoji it is shared as
+    pub const fn from_emoji(emoji: char) -> Option<Self> {
+        Some(match emoji {
+            | '🟩' => Self::Correct,
+            | '⏬' => Self::FarLess,
+            | '🔽' => Self::Less,
+            | '🟨' => Self::Near,
+            | '🔼' => Self::Greater,
+            | '⏫' => Self::FarGreater,
+            | _ => return None,
+        })
+    }
+    /// The comparison as it is entered when solving, such as `^^`
+    pub const fn to_ascii(self) -> &'static str {
         match self {
-            | Self::Correct => write!(f, "{}", self.to_str().green()),
-            | Self::Near => write!(f, "{}", self.to_str().yellow()),
-            | _ => write!(f, "{}", self.to_str().red()),
+            | Self::Correct => "=",
+            | Self::FarLess => "vv",
+            | Self::Less => "v",
+            | Self::Near => "~",
+            | Self::Greater => "^",
+            | Self::FarGreater => "^^",
         }
     }
 }
-#[derive(Copy, Clone, Debug, PartialEq, Facet)]
+
+/// How far a guess can be from the target and still be near, and how far it
+/// has to be to be far. Both are in hundredths of the value compared, so that
+/// comparisons are exact.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Facet)]
 pub struct Threshold {
-    pub near: f32,
-    pub far: f32,
+    pub near: u32,
+    pub far: u32,
 }
 
 impl Threshold {
-    #[expect(clippy::float_arithmetic, clippy::float_cmp, reason = "TODO: remove all float arithmetic")]
-    pub fn compare(self, target: f32, guess: f32) -> Comparison {
-        let distance = target - guess;
+    /// Compares `guess` with `target`, giving an arrow pointing from the guess
+    /// towards the target
+    pub fn compare(self, target: u16, guess: u16) -> Comparison {
+        let distance = (i64::from(target) - i64::from(guess)) * 100;
+        let [near, far] = [self.near, self.far].map(i64::from);
         if target == guess {
             return Comparison::Correct;
         }
-        if distance > self.near {
-            if distance > self.far {
+        if distance > near {
+            if distance > far {
                 Comparison::FarGreater
             } else {
                 Comparison::Greater
             }
-        } else if distance < -self.near {
-            if distance < -self.far {
+        } else if distance < -near {
+            if distance < -far {
                 Comparison::FarLess
             } else {
                 Comparison::Less
@@ -66,9 +130,337 @@ impl Threshold {
             Comparison::Near
         }
     }
+
+    /// The guesses that give `comparison` against `target`, as the bounds of
+    /// up to two ranges that include both ends, which may reach past the
+    /// values a `u16` can have or be empty
+    fn bounds(self, target: u16, comparison: Comparison) -> [(i64, i64); 2] {
+        // The furthest a guess can be, in whole units, and still be within
+        // each threshold. A far threshold below the near one has no effect,
+        // as the near one is checked first.
+        let near = i64::from(self.near / 100);
+        let far = i64::from(self.far.max(self.near) / 100);
+        let target = i64::from(target);
+        let (lowest, highest) = (0, i64::from(u16::MAX));
+        let none = (1, 0);
+        match comparison {
+            | Comparison::Correct => [(target, target), none],
+            | Comparison::Near => [(target - near, target - 1), (target + 1, target + near)],
+            | Comparison::Greater => [(target - far, target - near - 1), none],
+            | Comparison::FarGreater => [(lowest, target - far - 1), none],
+            | Comparison::Less => [(target + near + 1, target + far), none],
+            | Comparison::FarLess => [(target + far + 1, highest), none],
+        }
+    }
+
+    /// The guesses that give `comparison` against `target`, as ranges that
+    /// include both ends. This is the inverse of [`Threshold::compare`]: a
+    /// guess is in one of the ranges exactly when comparing it with `target`
+    /// gives `comparison`. Only ≅ can give two ranges, one on each side of the
+    /// target.
+    pub fn inverse(self, target: u16, comparison: Comparison) -> Vec<RangeInclusive<u16>> {
+        self.bounds(target, comparison)
+            .into_iter()
+            .filter_map(|(min, max)| {
+                let min = u16::try_from(min.max(0)).ok()?;
+                let max = u16::try_from(max.min(i64::from(u16::MAX))).ok()?;
+                (min <= max).then_some(min..=max)
+            })
+            .collect()
+    }
+
+    /// Whether comparing `guess` with `target` gives `comparison`, worked out
+    /// from the same ranges as [`Threshold::inverse`]
+    pub fn matches(self, target: u16, guess: u16, comparison: Comparison) -> bool {
+        let guess = i64::from(guess);
+        self.bounds(target, comparison)
+            .iter()
+            .any(|&(min, max)| min <= guess && guess <= max)
+    }
 }
-#[derive(Clone, Debug, PartialEq, Facet)]
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
 pub struct Thresholds {
     pub code: Option<Threshold>,
     pub height: Threshold,
 }
+
+/// How a threshold grows with the value it's for, as `base + per_unit × value`.
+/// Both are in hundredths, like [`Threshold`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Facet)]
+pub struct Growth {
+    pub base: u32,
+    pub per_unit: u32,
+}
+
+impl Growth {
+    /// The threshold for `value`
+    pub fn at(self, value: u32) -> u32 {
+        let threshold = u64::from(self.base) + u64::from(self.per_unit) * u64::from(value);
+        u32::try_from(threshold).unwrap_or(u32::MAX)
+    }
+}
+
+/// The formulas for the near and far thresholds of codes and heights. Height
+/// thresholds grow with how far the target's height is from
+/// `most_common_height`, and code thresholds with the target's code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Facet)]
+pub struct ThresholdConfig {
+    pub code_near: Growth,
+    pub code_far: Growth,
+    pub height_near: Growth,
+    pub height_far: Growth,
+    pub most_common_height: u8,
+}
+
+impl Default for ThresholdConfig {
+    /// The formulas ptndle.com uses
+    fn default() -> Self {
+        Self {
+            code_near: Growth {
+                base: 500,
+                per_unit: 10,
+            },
+            code_far: Growth {
+                base: 5000,
+                per_unit: 35,
+            },
+            height_near: Growth {
+                base: 300,
+                per_unit: 10,
+            },
+            height_far: Growth {
+                base: 1500,
+                per_unit: 35,
+            },
+            most_common_height: MOST_COMMON_HEIGHT,
+        }
+    }
+}
+
+/// A threshold formula as written in a thresholds file, in whole units
+#[derive(Facet)]
+struct GrowthFile {
+    base: f64,
+    per_unit: f64,
+}
+
+/// A thresholds file, or the thresholds of a game's profile. Formulas that are
+/// left out keep their default.
+#[derive(Facet)]
+pub(crate) struct ThresholdFile {
+    #[facet(default)]
+    code_near: Option<GrowthFile>,
+    #[facet(default)]
+    code_far: Option<GrowthFile>,
+    #[facet(default)]
+    height_near: Option<GrowthFile>,
+    #[facet(default)]
+    height_far: Option<GrowthFile>,
+    #[facet(default)]
+    most_common_height: Option<u8>,
+}
+
+/// Converts `value` to hundredths, failing if it isn't a whole number of
+/// hundredths
+#[expect(
+    clippy::float_arithmetic,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    reason = "the value is checked to be a small whole number before the cast"
+)]
+fn hundredths(value: f64, field: &str) -> eyre::Result<u32> {
+    let scaled = (value * 100.).round();
+    if !(0. ..=1_000_000.).contains(&scaled) || (scaled - value * 100.).abs() > 1e-6 {
+        return Err(Error::InvalidInput(format!(
+            "`{field}` is {value}, expected a number from 0 to 10000 with at most 2 decimals"
+        ))
+        .into());
+    }
+    Ok(scaled as u32)
+}
+
+impl GrowthFile {
+    fn parse(&self, field: &str) -> eyre::Result<Growth> {
+        Ok(Growth {
+            base: hundredths(self.base, &format!("{field}.base"))?,
+            per_unit: hundredths(self.per_unit, &format!("{field}.per_unit"))?,
+        })
+    }
+}
+
+impl ThresholdConfig {
+    /// Reads a thresholds file, a JSON object such as
+    /// `{"code_near": {"base": 5, "per_unit": 0.1}}`
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| Error::Io(format!("Failed to read {}: {e}", path.display())))?;
+        let file = facet_json::from_str::<ThresholdFile>(&json)
+            .map_err(|e| Error::InvalidInput(format!("Failed to parse {}: {e}", path.display())))?;
+        Self::from_file(&file).with_context(|| format!("Invalid thresholds in {}", path.display()))
+    }
+
+    /// The formulas in `file`, with the defaults for those left out
+    pub(crate) fn from_file(file: &ThresholdFile) -> eyre::Result<Self> {
+        let mut config = Self::default();
+        for (field, growth, target) in [
+            ("code_near", &file.code_near, &mut config.code_near),
+            ("code_far", &file.code_far, &mut config.code_far),
+            ("height_near", &file.height_near, &mut config.height_near),
+            ("height_far", &file.height_far, &mut config.height_far),
+        ] {
+            if let Some(growth) = growth {
+                *target = growth.parse(field)?;
+            }
+        }
+        if let Some(height) = file.most_common_height {
+            config.most_common_height = height;
+        }
+        Ok(config)
+    }
+
+    /// The threshold for codes when `code` is the target's code
+    pub fn code(&self, code: u16) -> Threshold {
+        Threshold {
+            near: self.code_near.at(code.into()),
+            far: self.code_far.at(code.into()),
+        }
+    }
+
+    /// The threshold for heights when `height` is the target's height
+    pub fn height(&self, height: u8) -> Threshold {
+        let offset = u32::from(height.abs_diff(self.most_common_height));
+        Threshold {
+            near: self.height_near.at(offset),
+            far: self.height_far.at(offset),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Comparison, Threshold, ThresholdConfig};
+    use crate::data::MOST_COMMON_HEIGHT;
+
+    /// The comparison with thresholds of `near_base + 0.1 × offset` and
+    /// `far_base + 0.35 × offset` worked out in `f32`, as this program did
+    /// before it switched to hundredths
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "the float formula is what's checked against"
+    )]
+    fn float_compare(
+        target: u16,
+        guess: u16,
+        [near_base, far_base]: [f32; 2],
+        offset: u16,
+    ) -> Comparison {
+        let near = near_base + f32::from(offset) * 0.1;
+        let far = far_base + f32::from(offset) * 0.35;
+        let distance = f32::from(target) - f32::from(guess);
+        if target == guess {
+            Comparison::Correct
+        } else if distance > near {
+            if distance > far {
+                Comparison::FarGreater
+            } else {
+                Comparison::Greater
+            }
+        } else if distance < -near {
+            if distance < -far {
+                Comparison::FarLess
+            } else {
+                Comparison::Less
+            }
+        } else {
+            Comparison::Near
+        }
+    }
+
+    /// The guesses just inside, on and just outside the near and far
+    /// thresholds on either side of `target`
+    fn boundaries(target: u16, threshold: Threshold) -> impl Iterator<Item = u16> {
+        let target = i64::from(target);
+        [threshold.near, threshold.far]
+            .into_iter()
+            .flat_map(move |x| {
+                let units = i64::from(x / 100);
+                [-1, 0, 1]
+                    .into_iter()
+                    .flat_map(move |step| [target - units + step, target + units + step])
+            })
+            .filter_map(|x| u16::try_from(x).ok())
+    }
+
+    /// Checks `threshold` against the float formula at every boundary around
+    /// `target`. The two may only disagree when the guess is exactly a
+    /// threshold away, where the float threshold can round to just under the
+    /// distance; the exact comparison then has to keep the guess inside it.
+    fn check_boundaries(target: u16, threshold: Threshold, bases: [f32; 2], offset: u16) {
+        for guess in boundaries(target, threshold) {
+            let exact = threshold.compare(target, guess);
+            let float = float_compare(target, guess, bases, offset);
+            let distance = u32::from(target.abs_diff(guess)) * 100;
+            let on_threshold = distance == threshold.near || distance == threshold.far;
+            let inside = match float {
+                | Comparison::FarGreater => Comparison::Greater,
+                | Comparison::FarLess => Comparison::Less,
+                | Comparison::Greater | Comparison::Less => Comparison::Near,
+                | x => x,
+            };
+            assert!(
+                exact == float || on_threshold && exact == inside,
+                "guessing {guess} with {target} as the target gave {exact:?} instead of {float:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn codes_compare_like_floats_at_every_boundary() {
+        let config = ThresholdConfig::default();
+        for target in 0..=u16::MAX {
+            check_boundaries(target, config.code(target), [5., 50.], target);
+        }
+    }
+
+    #[test]
+    fn heights_compare_like_floats_at_every_boundary() {
+        let config = ThresholdConfig::default();
+        for target in 0..=u8::MAX {
+            let offset = u16::from(target.abs_diff(MOST_COMMON_HEIGHT));
+            check_boundaries(target.into(), config.height(target), [3., 15.], offset);
+        }
+    }
+
+    /// Checks that `matches` accepts exactly the comparison `compare` gives
+    /// for every target and guess up to `max`
+    fn check_matches(max: u16, threshold: impl Fn(u16) -> Threshold) {
+        for target in 0..=max {
+            let threshold = threshold(target);
+            for guess in 0..=max {
+                let comparison = threshold.compare(target, guess);
+                for x in Comparison::ALL {
+                    assert_eq!(
+                        threshold.matches(target, guess, x),
+                        x == comparison,
+                        "guessing {guess} with {target} as the target gives {comparison:?}, not \
+                         {x:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn code_matches_agree_with_compare() {
+        let config = ThresholdConfig::default();
+        check_matches(1023, |x| config.code(x));
+    }
+
+    #[test]
+    fn height_matches_agree_with_compare() {
+        let config = ThresholdConfig::default();
+        check_matches(u8::MAX.into(), |x| {
+            config.height(u8::try_from(x).unwrap_or(u8::MAX))
+        });
+    }
+}
@@ -0,0 +1,593 @@
+//! The `completions` command, which writes shell completion scripts made from
+//! the xflags definitions in `flags.rs`, so they can't drift from the flags
+//! the program actually takes
+
+use std::fmt::Write;
+
+use ptndle_core::data::{load_sinners, variant_names, Alignment, BirthPlace, DataSource, Tendency};
+use ptndle_core::error::Error;
+use ptndle_core::game::GameProfile;
+use ptndle_core::i18n::Lang;
+use ptndle_core::solver::{SolverConfig, Strategy, TieBreak};
+
+use crate::config::ConfigKey;
+use crate::console::ProgressMode;
+use crate::flags::{Completions, Format, HelpCommand, Shell};
+use crate::prompt::sinner_names;
+use crate::simulate::PlayerKind;
+use crate::tournament::RankKey;
+use crate::{script, settings};
+
+/// The source of the xflags definitions the scripts are made from
+const FLAGS_SOURCE: &str = include_str!("flags.rs");
+
+/// The command the scripts run to get the sinner names. `--offline` keeps
+/// completion from waiting on the network.
+const NAMES_COMMAND: &str = "ptndle-cli --offline completions --names";
+
+/// What the value of a flag or positional argument can be completed with
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Values {
+    /// Anything, so nothing is suggested
+    Any,
+    Files,
+    /// The names and aliases of the sinners, from [`NAMES_COMMAND`]
+    Sinners,
+    /// The names of the commands with in-depth help, which are only known
+    /// once every command is read
+    Commands,
+    Words(Vec<String>),
+}
+
+/// A flag or positional argument of a command
+#[derive(Debug)]
+struct Arg {
+    /// The long flag without the dashes, or `None` for a positional argument
+    long: Option<String>,
+    short: Option<char>,
+    /// What the value can be, or `None` for a flag that takes no value
+    values: Option<Values>,
+    doc: String,
+}
+
+/// A command and the flags and positional arguments it takes itself
+#[derive(Debug)]
+struct Command {
+    /// The words that select the command, like `["data", "snapshot"]`, which
+    /// are empty for `ptndle-cli` itself
+    path: Vec<String>,
+    doc: String,
+    args: Vec<Arg>,
+}
+
+impl Command {
+    fn path(&self) -> String { self.path.join(" ") }
+
+    /// Whether this command is `other` or one of its subcommands, which take
+    /// `other`'s flags too
+    fn is_under(&self, other: &Self) -> bool { self.path.starts_with(&other.path) }
+
+    /// The values of this command's positional arguments
+    fn positional(&self) -> impl Iterator<Item = &Values> {
+        self.args
+            .iter()
+            .filter(|x| x.long.is_none())
+            .filter_map(|x| x.values.as_ref())
+    }
+}
+
+/// The first sentence of a doc comment, which is what shells have room for
+fn summary(doc: &[&str]) -> String {
+    let doc = doc.join(" ");
+    let doc = doc.trim();
+    doc.split_once(". ")
+        .map_or(doc, |(first, _)| first)
+        .trim_end_matches('.')
+        .to_owned()
+}
+
+/// What the value of `binding: ty` in `command` can be completed with
+fn values_of(command: &[String], binding: &str, ty: &str) -> Values {
+    let words = |words: &[&str]| Values::Words(words.iter().map(|&x| x.to_owned()).collect());
+    let strategies = Strategy::ALL.map(Strategy::to_str);
+    match (ty, binding) {
+        | ("PathBuf", _) => Values::Files,
+        | ("String", "names") => Values::Sinners,
+        | ("String", "name") if command == ["info"] => Values::Sinners,
+        | ("Strategy", _) => words(&strategies),
+        | ("Strategies", _) => words(&[&strategies[..], &["all"]].concat()),
+        | ("StrategyName", _) => {
+            words(
+                &script::registry(SolverConfig::default())
+                    .names()
+                    .collect::<Vec<_>>(),
+            )
+        },
+        | ("Theme", _) => words(&["default", "colorblind", "mono", "plain"]),
+        | ("Lang", _) => words(&Lang::ALL.map(Lang::to_str)),
+        | ("Format", _) => words(&Format::ALL.map(Format::to_str)),
+        | ("ProgressMode", _) => words(&ProgressMode::ALL.map(ProgressMode::to_str)),
+        | ("GameName", _) => Values::Words(GameProfile::available()),
+        | ("Alignment", _) => words(&variant_names::<Alignment>()),
+        | ("Tendency", _) => words(&variant_names::<Tendency>()),
+        | ("BirthPlace", _) => words(&variant_names::<BirthPlace>()),
+        | ("SortKey", _) => {
+            words(&[
+                "name",
+                "code",
+                "alignment",
+                "tendency",
+                "height",
+                "birthplace",
+            ])
+        },
+        | ("Query", _) => {
+            // One condition at a time, since the shell doesn't know about the commas
+            let conditions = [
+                ("alignment", variant_names::<Alignment>()),
+                ("tendency", variant_names::<Tendency>()),
+                ("birthplace", variant_names::<BirthPlace>()),
+            ];
+            Values::Words(
+                conditions
+                    .into_iter()
+                    .flat_map(|(key, values)| values.into_iter().map(move |x| format!("{key}={x}")))
+                    .collect(),
+            )
+        },
+        | ("CacheAction", _) => words(&["status", "clear", "path"]),
+        | ("HelpCommand", _) => Values::Commands,
+        | ("Shell", _) => words(&Shell::ALL.map(Shell::to_str)),
+        | ("ConfigKey", _) => words(&ConfigKey::ALL.map(ConfigKey::to_str)),
+        | ("String", "players") | ("StrategyNames", _) => {
+            let registry = script::registry(SolverConfig::default());
+            words(&registry.names().chain(["all"]).collect::<Vec<_>>())
+        },
+        | ("TieBreak", _) => words(&TieBreak::ALL.map(TieBreak::to_str)),
+        | ("RankKey", _) => words(&RankKey::ALL.map(RankKey::to_str)),
+        | ("PlayerKinds", _) => {
+            words(&[&PlayerKind::ALL.map(PlayerKind::to_str)[..], &["all"]].concat())
+        },
+        | _ => Values::Any,
+    }
+}
+
+/// Parses the words after `optional`, `required` or `repeated`, such as
+/// `-d, --depth depth: u8` or `name: String`
+fn parse_arg(command: &[String], mut words: &[&str], doc: String) -> Arg {
+    let mut long = None;
+    let mut short = None;
+    while let [word, rest @ ..] = words {
+        if let Some(flag) = word.strip_prefix("--") {
+            long = Some(flag.to_owned());
+        } else if let Some(flag) = word.strip_prefix('-') {
+            short = flag.chars().next();
+        } else {
+            break;
+        }
+        words = rest;
+    }
+    let values = match words {
+        | [binding, ty] => Some(values_of(command, binding.trim_end_matches(':'), ty)),
+        | _ => None,
+    };
+    Arg {
+        long,
+        short,
+        values,
+        doc,
+    }
+}
+
+/// Reads every command out of the `xflags!` block in `flags.rs`, parents
+/// before their subcommands
+fn parse_commands() -> Vec<Command> {
+    let (_, block) = FLAGS_SOURCE
+        .split_once("xflags::xflags! {")
+        .expect("flags.rs should define the flags with xflags");
+    let mut commands = Vec::<Command>::new();
+    // The commands whose braces are still open, innermost last
+    let mut open = Vec::<usize>::new();
+    let mut doc = Vec::new();
+    for line in block.lines().map(str::trim) {
+        if let Some(text) = line.strip_prefix("///") {
+            doc.push(text.trim());
+            continue;
+        }
+        let words = line
+            .split_whitespace()
+            .map(|x| x.trim_end_matches(','))
+            .collect::<Vec<_>>();
+        let parent = open.last().map(|&x| commands[x].path.clone());
+        match (words.as_slice(), parent) {
+            | (["cmd", name, ..], parent) => {
+                let path = parent.map_or_else(Vec::new, |mut path| {
+                    path.push((*name).to_owned());
+                    path
+                });
+                commands.push(Command {
+                    path,
+                    doc: summary(&doc),
+                    args: Vec::new(),
+                });
+                if !line.ends_with("{}") {
+                    open.push(commands.len() - 1);
+                }
+            },
+            | (["optional" | "required" | "repeated", rest @ ..], Some(path)) => {
+                let arg = parse_arg(&path, rest, summary(&doc));
+                if let Some(&command) = open.last() {
+                    commands[command].args.push(arg);
+                }
+            },
+            | (["}"], _) => {
+                open.pop();
+                if open.is_empty() {
+                    break;
+                }
+            },
+            | _ => {},
+        }
+        doc.clear();
+    }
+    let with_help = commands
+        .iter()
+        .filter(|x| x.path.len() == 1 && x.path[0].parse::<HelpCommand>().is_ok())
+        .map(|x| x.path[0].clone())
+        .collect::<Vec<_>>();
+    for arg in commands.iter_mut().flat_map(|x| &mut x.args) {
+        if arg.values == Some(Values::Commands) {
+            arg.values = Some(Values::Words(with_help.clone()));
+        }
+    }
+    commands
+}
+
+/// The commands directly under `command`
+fn subcommands<'commands>(
+    commands: &'commands [Command],
+    command: &'commands Command,
+) -> impl Iterator<Item = &'commands Command> {
+    commands
+        .iter()
+        .filter(move |x| x.path.len() == command.path.len() + 1 && x.is_under(command))
+}
+
+/// The flags `command` takes, its own before its parents'
+fn flags_of<'commands>(
+    commands: &'commands [Command],
+    command: &'commands Command,
+) -> impl Iterator<Item = &'commands Arg> {
+    commands
+        .iter()
+        .rev()
+        .filter(move |x| command.is_under(x))
+        .flat_map(|x| &x.args)
+        .filter(|x| x.long.is_some())
+}
+
+/// The words to suggest for `command` when not completing a flag's value:
+/// its subcommands, flags and the fixed values of its positional arguments
+fn words_of(commands: &[Command], command: &Command) -> Vec<String> {
+    let subcommands = subcommands(commands, command).filter_map(|x| x.path.last().cloned());
+    let flags = flags_of(commands, command).filter_map(|x| Some(format!("--{}", x.long.as_ref()?)));
+    let positional = command.positional().flat_map(|x| {
+        match x {
+            | Values::Words(words) => words.clone(),
+            | _ => Vec::new(),
+        }
+    });
+    subcommands.chain(positional).chain(flags).collect()
+}
+
+/// Every spelling of `arg`'s flag, like `["--depth", "-d"]`
+fn spellings(arg: &Arg) -> Vec<String> {
+    arg.long
+        .iter()
+        .map(|x| format!("--{x}"))
+        .chain(arg.short.map(|x| format!("-{x}")))
+        .collect()
+}
+
+/// Each spelling of the flags that take a value with the command they belong
+/// to, deepest commands first so they take precedence in a `case`
+fn value_flags(commands: &[Command]) -> Vec<(&Command, String, &Values)> {
+    let mut flags = commands
+        .iter()
+        .flat_map(|command| {
+            command
+                .args
+                .iter()
+                .filter(|x| x.long.is_some())
+                .flat_map(move |arg| {
+                    let values = arg.values.as_ref();
+                    spellings(arg)
+                        .into_iter()
+                        .filter_map(move |spelling| Some((command, spelling, values?)))
+                })
+        })
+        .collect::<Vec<_>>();
+    flags.sort_by_key(|(command, ..)| std::cmp::Reverse(command.path.len()));
+    flags
+}
+
+/// Quotes `words` as one newline-separated bash word
+fn bash_words(words: &[String]) -> String {
+    format!("$'{}'", words.join("\\n").replace('\'', "\\'"))
+}
+
+/// The bash that completes the current word with `values` and `words`
+fn bash_values(values: &Values, words: &[String]) -> String {
+    let names = format!("\"$({NAMES_COMMAND} 2>/dev/null)\"");
+    let options = match values {
+        | Values::Any | Values::Commands => return "COMPREPLY=()".to_owned(),
+        | Values::Files if words.is_empty() => "-f".to_owned(),
+        | Values::Files => format!("-f -W {}", bash_words(words)),
+        | Values::Sinners if words.is_empty() => format!("-W {names}"),
+        | Values::Sinners => format!("-W {names}$'\\n'{}", bash_words(words)),
+        | Values::Words(values) => format!("-W {}", bash_words(&[words, values].concat())),
+    };
+    let complete = format!("COMPREPLY=($(compgen {options} -- \"$cur\"))");
+    if *values == Values::Sinners {
+        // Names like `Mr. Fox` have to stay one argument
+        format!("{complete}; COMPREPLY=(\"${{COMPREPLY[@]// /\\\\ }}\")")
+    } else {
+        complete
+    }
+}
+
+/// What the positional arguments of `command` are completed with besides
+/// their fixed values, which are in [`words_of`]
+fn positional_values(command: &Command) -> &Values {
+    const NONE: &Values = &Values::Words(Vec::new());
+    command
+        .positional()
+        .find(|x| matches!(x, Values::Files | Values::Sinners))
+        .unwrap_or(NONE)
+}
+
+/// A bash `case` pattern matching `flag` after `command` or any of its
+/// subcommands
+fn bash_pattern(command: &Command, flag: &str) -> String {
+    match command.path() {
+        | path if path.is_empty() => format!("*\">{flag}\""),
+        | path => format!("\"{path}>{flag}\"|\"{path} \"*\">{flag}\""),
+    }
+}
+
+fn bash(commands: &[Command]) -> String {
+    let mut script = String::from(
+        "# bash completion for ptndle-cli. Load it with `source <(ptndle-cli completions \
+         bash)`.\n_ptndle_cli() {\n    local cur=\"${COMP_WORDS[COMP_CWORD]}\" \
+         prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n    local path=\"\" word i IFS=$'\\n'\n    for ((i \
+         = 1; i < COMP_CWORD; i++)); do\n        word=\"${COMP_WORDS[i]}\"\n        case \
+         \"$path>$word\" in\n",
+    );
+    for command in commands.iter().filter(|x| !x.path.is_empty()) {
+        let (name, parent) = command.path.split_last().expect("subcommands have a name");
+        _ = writeln!(
+            script,
+            "            \"{}>{name}\") path=\"${{path:+$path }}$word\" ;;",
+            parent.join(" ")
+        );
+    }
+    script.push_str("        esac\n    done\n    case \"$path>$prev\" in\n");
+    for (command, spelling, values) in value_flags(commands) {
+        _ = writeln!(
+            script,
+            "        {}) {}; return ;;",
+            bash_pattern(command, &spelling),
+            bash_values(values, &[])
+        );
+    }
+    script.push_str("    esac\n    case \"$path\" in\n");
+    for command in commands {
+        _ = writeln!(
+            script,
+            "        \"{}\") {} ;;",
+            command.path(),
+            bash_values(positional_values(command), &words_of(commands, command))
+        );
+    }
+    script.push_str("    esac\n}\ncomplete -F _ptndle_cli ptndle-cli\n");
+    script
+}
+
+fn zsh(commands: &[Command]) -> String {
+    format!(
+        "# zsh completion for ptndle-cli. Load it with `source <(ptndle-cli completions \
+         zsh)`.\nautoload -U +X bashcompinit && bashcompinit\n{}",
+        bash(commands)
+            .split_once('\n')
+            .map_or_else(String::new, |(_, rest)| rest.to_owned())
+    )
+}
+
+/// Quotes `text` as one fish word
+fn fish_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// The options of fish's `complete` that complete a flag or argument with
+/// `values`
+fn fish_values(values: &Values) -> String {
+    match values {
+        | Values::Any | Values::Commands => String::new(),
+        | Values::Files => " -F".to_owned(),
+        | Values::Sinners => {
+            format!(
+                " -a {}",
+                fish_quote(&format!("({NAMES_COMMAND} 2>/dev/null)"))
+            )
+        },
+        | Values::Words(words) => format!(" -a {}", fish_quote(&words.join(" "))),
+    }
+}
+
+fn fish(commands: &[Command]) -> String {
+    let mut script = String::from(
+        "# fish completion for ptndle-cli. Load it with `ptndle-cli completions fish | \
+         source`.\ncomplete -c ptndle-cli -f\nfunction __ptndle_cli_path\n    set -l path\n    \
+         for word in (commandline -opc)[2..-1]\n        switch \"$path>$word\"\n",
+    );
+    for command in commands.iter().filter(|x| !x.path.is_empty()) {
+        let (name, parent) = command.path.split_last().expect("subcommands have a name");
+        _ = writeln!(
+            script,
+            "            case {}\n                set path (string trim \"$path $word\")",
+            fish_quote(&format!("{}>{name}", parent.join(" ")))
+        );
+    }
+    script.push_str(
+        "        end\n    end\n    echo $path\nend\nfunction __ptndle_cli_in\n    set -l path \
+         (__ptndle_cli_path)\n    test \"$path\" = \"$argv[1]\"\nend\nfunction \
+         __ptndle_cli_under\n    set -l path (__ptndle_cli_path)\n    test \"$path\" = \
+         \"$argv[1]\"; or string match -q -- \"$argv[1] *\" \"$path\"\nend\n",
+    );
+    for command in commands {
+        let path = fish_quote(&command.path());
+        for subcommand in subcommands(commands, command) {
+            let name = subcommand.path.last().expect("subcommands have a name");
+            _ = writeln!(
+                script,
+                "complete -c ptndle-cli -n \"__ptndle_cli_in {path}\" -a {name} -d {}",
+                fish_quote(&subcommand.doc)
+            );
+        }
+        for arg in &command.args {
+            let condition = if command.path.is_empty() {
+                String::new()
+            } else if arg.long.is_some() {
+                format!(" -n \"__ptndle_cli_under {path}\"")
+            } else {
+                format!(" -n \"__ptndle_cli_in {path}\"")
+            };
+            let flag = arg
+                .long
+                .as_ref()
+                .map_or_else(String::new, |x| format!(" -l {x}"));
+            let short = arg.short.map_or_else(String::new, |x| format!(" -s {x}"));
+            let values = match (&arg.long, &arg.values) {
+                | (Some(_), Some(values)) => format!(" -r{}", fish_values(values)),
+                | (None, Some(values)) => fish_values(values),
+                | (_, None) => String::new(),
+            };
+            if flag.is_empty() && values.is_empty() {
+                continue;
+            }
+            _ = writeln!(
+                script,
+                "complete -c ptndle-cli{condition}{flag}{short}{values} -d {}",
+                fish_quote(&arg.doc)
+            );
+        }
+    }
+    script
+}
+
+/// Quotes `text` as a `PowerShell` string
+fn powershell_quote(text: &str) -> String { format!("'{}'", text.replace('\'', "''")) }
+
+/// The `PowerShell` expression for the completions of `values` and `words`
+fn powershell_values(values: &Values, words: &[String]) -> String {
+    let quoted = |all: &[String]| {
+        let quoted = all.iter().map(|x| powershell_quote(x)).collect::<Vec<_>>();
+        format!("@({})", quoted.join(", "))
+    };
+    let more = match values {
+        | Values::Any | Values::Commands => None,
+        | Values::Files => Some("@(Get-ChildItem -Name \"$wordToComplete*\")".to_owned()),
+        | Values::Sinners => Some(format!("@({NAMES_COMMAND} 2>$null)")),
+        | Values::Words(values) => return quoted(&[words, values].concat()),
+    };
+    match more {
+        | Some(more) if words.is_empty() => more,
+        | Some(more) => format!("{} + {more}", quoted(words)),
+        | None => quoted(words),
+    }
+}
+
+fn powershell(commands: &[Command]) -> String {
+    let mut script = String::from(
+        "# PowerShell completion for ptndle-cli. Load it with\n# `ptndle-cli completions \
+         powershell | Out-String | Invoke-Expression`.\nRegister-ArgumentCompleter -Native \
+         -CommandName 'ptndle-cli' -ScriptBlock {\n    param($wordToComplete, $commandAst, \
+         $cursorPosition)\n    $words = @($commandAst.CommandElements | Select-Object -Skip 1 | \
+         ForEach-Object { $_.ToString() })\n    if ($wordToComplete -and $words.Count) { $words = \
+         @($words | Select-Object -SkipLast 1) }\n    $path = ''\n    foreach ($word in $words) \
+         {\n        switch (\"$path>$word\") {\n",
+    );
+    for command in commands.iter().filter(|x| !x.path.is_empty()) {
+        let (name, parent) = command.path.split_last().expect("subcommands have a name");
+        _ = writeln!(
+            script,
+            "            {} {{ $path = \"$path $word\".Trim() }}",
+            powershell_quote(&format!("{}>{name}", parent.join(" ")))
+        );
+    }
+    script.push_str(
+        "        }\n    }\n    $prev = if ($words.Count) { $words[-1] } else { '' }\n    \
+         $completions = switch -Wildcard (\"$path>$prev\") {\n",
+    );
+    for (command, spelling, values) in value_flags(commands) {
+        let path = command.path();
+        let patterns = if path.is_empty() {
+            vec![format!("*>{spelling}")]
+        } else {
+            vec![format!("{path}>{spelling}"), format!("{path} *>{spelling}")]
+        };
+        for pattern in patterns {
+            _ = writeln!(
+                script,
+                "        {} {{ {}; break }}",
+                powershell_quote(&pattern),
+                powershell_values(values, &[])
+            );
+        }
+    }
+    script.push_str("        default {\n            switch ($path) {\n");
+    for command in commands {
+        _ = writeln!(
+            script,
+            "                {} {{ {} }}",
+            powershell_quote(&command.path()),
+            powershell_values(positional_values(command), &words_of(commands, command))
+        );
+    }
+    script.push_str(
+        "            }\n        }\n    }\n    \
+         $completions | Where-Object { $_ -like \"$wordToComplete*\" } | ForEach-Object {\n        \
+         $text = if ($_ -match '\\s') { \"'$_'\" } else { $_ }\n        \
+         [System.Management.Automation.CompletionResult]::new($text, $_, 'ParameterValue', $_)\n    \
+         }\n}\n",
+    );
+    script
+}
+
+/// Prints a completion script for `shell`, or the sinner names the scripts
+/// complete with `--names`
+pub fn run(Completions { shell, names }: Completions, source: &DataSource) -> eyre::Result<()> {
+    if names {
+        let sinners = load_sinners(&settings::context(), source, &<_>::default())?;
+        for name in sinner_names(&sinners) {
+            println!("{name}");
+        }
+        return Ok(());
+    }
+    let shell = shell.ok_or_else(|| {
+        Error::InvalidInput(
+            "Expected a shell: `bash`, `zsh`, `fish` or `powershell`. See `ptndle-cli help \
+             completions`"
+                .to_owned(),
+        )
+    })?;
+    let commands = parse_commands();
+    let script = match shell {
+        | Shell::Bash => bash(&commands),
+        | Shell::Zsh => zsh(&commands),
+        | Shell::Fish => fish(&commands),
+        | Shell::Powershell => powershell(&commands),
+    };
+    print!("{script}");
+    Ok(())
+}
@@ -0,0 +1,457 @@
+//! The config file, which sets defaults for flags that aren't given, and the
+//! `config` command for viewing and changing it
+
+use std::fmt::{Display, Write};
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::str::{Chars, FromStr};
+
+use eyre::Context;
+use ptndle_core::data::CacheTtl;
+use ptndle_core::error::Error;
+use ptndle_core::i18n::Lang;
+use ptndle_core::paths;
+use ptndle_core::solver::Strategy;
+use ptndle_core::theme::Theme;
+
+use crate::console::verbose;
+use crate::flags::{Config, ConfigCmd, Get, Set, Unset};
+use crate::settings;
+
+/// A setting in the config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigKey {
+    Strategy,
+    Theme,
+    DataUrl,
+    CacheTtl,
+    MaxGuesses,
+    Strict,
+    Exclude,
+    Lang,
+}
+
+impl ConfigKey {
+    pub const ALL: [Self; 8] = [
+        Self::Strategy,
+        Self::Theme,
+        Self::DataUrl,
+        Self::CacheTtl,
+        Self::MaxGuesses,
+        Self::Strict,
+        Self::Exclude,
+        Self::Lang,
+    ];
+    /// The name of the setting, which is the same as the flag it sets
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            | Self::Strategy => "strategy",
+            | Self::Theme => "theme",
+            | Self::DataUrl => "data-url",
+            | Self::CacheTtl => "cache-ttl",
+            | Self::MaxGuesses => "max-guesses",
+            | Self::Strict => "strict",
+            | Self::Exclude => "exclude",
+            | Self::Lang => "lang",
+        }
+    }
+}
+
+impl Display for ConfigKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownConfigKeyError(String);
+
+impl FromStr for ConfigKey {
+    type Err = UnknownConfigKeyError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.to_str() == s.trim())
+            .ok_or_else(|| UnknownConfigKeyError(s.to_owned()))
+    }
+}
+impl Display for UnknownConfigKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Unknown setting: `")?;
+        f.write_str(&self.0)?;
+        f.write_str(
+            "`. Expected one of `strategy`, `theme`, `data-url`, `cache-ttl`, `max-guesses`, \
+             `strict`, `exclude` or `lang`",
+        )
+    }
+}
+
+/// A value in the config file, which only needs the strings, integers,
+/// booleans and arrays of strings of TOML
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Text(String),
+    Integer(i64),
+    Bool(bool),
+    List(Vec<String>),
+}
+
+impl Value {
+    fn text(self) -> eyre::Result<String> {
+        match self {
+            | Self::Text(text) => Ok(text),
+            | Self::Integer(x) => Ok(x.to_string()),
+            | Self::Bool(x) => Ok(x.to_string()),
+            | Self::List(_) => {
+                Err(Error::InvalidInput("Expected a single value, not a list".to_owned()).into())
+            },
+        }
+    }
+
+    /// The items of a list, or of a comma-separated string as given to
+    /// `config set`
+    fn list(self) -> eyre::Result<Vec<String>> {
+        match self {
+            | Self::Text(text) => {
+                Ok(text
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|x| !x.is_empty())
+                    .map(str::to_owned)
+                    .collect())
+            },
+            | Self::Integer(_) | Self::Bool(_) => {
+                Err(Error::InvalidInput("Expected a list".to_owned()).into())
+            },
+            | Self::List(items) => Ok(items),
+        }
+    }
+
+    /// Writes the value as TOML
+    fn to_toml(&self) -> String {
+        let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+        match self {
+            | Self::Text(text) => quote(text),
+            | Self::Integer(x) => x.to_string(),
+            | Self::Bool(x) => x.to_string(),
+            | Self::List(items) => {
+                let items = items.iter().map(|x| quote(x)).collect::<Vec<_>>();
+                format!("[{}]", items.join(", "))
+            },
+        }
+    }
+}
+
+/// Shown as given to `config set`, with lists separated by commas
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            | Self::Text(text) => f.write_str(text),
+            | Self::Integer(x) => write!(f, "{x}"),
+            | Self::Bool(x) => write!(f, "{x}"),
+            | Self::List(items) => f.write_str(&items.join(",")),
+        }
+    }
+}
+
+fn skip_spaces(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|x| x.is_whitespace()).is_some() {}
+}
+
+/// Reads the rest of a string whose opening `quote` has been read. Single
+/// quoted strings have no escapes, like in TOML.
+fn parse_string(chars: &mut Peekable<Chars>, quote: char) -> Option<String> {
+    let mut text = String::new();
+    loop {
+        match chars.next()? {
+            | c if c == quote => return Some(text),
+            | '\\' if quote == '"' => {
+                text.push(match chars.next()? {
+                    | 'n' => '\n',
+                    | 't' => '\t',
+                    | c @ ('"' | '\\') => c,
+                    | _ => return None,
+                });
+            },
+            | c => text.push(c),
+        }
+    }
+}
+
+/// Parses the value of a `key = value` line, which may be followed by a
+/// comment
+fn parse_value(text: &str) -> Option<Value> {
+    let mut chars = text.trim().chars().peekable();
+    let value = match chars.next()? {
+        | quote @ ('"' | '\'') => Value::Text(parse_string(&mut chars, quote)?),
+        | '[' => {
+            let mut items = Vec::new();
+            loop {
+                skip_spaces(&mut chars);
+                match chars.next()? {
+                    | ']' => break,
+                    | quote @ ('"' | '\'') => items.push(parse_string(&mut chars, quote)?),
+                    | _ => return None,
+                }
+                skip_spaces(&mut chars);
+                match chars.next()? {
+                    | ',' => {},
+                    | ']' => break,
+                    | _ => return None,
+                }
+            }
+            Value::List(items)
+        },
+        | first => {
+            let word = std::iter::once(first)
+                .chain(chars.by_ref().take_while(|x| !x.is_whitespace()))
+                .filter(|&x| x != '_')
+                .collect::<String>();
+            match word.as_str() {
+                | "true" => Value::Bool(true),
+                | "false" => Value::Bool(false),
+                | _ => Value::Integer(word.parse().ok()?),
+            }
+        },
+    };
+    skip_spaces(&mut chars);
+    matches!(chars.next(), None | Some('#')).then_some(value)
+}
+
+/// The defaults set in the config file. Anything not set uses the built-in
+/// default.
+#[derive(Debug, Default)]
+pub struct ConfigFile {
+    pub strategy: Option<Strategy>,
+    /// Kept as written, since a [`Theme`] can't be written back out
+    pub theme: Option<String>,
+    pub data_urls: Vec<String>,
+    pub cache_ttl: Option<CacheTtl>,
+    pub max_guesses: Option<u8>,
+    pub strict: Option<bool>,
+    pub exclude: Vec<String>,
+    pub lang: Option<Lang>,
+}
+
+impl ConfigFile {
+    /// The config file `config` changes, which is the shared one, or the
+    /// profile's own with --profile
+    pub fn path() -> PathBuf {
+        settings::profile().map_or_else(paths::config_file, paths::profile_config_file)
+    }
+
+    /// Loads the shared config file with the profile's settings on top, or no
+    /// settings if there are no config files
+    pub fn load() -> eyre::Result<Self> {
+        let shared = Self::load_file(&paths::config_file())?;
+        match settings::profile().map(paths::profile_config_file) {
+            | Some(path) => Ok(shared.overridden_by(Self::load_file(&path)?)),
+            | None => Ok(shared),
+        }
+    }
+
+    /// These settings, with the ones set in `other` replaced
+    fn overridden_by(self, other: Self) -> Self {
+        let list = |items: Vec<String>, overrides: Vec<String>| {
+            if overrides.is_empty() {
+                items
+            } else {
+                overrides
+            }
+        };
+        Self {
+            strategy: other.strategy.or(self.strategy),
+            theme: other.theme.or(self.theme),
+            data_urls: list(self.data_urls, other.data_urls),
+            cache_ttl: other.cache_ttl.or(self.cache_ttl),
+            max_guesses: other.max_guesses.or(self.max_guesses),
+            strict: other.strict.or(self.strict),
+            exclude: list(self.exclude, other.exclude),
+            lang: other.lang.or(self.lang),
+        }
+    }
+
+    /// Loads the config file at `path`, or no settings if there isn't one
+    fn load_file(path: &Path) -> eyre::Result<Self> {
+        let text = match std::fs::read_to_string(path) {
+            | Ok(text) => text,
+            | Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            | Err(e) => {
+                return Err(Error::Io(format!("Failed to read {}: {e}", path.display())).into());
+            },
+        };
+        verbose!("Loading the defaults in {}", path.display());
+        let mut config = Self::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let context = || format!("On line {} of {}", index + 1, path.display());
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| {
+                    Error::InvalidInput(
+                        "Expected `setting = value`, like `strategy = \"entropy\"`".to_owned(),
+                    )
+                })
+                .with_context(context)?;
+            let key = key
+                .trim()
+                .parse::<ConfigKey>()
+                .map_err(|e| Error::InvalidInput(e.to_string()))
+                .with_context(context)?;
+            let value = parse_value(value)
+                .ok_or_else(|| {
+                    Error::InvalidInput(
+                        "Expected a quoted string, a number, `true`, `false` or a list of strings"
+                            .to_owned(),
+                    )
+                })
+                .with_context(context)?;
+            config.set(key, value).with_context(context)?;
+        }
+        Ok(config)
+    }
+
+    /// Writes the settings to the config file at `path`, replacing what was
+    /// there
+    fn save(&self, path: &Path) -> eyre::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| Error::Io(format!("Failed to create {}: {e}", dir.display())))?;
+        }
+        let mut text = "# Defaults for ptndle-cli. Flags given on the command line take \
+                        precedence.\n"
+            .to_owned();
+        for key in ConfigKey::ALL {
+            if let Some(value) = self.value(key) {
+                _ = writeln!(text, "{key} = {}", value.to_toml());
+            }
+        }
+        std::fs::write(path, text)
+            .map_err(|e| Error::Io(format!("Failed to write {}: {e}", path.display())).into())
+    }
+
+    /// The theme, if one is set
+    pub fn theme(&self) -> Option<Theme> { self.theme.as_deref().and_then(|x| x.parse().ok()) }
+
+    fn set(&mut self, key: ConfigKey, value: Value) -> eyre::Result<()> {
+        match key {
+            | ConfigKey::Strategy => {
+                self.strategy = Some(
+                    value
+                        .text()?
+                        .parse::<Strategy>()
+                        .map_err(|e| Error::InvalidInput(e.to_string()))?,
+                );
+            },
+            | ConfigKey::Theme => {
+                let theme = value.text()?;
+                theme
+                    .parse::<Theme>()
+                    .map_err(|e| Error::InvalidInput(e.to_string()))?;
+                self.theme = Some(theme);
+            },
+            | ConfigKey::DataUrl => self.data_urls = value.list()?,
+            | ConfigKey::CacheTtl => {
+                self.cache_ttl = Some(
+                    value
+                        .text()?
+                        .parse::<CacheTtl>()
+                        .map_err(|e| Error::InvalidInput(e.to_string()))?,
+                );
+            },
+            | ConfigKey::MaxGuesses => {
+                self.max_guesses = Some(value.text()?.parse().map_err(|_| {
+                    Error::InvalidInput(
+                        "Expected a number of guesses up to 255, or 0 for no limit".to_owned(),
+                    )
+                })?);
+            },
+            | ConfigKey::Strict => {
+                self.strict =
+                    Some(value.text()?.parse().map_err(|_| {
+                        Error::InvalidInput("Expected `true` or `false`".to_owned())
+                    })?);
+            },
+            | ConfigKey::Exclude => self.exclude = value.list()?,
+            | ConfigKey::Lang => {
+                self.lang = Some(
+                    value
+                        .text()?
+                        .parse::<Lang>()
+                        .map_err(|e| Error::InvalidInput(e.to_string()))?,
+                );
+            },
+        }
+        Ok(())
+    }
+
+    fn unset(&mut self, key: ConfigKey) {
+        match key {
+            | ConfigKey::Strategy => self.strategy = None,
+            | ConfigKey::Theme => self.theme = None,
+            | ConfigKey::DataUrl => self.data_urls.clear(),
+            | ConfigKey::CacheTtl => self.cache_ttl = None,
+            | ConfigKey::MaxGuesses => self.max_guesses = None,
+            | ConfigKey::Strict => self.strict = None,
+            | ConfigKey::Exclude => self.exclude.clear(),
+            | ConfigKey::Lang => self.lang = None,
+        }
+    }
+
+    /// The value of `key`, if it is set
+    fn value(&self, key: ConfigKey) -> Option<Value> {
+        let list = |items: &Vec<String>| (!items.is_empty()).then(|| Value::List(items.clone()));
+        match key {
+            | ConfigKey::Strategy => self.strategy.map(|x| Value::Text(x.to_string())),
+            | ConfigKey::Theme => self.theme.clone().map(Value::Text),
+            | ConfigKey::DataUrl => list(&self.data_urls),
+            | ConfigKey::CacheTtl => self.cache_ttl.map(|x| Value::Text(x.to_string())),
+            | ConfigKey::MaxGuesses => self.max_guesses.map(|x| Value::Integer(x.into())),
+            | ConfigKey::Strict => self.strict.map(Value::Bool),
+            | ConfigKey::Exclude => list(&self.exclude),
+            | ConfigKey::Lang => self.lang.map(|x| Value::Text(x.to_string())),
+        }
+    }
+}
+
+/// Shows, sets or removes settings in the config file
+pub fn run(Config { subcommand }: Config) -> eyre::Result<()> {
+    let path = ConfigFile::path();
+    match subcommand {
+        | ConfigCmd::Get(Get { key: Some(key) }) => {
+            match ConfigFile::load()?.value(key) {
+                | Some(value) => println!("{value}"),
+                | None => eprintln!("`{key}` isn't set, so the built-in default is used"),
+            }
+        },
+        | ConfigCmd::Get(Get { key: None }) => {
+            let config = ConfigFile::load()?;
+            println!("Config file: {}", paths::config_file().display());
+            if let Some(profile) = settings::profile() {
+                let file = paths::profile_config_file(profile);
+                println!("Profile config file: {}", file.display());
+            }
+            for key in ConfigKey::ALL {
+                if let Some(value) = config.value(key) {
+                    println!("{key} = {}", value.to_toml());
+                }
+            }
+        },
+        | ConfigCmd::Set(Set { key, value }) => {
+            let mut config = ConfigFile::load_file(&path)?;
+            config.set(key, Value::Text(value))?;
+            config.save(&path)?;
+            println!("Set `{key}` in {}", path.display());
+        },
+        | ConfigCmd::Unset(Unset { key }) => {
+            let mut config = ConfigFile::load_file(&path)?;
+            config.unset(key);
+            config.save(&path)?;
+            println!("Removed `{key}` from {}", path.display());
+        },
+    }
+    Ok(())
+}
@@ -0,0 +1,163 @@
+//! Where the messages and progress the library reports while it works are
+//! printed: banners on stdout, and notes, diagnostics, warnings and progress
+//! on stderr
+
+use std::fmt::{Arguments, Display};
+use std::io::{IsTerminal, Write};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use ptndle_core::progress::ProgressEvent;
+use ptndle_core::verbosity::{Note, Reporter, Verbosity};
+
+/// The width of the progress bar
+const BAR_WIDTH: usize = 24;
+
+/// How progress is reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// A bar that is redrawn in place
+    Bar,
+    /// A line of JSON for each step, such as
+    /// `{"task":"gather
+    /// mean","done":3,"total":112,"percent":2,"current":"Hella"}`
+    Json,
+    /// Nothing
+    None,
+}
+
+impl ProgressMode {
+    pub const ALL: [Self; 3] = [Self::Bar, Self::Json, Self::None];
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            | Self::Bar => "bar",
+            | Self::Json => "json",
+            | Self::None => "none",
+        }
+    }
+
+    /// A bar if stderr is a terminal and `--quiet` wasn't used, as nobody
+    /// would see it otherwise
+    fn detect(verbosity: Verbosity) -> Self {
+        if verbosity >= Verbosity::Normal && std::io::stderr().is_terminal() {
+            Self::Bar
+        } else {
+            Self::None
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownProgressModeError(String);
+
+impl FromStr for ProgressMode {
+    type Err = UnknownProgressModeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.to_str() == s.trim())
+            .ok_or_else(|| UnknownProgressModeError(s.to_owned()))
+    }
+}
+impl Display for UnknownProgressModeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Unknown progress mode: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected `bar`, `json` or `none`")
+    }
+}
+
+/// Prints what the library reports, hiding what isn't shown at `verbosity`
+#[derive(Debug, Clone, Copy)]
+pub struct Console {
+    verbosity: Verbosity,
+    progress: ProgressMode,
+}
+
+static CONSOLE: OnceLock<Console> = OnceLock::new();
+
+impl Reporter for Console {
+    fn note(&self, note: Note, message: Arguments<'_>) {
+        if !note.shown(self.verbosity) {
+            return;
+        }
+        match note {
+            | Note::Banner => println!("{message}"),
+            | Note::Status => eprintln!("{message}"),
+            | Note::Verbose => eprintln!("[VERBOSE] {message}"),
+            | Note::Warning => eprintln!("[WARNING] {message}"),
+        }
+    }
+
+    fn step(&self, event: &ProgressEvent) {
+        let mut stderr = std::io::stderr().lock();
+        _ = match self.progress {
+            | ProgressMode::Bar => {
+                let filled = event.done * BAR_WIDTH / event.total.max(1);
+                write!(
+                    stderr,
+                    "\r\x1b[2K{} [{}{}] {:>3}% ({}/{}) {}",
+                    event.task,
+                    "█".repeat(filled),
+                    "░".repeat(BAR_WIDTH.saturating_sub(filled)),
+                    event.percent,
+                    event.done,
+                    event.total,
+                    event.current
+                )
+            },
+            | ProgressMode::Json => writeln!(stderr, "{}", facet_json::to_string(event)),
+            | ProgressMode::None => Ok(()),
+        };
+    }
+
+    /// Clears the bar so that what is printed next starts on an empty line
+    fn finish(&self, _: &str) {
+        if self.progress == ProgressMode::Bar {
+            eprint!("\r\x1b[2K");
+        }
+    }
+}
+
+/// Prints what the library reports from now on, hiding what isn't shown at
+/// `verbosity`, with progress reported as `mode`, or as a bar when it would be
+/// seen. Only the first call has an effect.
+pub fn install(verbosity: Verbosity, mode: Option<ProgressMode>) {
+    _ = CONSOLE.set(Console {
+        verbosity,
+        progress: mode.unwrap_or_else(|| ProgressMode::detect(verbosity)),
+    });
+}
+
+/// The installed console, or one that shows notes but no progress if none
+/// was installed
+pub fn console() -> &'static Console {
+    CONSOLE.get_or_init(|| {
+        Console {
+            verbosity: Verbosity::Normal,
+            progress: ProgressMode::None,
+        }
+    })
+}
+
+/// Prints welcome text or instructions to the [`console`]
+macro_rules! banner {
+    ($($arg:tt)*) => { ptndle_core::banner!($crate::console::console(), $($arg)*) };
+}
+
+/// Prints a note about what the program is doing to the [`console`]
+macro_rules! status {
+    ($($arg:tt)*) => { ptndle_core::status!($crate::console::console(), $($arg)*) };
+}
+
+/// Prints a diagnostic to the [`console`]
+macro_rules! verbose {
+    ($($arg:tt)*) => { ptndle_core::verbose!($crate::console::console(), $($arg)*) };
+}
+
+/// Prints a warning to the [`console`]
+macro_rules! warning {
+    ($($arg:tt)*) => { ptndle_core::warning!($crate::console::console(), $($arg)*) };
+}
+
+pub(crate) use {banner, status, verbose, warning};
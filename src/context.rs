@@ -0,0 +1,51 @@
+//! What the library needs to know about the program using it, passed to the
+//! functions that need it instead of being kept in globals, so that a program
+//! can use several at once
+
+use std::fmt::Arguments;
+use std::path::PathBuf;
+
+use crate::fuzzy::Names;
+use crate::game::GameProfile;
+use crate::i18n::Lang;
+use crate::paths::{cache_dir, data_dir, Profile};
+use crate::progress::ProgressEvent;
+use crate::theme::Theme;
+use crate::verbosity::{Note, Reporter};
+
+/// The game and profile whose files are used, how sinners are named and
+/// text is shown, and where messages go
+#[derive(Clone, Copy)]
+pub struct Context<'context> {
+    pub game: &'context GameProfile,
+    /// The profile whose stats, history and saved games are used, or `None`
+    /// for the ones shared by everyone
+    pub profile: Option<&'context Profile>,
+    pub names: &'context Names,
+    /// The language text shown while playing is in
+    pub lang: Lang,
+    pub theme: Theme,
+    pub reporter: &'context dyn Reporter,
+}
+
+impl Context<'_> {
+    /// The directory the game's sinner data is cached in
+    pub fn cache_dir(&self) -> PathBuf { cache_dir(self.game) }
+
+    /// The directory the profile's stats, history and saved games are kept
+    /// in
+    pub fn data_dir(&self) -> PathBuf { data_dir(self.game, self.profile) }
+
+    /// The name of the sinner named `name` in the context's language
+    pub fn display_name<'name>(&'name self, name: &'name str) -> &'name str {
+        self.names.translations.display_name(self.lang, name)
+    }
+}
+
+/// A context reports to its [`Reporter`], so it can be given to
+/// [`status!`](crate::status) and the other macros
+impl Reporter for Context<'_> {
+    fn note(&self, note: Note, message: Arguments<'_>) { self.reporter.note(note, message); }
+    fn step(&self, event: &ProgressEvent) { self.reporter.step(event); }
+    fn finish(&self, task: &str) { self.reporter.finish(task); }
+}
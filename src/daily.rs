@@ -0,0 +1,94 @@
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use eyre::eyre;
+
+use crate::data::Sinner;
+use crate::rng::Rng;
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+/// A day in UTC, counted in days since 1970-01-01
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Day(u64);
+
+impl Day {
+    pub fn today() -> eyre::Result<Self> {
+        let since_epoch = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|e| eyre!("System clock is set before 1970: {e}"))?;
+        Ok(Self(since_epoch.as_secs() / SECONDS_PER_DAY))
+    }
+
+    /// Picks the target of the daily puzzle.
+    ///
+    /// The sinners are sorted by name and one is picked by a [`Rng`] seeded
+    /// with the number of days since 1970-01-01, so the daily is the same for
+    /// everyone with the same sinner data no matter what order it is in.
+    pub fn target(self, sinners: &[Sinner]) -> &Sinner {
+        let mut sorted = sinners.iter().collect::<Vec<_>>();
+        sorted.sort_by(|a, b| a.name.cmp(&b.name));
+        sorted[Rng::new(self.0).below(sorted.len())]
+    }
+}
+
+impl std::fmt::Display for Day {
+    /// Formats the day as `YYYY-MM-DD`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = self.0 + 719_468;
+        let era = z / 146_097;
+        let day_of_era = z % 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + u64::from(month <= 2);
+        write!(f, "{year:04}-{month:02}-{day:02}")
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidDayError(String);
+
+impl FromStr for Day {
+    type Err = InvalidDayError;
+    /// Parses a day formatted as `YYYY-MM-DD`, from 1970 to 9999
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || InvalidDayError(s.to_owned());
+        let mut parts = s.trim().splitn(3, '-').map(str::parse::<u64>);
+        let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(error());
+        };
+        // Years past 9999 would overflow, and don't fit the format anyway
+        if !(1970..=9999).contains(&year) || !(1..=12).contains(&month) || !(1..=31).contains(&day)
+        {
+            return Err(error());
+        }
+        // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let year_of_march = year - u64::from(month <= 2);
+        let era = year_of_march / 400;
+        let year_of_era = year_of_march - era * 400;
+        let month_from_march = if month > 2 { month - 3 } else { month + 9 };
+        let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let parsed = Self(era * 146_097 + day_of_era - 719_468);
+        // Days past the end of the month, like 02-30, come out as a different date
+        if parsed.to_string() == format!("{year:04}-{month:02}-{day:02}") {
+            Ok(parsed)
+        } else {
+            Err(error())
+        }
+    }
+}
+impl std::fmt::Display for InvalidDayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Invalid date: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected a date like `2024-06-01`")
+    }
+}
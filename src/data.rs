@@ -3,15 +3,24 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::{Duration, SystemTime};
 
-use eyre::{eyre, Context};
-use facet::Facet;
+use eyre::eyre;
+use facet::{Facet, Type, UserType};
 
-use crate::compare::{Threshold, Thresholds};
-use crate::flags::NameAndGuesses;
-use crate::play::NameAndGuessError;
+use crate::compare::{ThresholdConfig, Thresholds};
+use crate::context::Context;
+use crate::daily::Day;
+use crate::error::Error;
+use crate::fuzzy::find_sinner;
+use crate::game::GameProfile;
+use crate::paths::cache_dir;
+use crate::play::{NameAndGuess, NameAndGuessError, NameAndGuesses};
+use crate::verbosity::{Reporter, Silent};
+use crate::{status, verbose, warning};
+
+pub mod diff;
+mod schema;
 
 /// A sinner's alignment
-#[expect(dead_code, reason = "Facet constructs these")]
 #[derive(Facet, Debug, PartialEq, Eq, Clone, Copy)]
 #[repr(u8)]
 pub enum Alignment {
@@ -28,10 +37,9 @@ pub enum Alignment {
     Famine,
     Violence,
     Treachery,
-    War
+    War,
 }
 /// A sinner's tendency
-#[expect(dead_code, reason = "Facet constructs these")]
 #[derive(Facet, Debug, PartialEq, Eq, Clone, Copy)]
 #[repr(u8)]
 pub enum Tendency {
@@ -44,7 +52,6 @@ pub enum Tendency {
 }
 
 /// A sinner's birthplace
-#[expect(dead_code, reason = "Facet constructs these")]
 #[derive(Facet, Debug, PartialEq, Eq, Clone, Copy)]
 #[repr(u8)]
 pub enum BirthPlace {
@@ -53,6 +60,66 @@ pub enum BirthPlace {
     Eastside,
 }
 
+/// The names of the variants of `T`, one of the enums in the sinner data
+pub fn variant_names<T: for<'facet> Facet<'facet>>() -> Vec<&'static str> {
+    match T::SHAPE.ty {
+        | Type::User(UserType::Enum(x)) => x.variants.iter().map(|x| x.name).collect(),
+        | _ => Vec::new(),
+    }
+}
+
+/// A name that isn't one of the variants of an enum in the sinner data
+#[derive(Debug)]
+pub struct UnknownVariantError {
+    /// What the enum is, such as `alignment`
+    kind: &'static str,
+    value: String,
+    variants: Vec<&'static str>,
+}
+
+impl std::fmt::Display for UnknownVariantError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown {}: `{}`", self.kind, self.value)?;
+        if let [rest @ .., last] = self.variants.as_slice() {
+            write!(f, ". Expected one of {} or {last}", rest.join(", "))?;
+        }
+        Ok(())
+    }
+}
+impl std::error::Error for UnknownVariantError {}
+
+/// Parses the name of a variant of `T`, ignoring case
+fn parse_variant<T: for<'facet> Facet<'facet>>(
+    kind: &'static str,
+    s: &str,
+) -> Result<T, UnknownVariantError> {
+    let variants = variant_names::<T>();
+    variants
+        .iter()
+        .find(|x| x.eq_ignore_ascii_case(s.trim()))
+        .and_then(|name| facet_json::from_str(&format!("{name:?}")).ok())
+        .ok_or_else(|| {
+            UnknownVariantError {
+                kind,
+                value: s.to_owned(),
+                variants,
+            }
+        })
+}
+
+impl FromStr for Alignment {
+    type Err = UnknownVariantError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> { parse_variant("alignment", s) }
+}
+impl FromStr for Tendency {
+    type Err = UnknownVariantError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> { parse_variant("tendency", s) }
+}
+impl FromStr for BirthPlace {
+    type Err = UnknownVariantError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> { parse_variant("birthplace", s) }
+}
+
 #[derive(Facet)]
 struct RawSinner {
     name: String,
@@ -61,6 +128,8 @@ struct RawSinner {
     tendency: Tendency,
     height: String,
     birthplace: BirthPlace,
+    #[facet(default)]
+    released: Option<String>,
 }
 
 impl RawSinner {
@@ -71,6 +140,9 @@ impl RawSinner {
             .strip_suffix("cm")
             .and_then(|x| x.parse().ok())
             .ok_or_else(|| eyre::eyre!("invalid height"))?;
+        if let Some(released) = &self.released {
+            released.parse::<Day>().map_err(|e| eyre!("{e}"))?;
+        }
         Ok(Sinner {
             name: self.name,
             code,
@@ -78,6 +150,7 @@ impl RawSinner {
             alignment: self.alignment,
             tendency: self.tendency,
             birthplace: self.birthplace,
+            released: self.released,
         })
     }
 }
@@ -94,59 +167,117 @@ pub struct Sinner {
     /// The height of the sinner in cm
     pub height: u8,
     pub birthplace: BirthPlace,
+    /// The day the sinner was added to the game as `YYYY-MM-DD`, if the data
+    /// has it
+    #[facet(default)]
+    pub released: Option<String>,
 }
 
 impl Sinner {
-    /// Gets the height and code thresholds based on this sinner's data
-    #[expect(clippy::float_arithmetic, reason = "we don't care for now")]
-    pub fn thresholds(&self) -> Thresholds {
+    /// The day the sinner was added to the game, if the data has a valid one
+    pub fn release_day(&self) -> Option<Day> { self.released.as_ref()?.parse().ok() }
+
+    /// Gets the height and code thresholds based on this sinner's data, with
+    /// the formulas in `config`
+    pub fn thresholds(&self, config: &ThresholdConfig) -> Thresholds {
         Thresholds {
-            code: self.code.map(|code| {
-                Threshold {
-                    near: 5. + f32::from(code) * 0.1,
-                    far: 50. + f32::from(code) * 0.35,
-                }
-            }),
-            height: Threshold {
-                near: 3. + f32::from((i16::from(self.height) - MOST_COMMON_HEIGHT).abs()) * 0.1,
-                far: 15. + f32::from((i16::from(self.height) - MOST_COMMON_HEIGHT).abs()) * 0.35,
-            },
+            code: self.code.map(|code| config.code(code)),
+            height: config.height(self.height),
         }
     }
 }
 
-pub const MOST_COMMON_HEIGHT: i16 = 168;
+pub const MOST_COMMON_HEIGHT: u8 = 168;
 
 /// A filter to apply to the sinner data to narrow the initial pool of sinners
 #[derive(Default, Debug)]
 pub struct Filter {
     pub names: HashSet<String>,
     pub matches: NameAndGuesses,
+    /// Sinners to leave out of the pool, such as ones the website doesn't have
+    /// yet
+    pub exclude: HashSet<String>,
+    /// If not empty, the only sinners that can be in the pool
+    pub only: HashSet<String>,
+    /// Leaves out sinners released after this day, to get the pool as it was
+    /// back then
+    pub as_of: Option<Day>,
+    /// The formulas the guesses in `matches` are compared with
+    pub thresholds: ThresholdConfig,
+}
+
+/// The names of the sinners in `data` that `names` refer to, warning about
+/// names that don't match any sinner
+fn resolve_names(
+    context: &Context,
+    data: &[Sinner],
+    names: &HashSet<String>,
+    place: &str,
+) -> HashSet<String> {
+    names
+        .iter()
+        .filter_map(|name| {
+            find_sinner(data, name, context.names)
+                .inspect_err(|e| warning!(context, "In {place}: {e}"))
+                .ok()
+                .map(|sinner| sinner.name.clone())
+        })
+        .collect()
 }
+
 impl Filter {
-    pub fn apply(&self, data: &mut Vec<Sinner>) {
-        if self.names.is_empty() && self.matches.0.is_empty() {
+    /// Whether the filter has the conditions from `--filter`, as opposed to
+    /// only narrowing down the pool
+    pub fn has_conditions(&self) -> bool { !self.names.is_empty() || !self.matches.0.is_empty() }
+
+    pub fn apply(&self, context: &Context, data: &mut Vec<Sinner>) {
+        if !self.has_conditions() &&
+            self.exclude.is_empty() &&
+            self.only.is_empty() &&
+            self.as_of.is_none()
+        {
             return;
         }
-        if !self.names.is_empty() {
-            data.retain(|sinner| self.names.contains(&sinner.name.to_ascii_lowercase()));
-        }
-
-        // In hind sight, maybe using a hashmap to store sinner data would've been
-        // better. I mean it doesn't really matter because there's less than 200 entries
-        let sinners_to_guess = data
+        // Names are resolved against all the data, so that a guess can narrow the
+        // pool even if the sinner guessed isn't in it
+        let names = resolve_names(context, data, &self.names, "the filter");
+        let only = resolve_names(context, data, &self.only, "--only");
+        let exclude = resolve_names(context, data, &self.exclude, "--exclude");
+        let sinners_to_guess = self
+            .matches
+            .0
             .iter()
-            .filter_map(|sinner| {
-                self.matches
-                    .0
-                    .iter()
-                    .find(|x| x.name.eq_ignore_ascii_case(&sinner.name))
-                    .map(|guess| (sinner.clone(), guess.guess))
+            .filter_map(|NameAndGuess { name, guess }| {
+                match find_sinner(data, name, context.names) {
+                    | Ok(sinner) => Some((sinner.clone(), *guess)),
+                    | Err(e) => {
+                        warning!(context, "In the filter: {e}");
+                        None
+                    },
+                }
             })
             .collect::<Vec<_>>();
+        data.retain(|sinner| {
+            (self.names.is_empty() || names.contains(&sinner.name)) &&
+                (self.only.is_empty() || only.contains(&sinner.name)) &&
+                !exclude.contains(&sinner.name)
+        });
+        if let Some(as_of) = self.as_of {
+            if data.iter().all(|x| x.release_day().is_none()) {
+                warning!(
+                    context,
+                    "The sinner data has no release dates, so --as-of doesn't leave anyone out. \
+                     Use --data or --dataset with data that has them."
+                );
+            }
+            // Sinners without a release date are assumed to have always been there
+            data.retain(|x| x.release_day().is_none_or(|day| day <= as_of));
+        }
 
         for (sinner, guess) in sinners_to_guess {
-            data.retain(|x| sinner.matches_result(guess, x) && x.code != sinner.code);
+            data.retain(|x| {
+                sinner.matches_result(guess, x, &self.thresholds) && x.code != sinner.code
+            });
         }
     }
 }
@@ -163,50 +294,270 @@ impl FromStr for Filter {
         if let Some((names, guesses)) = s.split_once(';') {
             let matches = guesses.parse()?;
             let names = parse_names(names);
-            Ok(Self { names, matches })
+            Ok(Self {
+                names,
+                matches,
+                ..Self::default()
+            })
         } else {
             Ok(s.parse().map_or_else(
                 |_| {
                     Self {
                         names: parse_names(s),
-                        matches: <_>::default(),
+                        ..Self::default()
                     }
                 },
                 |matches| {
                     Self {
-                        names: <_>::default(),
                         matches,
+                        ..Self::default()
                     }
                 },
             ))
         }
     }
 }
-fn cache_dir() -> PathBuf {
-    dirs::cache_dir().map_or_else(
-        || "path-to-nowordle-cli-cache".into(),
-        |x| x.join("Path-To-Nowordle-CLI"),
-    )
+pub fn make_and_get_cache_dir(game: &GameProfile) -> eyre::Result<PathBuf> {
+    let cache = cache_dir(game);
+    std::fs::create_dir_all(&cache).map_err(|e| {
+        Error::Io(format!(
+            "Failed to create the sinner cache directory {}: {e}",
+            cache.display()
+        ))
+    })?;
+    Ok(cache)
 }
 
-fn make_and_get_cache_dir() -> eyre::Result<PathBuf> {
-    let cache = cache_dir();
-    std::fs::create_dir_all(&cache).with_context(|| "Failed to create sinner cache directory")?;
-    Ok(cache)
+/// A hash of the sinner data that stays the same between runs, used to tell
+/// when data computed from the sinners is outdated
+pub fn data_hash(sinners: &[Sinner]) -> u64 {
+    // FNV-1a
+    sinners
+        .iter()
+        .flat_map(|sinner| facet_json::to_string(sinner).into_bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
 }
 
-fn load_sinners_from_json(bytes: &[u8]) -> eyre::Result<Vec<Sinner>> {
-    let raw_sinners = facet_json::from_slice::<Vec<RawSinner>>(bytes).map_err(|e| eyre!("{e}"))?;
-    raw_sinners
-        .into_iter()
-        .map(RawSinner::into_sinner)
-        .collect()
+/// Parses sinner data in either the website's format or the format this
+/// program writes. Sinners that are skipped are warned about to `reporter`.
+pub fn load_sinners_from_json(bytes: &[u8], reporter: &dyn Reporter) -> eyre::Result<Vec<Sinner>> {
+    // facet-json can get stuck on some malformed JSON, so it's only given
+    // data that is well-formed
+    schema::check_syntax(bytes)?;
+    // The website's data has codes and heights as strings, while data written
+    // by this program, like the bundled fallback, has them parsed already
+    if let Ok(sinners) = facet_json::from_slice::<Vec<Sinner>>(bytes) {
+        if sinners
+            .iter()
+            .all(|x| x.released.is_none() || x.release_day().is_some())
+        {
+            return Ok(sinners);
+        }
+    }
+    if let Ok(raw_sinners) = facet_json::from_slice::<Vec<RawSinner>>(bytes) {
+        if let Ok(sinners) = raw_sinners
+            .into_iter()
+            .map(RawSinner::into_sinner)
+            .collect()
+        {
+            return Ok(sinners);
+        }
+    }
+    // Something in the data is off, so check each sinner to tell exactly what
+    schema::validate(bytes, reporter)
 }
 
 static FALLBACK_SINNER_DATA: &[u8] = include_bytes!("../sinners.json");
-static SINNER_DATA_URL: &str = "https://raw.githubusercontent.com/Kaseioo/pathtonowordle/refs/heads/main/src/character_data/characters.json";
 
-fn is_cache_outdated<P: AsRef<Path>>(path: P) -> bool {
+/// The sinner data bundled with the program, which only Path to Nowordle has
+fn bundled_data(game: &GameProfile) -> eyre::Result<Vec<u8>> {
+    if game.is_builtin() {
+        Ok(FALLBACK_SINNER_DATA.to_vec())
+    } else {
+        Err(Error::DataFetch(format!(
+            "There is no cached character data for {}, and only Path to Nowordle has data bundled \
+             with the program",
+            game.title
+        ))
+        .into())
+    }
+}
+/// Where the website gets its sinner data from
+pub const DEFAULT_SINNER_DATA_URL: &str = "https://raw.githubusercontent.com/Kaseioo/pathtonowordle/refs/heads/main/src/character_data/characters.json";
+
+/// The path of the cached sinner data
+pub fn sinner_cache_path(game: &GameProfile) -> PathBuf { cache_dir(game).join("sinners.json") }
+
+/// Replaces the cached sinner data with `json`, which is used until it is
+/// older than the cache TTL
+pub fn write_cache(game: &GameProfile, json: &[u8]) -> eyre::Result<()> {
+    make_and_get_cache_dir(game)?;
+    let path = sinner_cache_path(game);
+    std::fs::write(&path, json)
+        .map_err(|e| Error::Io(format!("Failed to write {}: {e}", path.display())))?;
+    // The validators are for the data that was replaced, so the next fetch has
+    // to download everything
+    match std::fs::remove_file(CacheValidators::path(game)) {
+        | Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(Error::Io(format!("Failed to remove the cache validators: {e}")).into())
+        },
+        | _ => Ok(()),
+    }
+}
+
+/// The directory saved snapshots of the sinner data are kept in
+pub fn snapshot_dir(game: &GameProfile) -> PathBuf { cache_dir(game).join("snapshots") }
+
+/// The path of the snapshot called `name`. Names can only have letters,
+/// digits, `-`, `_` and `.`, so they can't point outside [`snapshot_dir`].
+pub fn snapshot_path(game: &GameProfile, name: &str) -> eyre::Result<PathBuf> {
+    let valid = !name.is_empty() &&
+        !name.starts_with('.') &&
+        name.chars()
+            .all(|x| x.is_ascii_alphanumeric() || matches!(x, '-' | '_' | '.'));
+    if !valid {
+        return Err(Error::InvalidInput(format!(
+            "Invalid snapshot name: `{name}`. Names can only have letters, digits, `-`, `_` and \
+             `.`"
+        ))
+        .into());
+    }
+    Ok(snapshot_dir(game).join(format!("{name}.json")))
+}
+
+/// Where the cached sinner data came from and the validators sent with it,
+/// used to check whether it changed without downloading it again
+#[derive(Debug, Default, Facet)]
+struct CacheValidators {
+    #[facet(default)]
+    url: Option<String>,
+    #[facet(default)]
+    etag: Option<String>,
+    #[facet(default)]
+    last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    fn path(game: &GameProfile) -> PathBuf { cache_dir(game).join("sinners.meta.json") }
+
+    /// Reads the validators of the cached data, if there are any
+    fn load(game: &GameProfile) -> Option<Self> {
+        let json = std::fs::read_to_string(Self::path(game)).ok()?;
+        facet_json::from_str(&json).ok()
+    }
+
+    fn from_response<B>(url: &str, response: &ureq::http::Response<B>) -> Self {
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|x| x.to_str().ok())
+                .map(str::to_owned)
+        };
+        Self {
+            url: Some(url.to_owned()),
+            etag: header("etag"),
+            last_modified: header("last-modified"),
+        }
+    }
+
+    fn save(&self, game: &GameProfile) {
+        // Without them the next fetch just downloads everything, so a failed
+        // write doesn't matter
+        _ = std::fs::write(Self::path(game), facet_json::to_string(self));
+    }
+}
+
+/// The URL the cached sinner data was fetched from, if it is known
+pub fn cached_data_url(game: &GameProfile) -> Option<String> { CacheValidators::load(game)?.url }
+
+/// The sinner data fetched from a URL, with the validators to save alongside
+/// it once it's cached
+type Fetched = (Vec<u8>, CacheValidators);
+
+/// Fetches the sinner data from `url`. If `conditional` is set and the cached
+/// data came from `url`, its validators are sent along, and `None` is returned
+/// if it hasn't changed.
+fn fetch_sinner_data(
+    game: &GameProfile,
+    url: &str,
+    conditional: bool,
+) -> Result<Option<Fetched>, ureq::Error> {
+    let validators = conditional
+        .then(|| CacheValidators::load(game))
+        .flatten()
+        .filter(|x| x.url.as_deref() == Some(url))
+        .unwrap_or_default();
+    let mut request = ureq::get(url);
+    if let Some(etag) = &validators.etag {
+        request = request.header("If-None-Match", etag);
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        request = request.header("If-Modified-Since", last_modified);
+    }
+    let mut response = request.call()?;
+    if response.status() == ureq::http::StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+    let json = response.body_mut().read_to_vec()?;
+    Ok(Some((json, CacheValidators::from_response(url, &response))))
+}
+
+/// How long the cached sinner data is used before it is fetched again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheTtl(pub Duration);
+
+impl Default for CacheTtl {
+    fn default() -> Self { Self(Duration::from_secs(24 * 3600)) }
+}
+
+impl std::fmt::Display for CacheTtl {
+    /// Writes the TTL in the largest unit that divides it, such as `12h`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let seconds = self.0.as_secs();
+        match [(24 * 60 * 60, 'd'), (60 * 60, 'h'), (60, 'm')]
+            .into_iter()
+            .find(|(unit, _)| seconds != 0 && seconds.is_multiple_of(*unit))
+        {
+            | Some((unit, suffix)) => write!(f, "{}{suffix}", seconds / unit),
+            | None => write!(f, "{seconds}s"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidCacheTtlError(String);
+
+impl FromStr for CacheTtl {
+    type Err = InvalidCacheTtlError;
+    /// Parses a number followed by `s`, `m`, `h` or `d`, such as `12h`. A
+    /// number alone is in seconds, so `0` always fetches.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (number, unit) = [('s', 1), ('m', 60), ('h', 60 * 60), ('d', 24 * 60 * 60)]
+            .into_iter()
+            .find_map(|(suffix, unit)| Some((s.strip_suffix(suffix)?, unit)))
+            .unwrap_or((s, 1));
+        number
+            .parse::<u64>()
+            .ok()
+            .and_then(|x| x.checked_mul(unit))
+            .map(|x| Self(Duration::from_secs(x)))
+            .ok_or_else(|| InvalidCacheTtlError(s.to_owned()))
+    }
+}
+impl std::fmt::Display for InvalidCacheTtlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Invalid cache TTL: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected a number followed by s, m, h or d, such as `12h`")
+    }
+}
+
+/// Whether the file at `path` is older than `ttl`, or doesn't exist
+pub fn is_cache_outdated<P: AsRef<Path>>(path: P, ttl: CacheTtl) -> bool {
     path.as_ref()
         .metadata()
         .map(|meta| {
@@ -214,7 +565,7 @@ fn is_cache_outdated<P: AsRef<Path>>(path: P) -> bool {
                 .map(|modified| {
                     SystemTime::now()
                         .duration_since(modified)
-                        .map(|x| x > Duration::from_secs(24 * 3600))
+                        .map(|x| x > ttl.0)
                         .unwrap_or(true)
                 })
                 .unwrap_or(true)
@@ -222,40 +573,229 @@ fn is_cache_outdated<P: AsRef<Path>>(path: P) -> bool {
         .unwrap_or(true)
 }
 
-pub fn load_sinners(force_update: bool, filter: &Filter) -> eyre::Result<Vec<Sinner>> {
-    let cache_path = make_and_get_cache_dir()?.join("sinners.json");
+/// Where to load the sinner data from
+#[derive(Debug, Clone)]
+pub enum DataSource {
+    /// The data the website uses, fetched at most once every `ttl` and cached
+    /// in between. `force_update` fetches it even if the cache is recent.
+    /// `urls` are tried in order until one of them works.
+    Remote {
+        force_update: bool,
+        ttl: CacheTtl,
+        urls: Vec<String>,
+    },
+    /// The cached data, or the data bundled with the program if there is no
+    /// cache, without ever touching the network
+    Offline,
+    /// A JSON file in the same format as the website's data, used as is
+    File(PathBuf),
+}
+
+impl DataSource {
+    /// Reads the raw JSON for the sinner data
+    fn read(&self, context: &Context) -> eyre::Result<Vec<u8>> {
+        match self {
+            | Self::Remote {
+                force_update,
+                ttl,
+                urls,
+            } => read_remote(context, *force_update, *ttl, urls),
+            | Self::Offline => read_offline(context),
+            | Self::File(path) => {
+                verbose!(context, "Reading the sinner data from {}", path.display());
+                std::fs::read(path).map_err(|e| {
+                    Error::Io(format!("Failed to read {}: {e}", path.display())).into()
+                })
+            },
+        }
+    }
+}
+
+/// Describes how long ago `time` was, such as `3 days`
+pub fn describe_age(time: SystemTime) -> Option<String> {
+    let seconds = SystemTime::now().duration_since(time).ok()?.as_secs();
+    Some(match seconds / 3600 {
+        | 0 => "less than an hour".to_owned(),
+        | 1 => "1 hour".to_owned(),
+        | hours @ 2..24 => format!("{hours} hours"),
+        | 24..48 => "1 day".to_owned(),
+        | hours => format!("{} days", hours / 24),
+    })
+}
+
+/// Reads the cached sinner data, or the bundled data if there is no cache,
+/// and notes how old the data is
+fn read_offline(context: &Context) -> eyre::Result<Vec<u8>> {
+    make_and_get_cache_dir(context.game)?;
+    let cache_path = sinner_cache_path(context.game);
+    if let Ok(json) = std::fs::read(&cache_path) {
+        let age = cache_path
+            .metadata()
+            .and_then(|x| x.modified())
+            .ok()
+            .and_then(describe_age);
+        match age {
+            | Some(age) => status!(context, "Offline: using sinner data cached {age} ago."),
+            | None => status!(context, "Offline: using cached sinner data of unknown age."),
+        }
+        return Ok(json);
+    }
+    let json = bundled_data(context.game)?;
+    status!(
+        context,
+        "Offline: no cached sinner data, using the data bundled with this version, which may be \
+         out of date."
+    );
+    Ok(json)
+}
+
+/// Fetches the sinner data from the first of `urls` that works and sends data
+/// that can be parsed. Returns `None` if the cached data from that URL hasn't
+/// changed, and an error if every URL failed.
+fn fetch_from_any(
+    context: &Context,
+    urls: &[String],
+    conditional: bool,
+) -> eyre::Result<Option<Fetched>> {
+    for (i, url) in urls.iter().enumerate() {
+        verbose!(context, "Fetching the sinner data from {url}");
+        let fetched = match fetch_sinner_data(context.game, url, conditional) {
+            | Ok(fetched) => fetched,
+            | Err(e) => {
+                warning!(context, "Failed to fetch sinner data from {url}: {e}");
+                continue;
+            },
+        };
+        // A mirror that answers with an error page or broken data is skipped
+        // like one that is down, so that it never replaces the cache. Sinners
+        // that are skipped are warned about once the data is loaded.
+        if let Some((json, _)) = &fetched {
+            if let Err(e) = load_sinners_from_json(json, &Silent) {
+                warning!(context, "The sinner data from {url} can't be used: {e}");
+                continue;
+            }
+        }
+        if i > 0 {
+            status!(context, "Fetched the sinner data from {url}");
+        }
+        return Ok(fetched);
+    }
+    Err(Error::DataFetch("Failed to fetch the sinner data from every data URL".to_owned()).into())
+}
+
+/// Fetches the latest sinner data from the first of `urls` that works, without
+/// touching the cache
+pub fn fetch_sinners(context: &Context, urls: &[String]) -> eyre::Result<Vec<Sinner>> {
+    let (json, _) = fetch_from_any(context, urls, false)?
+        .ok_or_else(|| Error::DataFetch("No data was fetched".to_owned()))?;
+    load_sinners_from_json(&json, context)
+}
+
+/// Fetches the sinner data if the cache is outdated, falling back to the cache
+/// and then the bundled data
+fn read_remote(
+    context: &Context,
+    force_update: bool,
+    ttl: CacheTtl,
+    urls: &[String],
+) -> eyre::Result<Vec<u8>> {
+    make_and_get_cache_dir(context.game)?;
+    let cache_path = sinner_cache_path(context.game);
     let load_cache = || {
-        std::fs::read(&cache_path).unwrap_or_else(|e| {
-            eprintln!("[WARNING] Could not read cache: {e}. Falling back to hard-coded data.");
-            FALLBACK_SINNER_DATA.to_vec()
+        std::fs::read(&cache_path).or_else(|e| {
+            warning!(
+                context,
+                "Could not read cache: {e}. Falling back to hard-coded data."
+            );
+            bundled_data(context.game)
         })
     };
 
-    let json = if force_update || is_cache_outdated(&cache_path) {
-        if let Ok(json) = ureq::get(SINNER_DATA_URL)
-            .call()
-            .map(|mut x| x.body_mut().read_to_vec())
-            .and_then(|x| x)
-            .inspect_err(|e| {
-                eprintln!(
-                    "[WARNING]: Failed to update sinner data: {e}. Falling back to reading cache \
-                     instead."
-                );
-            })
-        {
-            // I don't care if the write fails... just try
-            _ = std::fs::write(&cache_path, &json);
-            json
+    // How long ago the cache was fetched, for diagnostics
+    let age = || {
+        cache_path
+            .metadata()
+            .and_then(|x| x.modified())
+            .ok()
+            .and_then(describe_age)
+            .map_or_else(String::new, |age| format!(" (fetched {age} ago)"))
+    };
+    let json = if force_update || is_cache_outdated(&cache_path, ttl) {
+        // Forcing an update downloads everything, in case the cache is broken
+        let conditional = !force_update && cache_path.exists();
+        if force_update {
+            verbose!(
+                context,
+                "Fetching the sinner data because --force-cache-update was given"
+            );
+        } else if conditional {
+            verbose!(
+                context,
+                "The cached sinner data{} is older than the TTL of {ttl}, so it is fetched again \
+                 if it changed",
+                age()
+            );
         } else {
-            load_cache()
+            verbose!(
+                context,
+                "There is no cached sinner data, so it will be fetched"
+            );
+        }
+        match fetch_from_any(context, urls, conditional) {
+            | Ok(Some((json, validators))) => {
+                // I don't care if the write fails... just try
+                if std::fs::write(&cache_path, &json).is_ok() {
+                    validators.save(context.game);
+                }
+                json
+            },
+            | Ok(None) => {
+                verbose!(
+                    context,
+                    "The sinner data hasn't changed since it was cached"
+                );
+                // The data hasn't changed, so the cache is good for another TTL
+                _ = std::fs::File::options()
+                    .write(true)
+                    .open(&cache_path)
+                    .and_then(|x| x.set_modified(SystemTime::now()));
+                load_cache()?
+            },
+            | Err(e) => {
+                warning!(
+                    context,
+                    "Failed to update sinner data: {e}. Falling back to reading cache instead."
+                );
+                load_cache()?
+            },
         }
     } else {
-        load_cache()
+        verbose!(
+            context,
+            "Using the cached sinner data{}, which is within the TTL of {ttl}",
+            age()
+        );
+        load_cache()?
     };
-    let mut sinners = load_sinners_from_json(&json)?;
-    filter.apply(&mut sinners);
+    Ok(json)
+}
+
+pub fn load_sinners(
+    context: &Context,
+    source: &DataSource,
+    filter: &Filter,
+) -> eyre::Result<Vec<Sinner>> {
+    let json = source.read(context)?;
+    let mut sinners = load_sinners_from_json(&json, context)?;
+    let total = sinners.len();
+    filter.apply(context, &mut sinners);
+    verbose!(
+        context,
+        "{} of the {total} sinners in the data are in the pool",
+        sinners.len()
+    );
     if sinners.is_empty() {
-        return Err(eyre!("Filter does not match any sinners"));
+        return Err(Error::InvalidInput("Filter does not match any sinners".to_owned()).into());
     }
     Ok(sinners)
 }
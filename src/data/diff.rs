@@ -0,0 +1,101 @@
+//! Comparing two versions of the sinner data, to see what an update changed
+
+use facet::Facet;
+
+use super::Sinner;
+
+/// A field of a sinner that differs between two versions of the data
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// A sinner that is in both versions of the data with different fields
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+pub struct SinnerChange {
+    pub name: String,
+    pub fields: Vec<FieldChange>,
+}
+
+/// Everything that differs between two versions of the sinner data. Sinners
+/// are matched by name.
+#[derive(Debug, Clone, Default, PartialEq, Facet)]
+pub struct DataDiff {
+    pub added: Vec<Sinner>,
+    pub removed: Vec<Sinner>,
+    pub changed: Vec<SinnerChange>,
+}
+
+impl DataDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Each field of `sinner` other than the name, as `(field, value)`
+fn fields(sinner: &Sinner) -> [(&'static str, String); 6] {
+    [
+        (
+            "code",
+            sinner
+                .code
+                .map_or_else(|| "NOX".to_owned(), |x| x.to_string()),
+        ),
+        ("alignment", format!("{:?}", sinner.alignment)),
+        ("tendency", format!("{:?}", sinner.tendency)),
+        ("height", format!("{}cm", sinner.height)),
+        ("birthplace", format!("{:?}", sinner.birthplace)),
+        (
+            "released",
+            sinner
+                .released
+                .clone()
+                .unwrap_or_else(|| "unknown".to_owned()),
+        ),
+    ]
+}
+
+/// The fields that differ between `old` and `new`
+fn field_changes(old: &Sinner, new: &Sinner) -> Vec<FieldChange> {
+    fields(old)
+        .into_iter()
+        .zip(fields(new))
+        .filter(|((_, old), (_, new))| old != new)
+        .map(|((field, old), (_, new))| {
+            FieldChange {
+                field: field.to_owned(),
+                old,
+                new,
+            }
+        })
+        .collect()
+}
+
+/// Finds the sinners added to, removed from and changed in `old` to make
+/// `new`
+pub fn diff(old: &[Sinner], new: &[Sinner]) -> DataDiff {
+    let find = |sinners: &'_ [Sinner], name: &str| sinners.iter().position(|x| x.name == name);
+    let mut result = DataDiff::default();
+    for sinner in new {
+        match find(old, &sinner.name) {
+            | Some(i) => {
+                let fields = field_changes(&old[i], sinner);
+                if !fields.is_empty() {
+                    result.changed.push(SinnerChange {
+                        name: sinner.name.clone(),
+                        fields,
+                    });
+                }
+            },
+            | None => result.added.push(sinner.clone()),
+        }
+    }
+    result.removed = old
+        .iter()
+        .filter(|x| find(new, &x.name).is_none())
+        .cloned()
+        .collect();
+    result
+}
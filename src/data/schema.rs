@@ -0,0 +1,422 @@
+//! Checks the sinner data one sinner at a time, so that a change to its format
+//! is reported as the sinner and field that are wrong instead of as a parse
+//! error somewhere in the file
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::{Sinner, UnknownVariantError};
+use crate::daily::Day;
+use crate::error::Error;
+use crate::verbosity::Reporter;
+use crate::warning;
+
+/// A JSON value, with numbers kept as they were written
+enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Display for Value {
+    /// Writes the value as it would appear in an error message
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            | Self::Null => f.write_str("null"),
+            | Self::Bool(x) => write!(f, "{x}"),
+            | Self::Number(x) => f.write_str(x),
+            | Self::String(x) => write!(f, "{x:?}"),
+            | Self::Array(_) => f.write_str("a list"),
+            | Self::Object(_) => f.write_str("an object"),
+        }
+    }
+}
+
+/// How deeply lists and objects can be nested. The sinner data only goes 2
+/// deep, and anything much deeper would overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+/// Reads JSON into [`Value`]s, keeping track of where it is for errors
+struct Parser<'input> {
+    bytes: &'input [u8],
+    pos: usize,
+    /// The number of lists and objects the parser is in
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: impl Display) -> eyre::Report {
+        let before = &self.bytes[..self.pos.min(self.bytes.len())];
+        let line = before.split(|x| *x == b'\n').count();
+        let column = before.iter().rev().take_while(|x| **x != b'\n').count() + 1;
+        Error::Parse(format!(
+            "Invalid JSON at line {line}, column {column}: {message}"
+        ))
+        .into()
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(u8::is_ascii_whitespace)
+        {
+            self.pos += 1;
+        }
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> eyre::Result<()> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(format_args!("expected `{}`", char::from(byte))))
+        }
+    }
+
+    fn value(&mut self) -> eyre::Result<Value> {
+        match self.peek() {
+            | Some(b'{' | b'[') if self.depth >= MAX_DEPTH => {
+                Err(self.error("lists and objects are nested too deeply"))
+            },
+            | Some(b'{') => self.object(),
+            | Some(b'[') => self.array(),
+            | Some(b'"') => self.string().map(Value::String),
+            | Some(b'-' | b'0'..=b'9') => self.number(),
+            | Some(b't') => self.literal("true", Value::Bool(true)),
+            | Some(b'f') => self.literal("false", Value::Bool(false)),
+            | Some(b'n') => self.literal("null", Value::Null),
+            | Some(_) => Err(self.error("expected a value")),
+            | None => Err(self.error("the data ended early")),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Value) -> eyre::Result<Value> {
+        if self.bytes[self.pos..].starts_with(word.as_bytes()) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            Err(self.error("expected a value"))
+        }
+    }
+
+    fn number(&mut self) -> eyre::Result<Value> {
+        let start = self.pos;
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(|x| matches!(x, b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'))
+        {
+            self.pos += 1;
+        }
+        let number = String::from_utf8_lossy(&self.bytes[start..self.pos]).into_owned();
+        if number.parse::<f64>().is_err() {
+            return Err(self.error(format_args!("`{number}` isn't a number")));
+        }
+        Ok(Value::Number(number))
+    }
+
+    fn hex_escape(&mut self) -> eyre::Result<u32> {
+        let hex = self
+            .bytes
+            .get(self.pos..self.pos + 4)
+            .and_then(|x| std::str::from_utf8(x).ok())
+            .and_then(|x| u32::from_str_radix(x, 16).ok())
+            .ok_or_else(|| self.error("invalid \\u escape"))?;
+        self.pos += 4;
+        Ok(hex)
+    }
+
+    fn string(&mut self) -> eyre::Result<String> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            let Some(&byte) = self.bytes.get(self.pos) else {
+                return Err(self.error("the data ended in the middle of a string"));
+            };
+            self.pos += 1;
+            let escaped = match byte {
+                | b'"' => break,
+                | b'\\' => {
+                    let Some(&escape) = self.bytes.get(self.pos) else {
+                        return Err(self.error("the data ended in the middle of a string"));
+                    };
+                    self.pos += 1;
+                    match escape {
+                        | b'"' | b'\\' | b'/' => char::from(escape),
+                        | b'b' => '\u{8}',
+                        | b'f' => '\u{c}',
+                        | b'n' => '\n',
+                        | b'r' => '\r',
+                        | b't' => '\t',
+                        | b'u' => {
+                            let mut code = self.hex_escape()?;
+                            // Characters outside the BMP are written as a surrogate pair
+                            if (0xd800..0xdc00).contains(&code) &&
+                                self.bytes[self.pos..].starts_with(b"\\u")
+                            {
+                                let start = self.pos;
+                                self.pos += 2;
+                                let low = self.hex_escape()?;
+                                if (0xdc00..0xe000).contains(&low) {
+                                    code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                                } else {
+                                    // The high surrogate is on its own, and
+                                    // the escape after it is read by itself
+                                    self.pos = start;
+                                }
+                            }
+                            char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+                        },
+                        | _ => return Err(self.error("invalid escape in a string")),
+                    }
+                },
+                | byte => {
+                    bytes.push(byte);
+                    continue;
+                },
+            };
+            bytes.extend_from_slice(escaped.encode_utf8(&mut [0; 4]).as_bytes());
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("a string isn't valid UTF-8"))
+    }
+
+    /// Reads `open`, then values read by `item` separated by commas, then
+    /// `close`
+    fn list<T>(
+        &mut self,
+        open: u8,
+        close: u8,
+        mut item: impl FnMut(&mut Self) -> eyre::Result<T>,
+    ) -> eyre::Result<Vec<T>> {
+        self.expect(open)?;
+        let mut items = Vec::new();
+        if self.peek() == Some(close) {
+            self.pos += 1;
+            return Ok(items);
+        }
+        self.depth += 1;
+        loop {
+            items.push(item(self)?);
+            match self.peek() {
+                | Some(b',') => self.pos += 1,
+                | Some(x) if x == close => {
+                    self.pos += 1;
+                    self.depth -= 1;
+                    return Ok(items);
+                },
+                | _ => {
+                    return Err(self.error(format_args!("expected `,` or `{}`", char::from(close))))
+                },
+            }
+        }
+    }
+
+    fn array(&mut self) -> eyre::Result<Value> {
+        self.list(b'[', b']', Self::value).map(Value::Array)
+    }
+
+    fn object(&mut self) -> eyre::Result<Value> {
+        self.list(b'{', b'}', |parser| {
+            let key = parser.string()?;
+            parser.expect(b':')?;
+            Ok((key, parser.value()?))
+        })
+        .map(Value::Object)
+    }
+}
+
+/// Everything wrong with one sinner in the data
+struct SinnerError {
+    /// The position of the sinner in the data, counting from 0
+    index: usize,
+    name: Option<String>,
+    problems: Vec<String>,
+}
+
+impl Display for SinnerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Sinner #{}", self.index + 1)?;
+        if let Some(name) = &self.name {
+            write!(f, " (`{name}`)")?;
+        }
+        write!(f, ": {}", self.problems.join("; "))
+    }
+}
+
+/// The value of the field `key`, or a problem saying it's missing
+fn field<'record>(record: &'record [(String, Value)], key: &str) -> Result<&'record Value, String> {
+    record
+        .iter()
+        .find(|(x, _)| x == key)
+        .map(|(_, value)| value)
+        .ok_or_else(|| format!("`{key}` is missing"))
+}
+
+fn name(record: &[(String, Value)]) -> Result<String, String> {
+    match field(record, "name")? {
+        | Value::String(name) if !name.trim().is_empty() => Ok(name.clone()),
+        | value => Err(format!("`name` is {value}, expected a name")),
+    }
+}
+
+fn code(record: &[(String, Value)]) -> Result<Option<u16>, String> {
+    match field(record, "code")? {
+        // Codes that aren't numbers, like NOX's, are allowed. Data written by
+        // this program has them as null.
+        | Value::String(code) => Ok(code.parse().ok()),
+        | Value::Null => Ok(None),
+        | Value::Number(code) => {
+            code.parse()
+                .map(Some)
+                .map_err(|_| format!("`code` is {code}, expected a whole number up to 65535"))
+        },
+        | value => Err(format!("`code` is {value}, expected a string or number")),
+    }
+}
+
+fn height(record: &[(String, Value)]) -> Result<u8, String> {
+    let value = field(record, "height")?;
+    let height = match value {
+        | Value::String(height) => height.trim().strip_suffix("cm").map(str::trim),
+        | Value::Number(height) => Some(height.as_str()),
+        | _ => None,
+    };
+    height
+        .and_then(|x| x.parse().ok())
+        .ok_or_else(|| format!("`height` is {value}, expected a height in cm such as \"168cm\""))
+}
+
+/// The release date, which is optional
+fn released(record: &[(String, Value)]) -> Result<Option<String>, String> {
+    match field(record, "released") {
+        | Err(_) | Ok(Value::Null) => Ok(None),
+        | Ok(Value::String(released)) => {
+            released
+                .parse::<Day>()
+                .map(|_| Some(released.clone()))
+                .map_err(|e| format!("`released` is {released:?}. {e}"))
+        },
+        | Ok(value) => {
+            Err(format!(
+                "`released` is {value}, expected a date such as \"2024-06-01\""
+            ))
+        },
+    }
+}
+
+/// The value of the field `key`, which is the name of a variant of `T`
+fn variant<T: FromStr<Err = UnknownVariantError>>(
+    record: &[(String, Value)],
+    key: &str,
+) -> Result<T, String> {
+    match field(record, key)? {
+        | Value::String(name) => name.parse().map_err(|e: UnknownVariantError| e.to_string()),
+        | value => Err(format!("`{key}` is {value}, expected a string")),
+    }
+}
+
+/// Pulls the value out of `result`, or adds its problem to `problems`
+fn check<T>(problems: &mut Vec<String>, result: Result<T, String>) -> Option<T> {
+    result.map_err(|e| problems.push(e)).ok()
+}
+
+fn check_sinner(index: usize, value: &Value) -> Result<Sinner, SinnerError> {
+    let Value::Object(record) = value else {
+        return Err(SinnerError {
+            index,
+            name: None,
+            problems: vec![format!("expected an object, not {value}")],
+        });
+    };
+    let mut problems = Vec::new();
+    let name = check(&mut problems, name(record));
+    let code = check(&mut problems, code(record));
+    let alignment = check(&mut problems, variant(record, "alignment"));
+    let tendency = check(&mut problems, variant(record, "tendency"));
+    let height = check(&mut problems, height(record));
+    let birthplace = check(&mut problems, variant(record, "birthplace"));
+    let released = check(&mut problems, released(record));
+    match (
+        name, code, alignment, tendency, height, birthplace, released,
+    ) {
+        | (
+            Some(name),
+            Some(code),
+            Some(alignment),
+            Some(tendency),
+            Some(height),
+            Some(birthplace),
+            Some(released),
+        ) => {
+            Ok(Sinner {
+                name,
+                code,
+                alignment,
+                tendency,
+                height,
+                birthplace,
+                released,
+            })
+        },
+        | (name, ..) => {
+            Err(SinnerError {
+                index,
+                name,
+                problems,
+            })
+        },
+    }
+}
+
+/// Reads `bytes` as a single JSON value
+fn parse(bytes: &[u8]) -> eyre::Result<Value> {
+    let mut parser = Parser {
+        bytes,
+        pos: 0,
+        depth: 0,
+    };
+    let data = parser.value()?;
+    if parser.peek().is_some() {
+        return Err(parser.error("expected the data to end"));
+    }
+    Ok(data)
+}
+
+/// Fails with where the problem is if `bytes` isn't well-formed JSON
+pub fn check_syntax(bytes: &[u8]) -> eyre::Result<()> { parse(bytes).map(drop) }
+
+/// Reads the sinner data, skipping sinners with missing or invalid fields with
+/// a warning for each. Fails if the data isn't JSON, isn't a list, or has no
+/// valid sinners at all.
+pub fn validate(bytes: &[u8], reporter: &dyn Reporter) -> eyre::Result<Vec<Sinner>> {
+    let data = parse(bytes)?;
+    let Value::Array(values) = data else {
+        return Err(Error::Parse(format!(
+            "The sinner data should be a list of sinners, not {data}"
+        ))
+        .into());
+    };
+    let mut sinners = Vec::new();
+    let mut errors = Vec::new();
+    for (index, value) in values.iter().enumerate() {
+        match check_sinner(index, value) {
+            | Ok(sinner) => sinners.push(sinner),
+            | Err(e) => errors.push(e),
+        }
+    }
+    if sinners.is_empty() {
+        let first = errors
+            .first()
+            .map_or_else(|| "The list is empty".to_owned(), ToString::to_string);
+        return Err(Error::Parse(format!("The sinner data has no valid sinners. {first}")).into());
+    }
+    for e in errors {
+        warning!(reporter, "Skipping an invalid sinner in the data. {e}");
+    }
+    Ok(sinners)
+}
@@ -0,0 +1,251 @@
+//! The `data` command, for managing the sinner data itself
+
+use std::path::Path;
+
+use eyre::Context;
+use ptndle_core::data::diff::{diff, DataDiff};
+use ptndle_core::data::{describe_age, fetch_sinners, load_sinners, load_sinners_from_json,
+                        sinner_cache_path, snapshot_dir, snapshot_path, write_cache, DataSource,
+                        Sinner};
+use ptndle_core::error::Error;
+use ptndle_core::theme::Role;
+
+use crate::console::console;
+use crate::flags::{Data, DataCmd, Diff, Load, Save, Snapshot, SnapshotCmd};
+use crate::output::print_json;
+use crate::settings;
+use crate::sinners::row;
+
+/// Reads the sinner data in the file at `path`
+fn read_file(path: &Path) -> eyre::Result<Vec<Sinner>> {
+    let json = std::fs::read(path)
+        .map_err(|e| Error::Io(format!("Failed to read {}: {e}", path.display())))?;
+    load_sinners_from_json(&json, console())
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Reads the cached sinner data
+fn read_cache() -> eyre::Result<Vec<Sinner>> {
+    let path = sinner_cache_path(settings::game());
+    if !path.exists() {
+        return Err(Error::Io(
+            "There is no cached sinner data to compare with. Give two files to compare instead."
+                .to_owned(),
+        )
+        .into());
+    }
+    read_file(&path)
+}
+
+/// Reads the newest sinner data `source` knows of, without updating the cache
+fn read_latest(source: &DataSource) -> eyre::Result<(String, Vec<Sinner>)> {
+    match source {
+        | DataSource::Remote { urls, .. } => {
+            Ok((
+                "the latest data".to_owned(),
+                fetch_sinners(&settings::context(), urls)?,
+            ))
+        },
+        | DataSource::File(path) => Ok((path.display().to_string(), read_file(path)?)),
+        | DataSource::Offline => {
+            Err(Error::InvalidInput(
+                "Comparing with the latest data needs to fetch it, which --offline doesn't allow. \
+                 Give two files to compare instead."
+                    .to_owned(),
+            )
+            .into())
+        },
+    }
+}
+
+/// Describes `sinner` on one line, such as `Hella (98, Violence, Fury, 152cm,
+/// Syndicate)`
+fn describe(sinner: &Sinner) -> String {
+    let [name, fields @ ..] = row(sinner);
+    format!("{name} ({})", fields.join(", "))
+}
+
+fn print_diff(changes: &DataDiff) {
+    if changes.is_empty() {
+        println!("No differences");
+        return;
+    }
+    if !changes.added.is_empty() {
+        println!("{}", settings::paint("Added:", Role::Bold));
+        for sinner in &changes.added {
+            println!(
+                "  {}",
+                settings::paint(format!("+ {}", describe(sinner)), Role::Correct)
+            );
+        }
+    }
+    if !changes.removed.is_empty() {
+        println!("{}", settings::paint("Removed:", Role::Bold));
+        for sinner in &changes.removed {
+            println!(
+                "  {}",
+                settings::paint(format!("- {}", describe(sinner)), Role::Wrong)
+            );
+        }
+    }
+    if !changes.changed.is_empty() {
+        println!("{}", settings::paint("Changed:", Role::Bold));
+        for change in &changes.changed {
+            let fields = change
+                .fields
+                .iter()
+                .map(|x| format!("{} {} → {}", x.field, x.old, x.new))
+                .collect::<Vec<_>>();
+            println!(
+                "  {} {}",
+                settings::paint(format!("~ {}:", change.name), Role::Near),
+                fields.join(", ")
+            );
+        }
+    }
+}
+
+/// Compares two versions of the sinner data: the files given, the cache with
+/// the file given, or the cache with the latest data
+fn run_diff(Diff { old, new }: Diff, source: &DataSource, json: bool) -> eyre::Result<()> {
+    let (old_name, old_sinners, new_name, new_sinners) = match (old, new) {
+        | (Some(old), Some(new)) => {
+            (
+                old.display().to_string(),
+                read_file(&old)?,
+                new.display().to_string(),
+                read_file(&new)?,
+            )
+        },
+        | (Some(new), None) => {
+            (
+                "the cache".to_owned(),
+                read_cache()?,
+                new.display().to_string(),
+                read_file(&new)?,
+            )
+        },
+        | (None, _) => {
+            let (new_name, new_sinners) = read_latest(source)?;
+            ("the cache".to_owned(), read_cache()?, new_name, new_sinners)
+        },
+    };
+    let changes = diff(&old_sinners, &new_sinners);
+    if json {
+        print_json(&changes);
+        return Ok(());
+    }
+    println!(
+        "Comparing {old_name} ({} sinners) with {new_name} ({} sinners)",
+        old_sinners.len(),
+        new_sinners.len()
+    );
+    print_diff(&changes);
+    Ok(())
+}
+
+/// The source for `--dataset name`
+pub fn snapshot_source(name: &str) -> eyre::Result<DataSource> {
+    let path = snapshot_path(settings::game(), name)?;
+    if !path.exists() {
+        return Err(Error::InvalidInput(format!(
+            "There is no snapshot called `{name}`. See `ptndle-cli data snapshot list`"
+        ))
+        .into());
+    }
+    Ok(DataSource::File(path))
+}
+
+/// Saves the sinner data from `source` as a new snapshot
+fn save(Save { name }: Save, source: &DataSource) -> eyre::Result<()> {
+    let path = snapshot_path(settings::game(), &name)?;
+    if path.exists() {
+        return Err(Error::InvalidInput(format!(
+            "There is already a snapshot called `{name}`, and snapshots can't be changed. Pick \
+             another name."
+        ))
+        .into());
+    }
+    let sinners = load_sinners(&settings::context(), source, &<_>::default())?;
+    std::fs::create_dir_all(snapshot_dir(settings::game()))
+        .map_err(|e| Error::Io(format!("Failed to create snapshot directory: {e}")))?;
+    std::fs::write(&path, facet_json::to_string(&sinners))
+        .map_err(|e| Error::Io(format!("Failed to write {}: {e}", path.display())))?;
+    println!(
+        "Saved {} sinners as `{name}`. Use them with --dataset {name}",
+        sinners.len()
+    );
+    Ok(())
+}
+
+/// Replaces the cache with a snapshot
+fn load(Load { name }: Load) -> eyre::Result<()> {
+    let path = snapshot_path(settings::game(), &name)?;
+    let json = std::fs::read(&path)
+        .map_err(|_| Error::InvalidInput(format!("There is no snapshot called `{name}`")))?;
+    let sinners = load_sinners_from_json(&json, console())?;
+    write_cache(settings::game(), &json)?;
+    println!(
+        "Loaded {} sinners from `{name}` into the cache. They're used until the cache is next \
+         updated.",
+        sinners.len()
+    );
+    Ok(())
+}
+
+fn list() -> eyre::Result<()> {
+    let mut snapshots = match std::fs::read_dir(snapshot_dir(settings::game())) {
+        | Ok(entries) => {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| {
+                    let name = entry
+                        .file_name()
+                        .to_str()?
+                        .strip_suffix(".json")?
+                        .to_owned();
+                    Some((name, entry.path()))
+                })
+                .collect::<Vec<_>>()
+        },
+        | Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        | Err(e) => return Err(Error::Io(format!("Failed to read snapshot directory: {e}")).into()),
+    };
+    if snapshots.is_empty() {
+        println!(
+            "No snapshots have been saved. Save one with `ptndle-cli data snapshot save <name>`"
+        );
+        return Ok(());
+    }
+    snapshots.sort();
+    for (name, path) in snapshots {
+        let sinners = read_file(&path).map_or_else(
+            |_| "unreadable".to_owned(),
+            |x| format!("{} sinners", x.len()),
+        );
+        let age = path
+            .metadata()
+            .and_then(|x| x.modified())
+            .ok()
+            .and_then(describe_age)
+            .map_or_else(String::new, |x| format!(", saved {x} ago"));
+        println!(
+            "{name}  {}",
+            settings::paint(format!("({sinners}{age})"), Role::Dim)
+        );
+    }
+    Ok(())
+}
+
+pub fn run(Data { subcommand }: Data, source: &DataSource, json: bool) -> eyre::Result<()> {
+    match subcommand {
+        | DataCmd::Diff(flags) => run_diff(flags, source, json),
+        | DataCmd::Snapshot(Snapshot { subcommand }) => {
+            match subcommand {
+                | SnapshotCmd::Save(flags) => save(flags, source),
+                | SnapshotCmd::Load(flags) => load(flags),
+                | SnapshotCmd::List(_) => list(),
+            }
+        },
+    }
+}
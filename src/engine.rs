@@ -0,0 +1,127 @@
+//! A line-based protocol for driving the solver from another program, in the
+//! spirit of UCI for chess engines
+
+use std::io::stdin;
+use std::sync::Arc;
+
+use ptndle_core::data::Sinner;
+use ptndle_core::fuzzy::find_sinner;
+use ptndle_core::guess::Guess;
+use ptndle_core::matrix::GuessMatrix;
+use ptndle_core::play::NameAndGuess;
+use ptndle_core::solver::SolverConfig;
+use ptndle_core::tree::DecisionTree;
+
+use crate::settings;
+use crate::shell::SolveState;
+
+/// The state of a game being solved for the host
+struct Engine<'tree> {
+    config: SolverConfig,
+    matrix: Arc<GuessMatrix>,
+    tree: Option<&'tree DecisionTree>,
+    state: SolveState<'tree>,
+    /// The last `bestguess` sent, which a `result` without a name applies to
+    last_guess: Option<Sinner>,
+}
+
+impl<'tree> Engine<'tree> {
+    fn new(
+        config: SolverConfig,
+        matrix: Arc<GuessMatrix>,
+        tree: Option<&'tree DecisionTree>,
+    ) -> Self {
+        Self {
+            state: SolveState::new(config, Arc::clone(&matrix), tree),
+            config,
+            matrix,
+            tree,
+            last_guess: None,
+        }
+    }
+
+    fn new_game(&mut self) {
+        self.state = SolveState::new(self.config, Arc::clone(&self.matrix), self.tree);
+        self.last_guess = None;
+    }
+
+    /// Applies `arg`, either a row for the last `bestguess` or `name: row`
+    fn result(&mut self, arg: &str) -> Result<(), String> {
+        let (sinner, guess) = if arg.contains(':') {
+            let NameAndGuess { name, guess } = arg.parse().map_err(|e| format!("{e}"))?;
+            let sinner = find_sinner(self.state.sinners(), &name, settings::names())
+                .map_err(|e| e.to_string())?;
+            (sinner.clone(), guess)
+        } else {
+            let guess = arg
+                .parse::<Guess>()
+                .map_err(|()| format!("Invalid row: `{arg}`"))?;
+            let sinner = self
+                .last_guess
+                .clone()
+                .ok_or("No guess to apply the row to. Send `go` first or use `name: row`")?;
+            (sinner, guess)
+        };
+        self.state.push(sinner, guess);
+        self.last_guess = None;
+        Ok(())
+    }
+
+    fn go(&mut self) {
+        let report = self.state.report(None, false);
+        println!("info candidates {}", report.candidates.len());
+        println!("candidates {}", report.candidates.join(","));
+        self.last_guess = self.state.recommendation().cloned();
+        match &self.last_guess {
+            | Some(sinner) if report.solved => println!("bestguess {} solved", sinner.name),
+            | Some(sinner) => println!("bestguess {}", sinner.name),
+            | None => println!("bestguess none"),
+        }
+    }
+
+    /// Handles one line from the host. Returns `false` once the host quits.
+    fn handle(&mut self, line: &str) -> bool {
+        let (command, arg) = line.split_once(' ').unwrap_or((line, ""));
+        match command {
+            | "ptndle" => {
+                println!("id name ptndle-cli {}", env!("CARGO_PKG_VERSION"));
+                println!("option strategy {}", self.config.strategy);
+                println!("option depth {}", self.config.depth);
+                println!("option probe {}", self.config.probe);
+                println!("ptndleok");
+            },
+            | "isready" => println!("readyok"),
+            | "newgame" => self.new_game(),
+            | "result" => {
+                if let Err(e) = self.result(arg.trim()) {
+                    println!("error {e}");
+                }
+            },
+            | "undo" => {
+                self.last_guess = None;
+                if self.state.undo().is_none() {
+                    println!("error Nothing to undo");
+                }
+            },
+            | "go" => self.go(),
+            | "quit" => return false,
+            | "" => {},
+            | _ => println!("error Unknown command: `{command}`"),
+        }
+        true
+    }
+}
+
+/// Reads commands from stdin and answers on stdout until `quit` or the end of
+/// input
+pub fn run(sinners: Vec<Sinner>, config: SolverConfig) -> eyre::Result<()> {
+    let matrix = Arc::new(GuessMatrix::new(sinners, *settings::thresholds()));
+    let tree = DecisionTree::load_or_build(&settings::context(), config, &matrix)?;
+    let mut engine = Engine::new(config, matrix, tree.as_ref());
+    for line in stdin().lines() {
+        if !engine.handle(line?.trim()) {
+            break;
+        }
+    }
+    Ok(())
+}
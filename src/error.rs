@@ -0,0 +1,86 @@
+//! The kinds of errors that scripts running ptndle-cli may want to tell apart,
+//! each with its own exit code
+
+use std::fmt::Display;
+
+use crate::fuzzy::UnknownSinnerError;
+
+/// An error of a kind with its own exit code, with the message saying what
+/// went wrong. Every other error exits with 1.
+#[derive(Debug)]
+pub enum Error {
+    /// The sinner data couldn't be fetched from any URL
+    DataFetch(String),
+    /// A file, such as the cached sinner data, couldn't be read or written
+    Io(String),
+    /// The sinner data couldn't be parsed
+    Parse(String),
+    /// The results given rule out every sinner
+    Contradiction(String),
+    /// A flag, name or other input isn't valid
+    InvalidInput(String),
+}
+
+impl Error {
+    /// The code the program exits with because of this error
+    pub const fn exit_code(&self) -> u8 {
+        match self {
+            | Self::InvalidInput(_) => 2,
+            | Self::DataFetch(_) => 3,
+            | Self::Io(_) => 4,
+            | Self::Parse(_) => 5,
+            | Self::Contradiction(_) => 6,
+        }
+    }
+
+    /// What can be done about the error, printed after the message
+    const fn guidance(&self) -> Option<&'static str> {
+        match self {
+            | Self::DataFetch(_) => {
+                Some("Check your internet connection, or use --offline to use the cached data.")
+            },
+            | Self::Io(_) => Some("Check that the file or directory exists and can be written to."),
+            | Self::Parse(_) => {
+                Some(
+                    "If ptndle.com changed the format of its data, --offline uses the cached data \
+                     instead.",
+                )
+            },
+            | Self::Contradiction(_) => {
+                Some("One of the results given is probably wrong. Check them against the game.")
+            },
+            | Self::InvalidInput(_) => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            | Self::DataFetch(message) |
+            Self::Io(message) |
+            Self::Parse(message) |
+            Self::Contradiction(message) |
+            Self::InvalidInput(message) => f.write_str(message)?,
+        }
+        match self.guidance() {
+            | Some(guidance) => write!(f, "\n{guidance}"),
+            | None => Ok(()),
+        }
+    }
+}
+impl std::error::Error for Error {}
+
+/// The code to exit with because of `report`: that of the first [`Error`] in
+/// its chain, 2 for a name that doesn't match any sinner, or 1 for anything
+/// else
+pub fn exit_code(report: &eyre::Report) -> u8 {
+    report
+        .chain()
+        .find_map(|x| {
+            x.downcast_ref::<Error>()
+                .map(Error::exit_code)
+                .or_else(|| x.is::<UnknownSinnerError>().then_some(2))
+        })
+        .unwrap_or(1)
+}
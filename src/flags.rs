@@ -1,19 +1,89 @@
 use std::fmt::Display;
+use std::path::PathBuf;
 use std::str::FromStr;
 
-use crate::data::Filter;
-use crate::play::{NameAndGuess, NameAndGuessError};
+use ptndle_core::challenge::Challenge;
+use ptndle_core::daily::Day;
+use ptndle_core::data::{Alignment, BirthPlace, CacheTtl, Filter, Tendency};
+use ptndle_core::error::Error;
+use ptndle_core::game::GameName;
+use ptndle_core::i18n::Lang;
+use ptndle_core::paths::Profile;
+use ptndle_core::play::NameAndGuesses;
+use ptndle_core::query::{HeightRange, Query, SortKey};
+use ptndle_core::registry::{StrategyName, UnknownPlayerError};
+use ptndle_core::solver::{Strategy, TieBreak, UnknownStrategyError};
+use ptndle_core::theme::Theme;
+use ptndle_core::verbosity::Verbosity;
 
-#[derive(Debug, Default)]
-pub struct NameAndGuesses(pub Vec<NameAndGuess>);
+use crate::config::{ConfigFile, ConfigKey};
+use crate::console::ProgressMode;
+use crate::settings;
+use crate::simulate::PlayerKinds;
+use crate::tournament::RankKey;
+
+/// A comma-separated list of strategies, or `all` for every strategy
+#[derive(Debug)]
+pub struct Strategies(pub Vec<Strategy>);
 
-impl FromStr for NameAndGuesses {
-    type Err = NameAndGuessError;
+impl FromStr for Strategies {
+    type Err = UnknownStrategyError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim() == "all" {
+            return Ok(Strategies(Strategy::ALL.to_vec()));
+        }
         s.split(',')
             .map(|s| s.trim().parse())
             .collect::<Result<_, Self::Err>>()
-            .map(NameAndGuesses)
+            .map(Strategies)
+    }
+}
+/// A comma-separated list of strategies or registered players, or `all` for
+/// every strategy of the solver
+#[derive(Debug)]
+pub struct StrategyNames(pub Vec<StrategyName>);
+
+impl FromStr for StrategyNames {
+    type Err = UnknownPlayerError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim() == "all" {
+            return Ok(StrategyNames(
+                Strategy::ALL.map(StrategyName::Solver).to_vec(),
+            ));
+        }
+        s.split(',')
+            .map(str::parse)
+            .collect::<Result<_, Self::Err>>()
+            .map(StrategyNames)
+    }
+}
+/// A comma-separated list of pool sizes
+#[derive(Debug)]
+pub struct Sizes(pub Vec<usize>);
+
+#[derive(Debug)]
+pub struct InvalidSizeError(String);
+
+impl FromStr for Sizes {
+    type Err = InvalidSizeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|x| {
+                x.trim()
+                    .parse()
+                    .ok()
+                    .filter(|&x| x > 0)
+                    .ok_or_else(|| InvalidSizeError(x.to_owned()))
+            })
+            .collect::<Result<_, _>>()
+            .map(Sizes)
+    }
+}
+impl Display for InvalidSizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Invalid pool size: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected a positive number of sinners")
     }
 }
 #[derive(Debug)]
@@ -21,11 +91,51 @@ pub enum HelpCommand {
     Gather,
     Solve,
     Play,
+    Stats,
+    Replay,
+    Tui,
+    Serve,
+    Engine,
+    Cache,
+    Sinners,
+    Info,
+    Bands,
+    Data,
+    Verify,
+    Selftest,
+    Completions,
+    Config,
+    Bench,
+    Simulate,
+    Tournament,
+    Openers,
+    Analyze,
     Help,
 }
 #[derive(Debug)]
 pub struct UnknownCommandError(String);
 
+/// What the `cache` command does
+#[derive(Debug, Default)]
+pub enum CacheAction {
+    #[default]
+    Status,
+    Clear,
+    Path,
+}
+
+impl FromStr for CacheAction {
+    type Err = UnknownCommandError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            | "status" => Self::Status,
+            | "clear" => Self::Clear,
+            | "path" => Self::Path,
+            | s => return Err(UnknownCommandError(s.to_owned())),
+        })
+    }
+}
+
 impl FromStr for HelpCommand {
     type Err = UnknownCommandError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -33,6 +143,25 @@ impl FromStr for HelpCommand {
             | "gather" => Self::Gather,
             | "solve" => Self::Solve,
             | "play" => Self::Play,
+            | "stats" => Self::Stats,
+            | "replay" => Self::Replay,
+            | "tui" => Self::Tui,
+            | "serve" => Self::Serve,
+            | "engine" => Self::Engine,
+            | "cache" => Self::Cache,
+            | "sinners" => Self::Sinners,
+            | "info" => Self::Info,
+            | "bands" => Self::Bands,
+            | "data" => Self::Data,
+            | "verify" => Self::Verify,
+            | "selftest" => Self::Selftest,
+            | "completions" => Self::Completions,
+            | "config" => Self::Config,
+            | "bench" => Self::Bench,
+            | "simulate" => Self::Simulate,
+            | "tournament" => Self::Tournament,
+            | "openers" => Self::Openers,
+            | "analyze" => Self::Analyze,
             | "help" => Self::Help,
             | s => return Err(UnknownCommandError(s.to_owned())),
         })
@@ -45,6 +174,85 @@ impl Display for UnknownCommandError {
         f.write_str("`")
     }
 }
+/// How text output is formatted, for where it will be read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// For the terminal
+    #[default]
+    Text,
+    /// For a Discord message, with markdown escaped and tables in code blocks
+    Discord,
+}
+
+impl Format {
+    pub const ALL: [Self; 2] = [Self::Text, Self::Discord];
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            | Self::Text => "text",
+            | Self::Discord => "discord",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownFormatError(String);
+
+impl FromStr for Format {
+    type Err = UnknownFormatError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.to_str() == s.trim().to_ascii_lowercase())
+            .ok_or_else(|| UnknownFormatError(s.to_owned()))
+    }
+}
+impl Display for UnknownFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Unknown format: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected `text` or `discord`")
+    }
+}
+/// A shell that `completions` can write a script for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+impl Shell {
+    pub const ALL: [Self; 4] = [Self::Bash, Self::Zsh, Self::Fish, Self::Powershell];
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            | Self::Bash => "bash",
+            | Self::Zsh => "zsh",
+            | Self::Fish => "fish",
+            | Self::Powershell => "powershell",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownShellError(String);
+
+impl FromStr for Shell {
+    type Err = UnknownShellError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.to_str() == s.trim().to_ascii_lowercase())
+            .ok_or_else(|| UnknownShellError(s.to_owned()))
+    }
+}
+impl Display for UnknownShellError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Unknown shell: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected one of `bash`, `zsh`, `fish` or `powershell`")
+    }
+}
 xflags::xflags! {
     /// A cli tool for both playing and solving games of Path to Nowordle (https://ptndle.com/),
     /// a game for guessing Path to Nowhere characters based on their characteristics.
@@ -52,9 +260,72 @@ xflags::xflags! {
     cmd ptndle-cli {
         /// Force-fetch the latest sinner data and store it in the cache.
         optional -f, --force-cache-update
+        /// Load the sinner data from a JSON file in the same format as ptndle.com's instead of
+        /// fetching it. Useful for testing upcoming sinners, fan-made data or offline machines.
+        optional --data path: PathBuf
+        /// Load the sinner data from a snapshot saved with `data snapshot save`, so results can
+        /// be reproduced after the data changes.
+        optional --dataset name: String
+        /// Never fetch the sinner data, even if the cache is outdated. The cache is used if there
+        /// is one, and the data bundled with the program otherwise.
+        optional --offline
+        /// A URL to fetch the sinner data from instead of ptndle.com's source. Can be given more
+        /// than once, in which case each URL is tried in order until one works.
+        repeated --data-url url: String
+        /// How long the cached sinner data is used before it is fetched again, such as `30m`,
+        /// `12h` or `7d`. Defaults to 24h.
+        optional --cache-ttl ttl: CacheTtl
         /// Filter the sinner data based on a comma-separated list of names, a comma separated list of
         /// name:guess, or both separated by ;. Example: `Hella,Shalom,Shawn;L.L.:vv 0 0 ^ 0`
         optional --filter filter: Filter
+        /// A comma-separated list of sinners to leave out of the pool, such as ones the website
+        /// doesn't have yet. Applies to every command, including the daily.
+        optional --exclude names: String
+        /// A file listing the only sinners in the pool, one per line or separated by commas, for
+        /// matching the website's pool. Lines starting with `#` are ignored.
+        optional --only path: PathBuf
+        /// Leave out sinners released after this date, such as `2024-06-01`, to use the pool as it
+        /// was back then. Needs sinner data with release dates.
+        optional --as-of date: Day
+        /// How the solver picks between equally good guesses: by `name` (default), by `code`,
+        /// or by the `order` of the sinner data. See `help solve`.
+        optional --tie-break rule: TieBreak
+        /// A Rhai script or program that picks guesses, which becomes a player named after the
+        /// file that can be given to --strategy and --players. Can be given more than once. See
+        /// `help gather`.
+        repeated --strategy-script path: PathBuf
+        /// A JSON file with the formulas for the near and far thresholds, for when ptndle.com
+        /// changes them. See `help info`.
+        optional --thresholds path: PathBuf
+        /// Print machine-readable JSON instead of text. Supported by `gather`, `solve
+        /// --non-interactive`, `play`, `stats`, `sinners`, `info`, `data diff` and `verify`.
+        optional --json
+        /// How to format the share text of `play` and the output of `solve --non-interactive`:
+        /// `text`, or `discord` to escape markdown and put tables in code blocks, so that bots
+        /// can post the output as it is.
+        optional --format format: Format
+        /// How to report the progress of long runs such as `gather` and `tournament` on stderr:
+        /// `bar`, `json` for a line of JSON per step, or `none`. Defaults to `bar` on a terminal.
+        optional --progress mode: ProgressMode
+        /// The colors to use: `default`, `colorblind`, `mono`, `plain` or
+        /// `custom:<correct>,<near>,<wrong>`. Defaults to `plain` if `NO_COLOR` is set.
+        optional --theme theme: Theme
+        /// The language of the text shown while playing and of the sinners' names, where they're
+        /// translated: `en`, `zh` or `ja`.
+        optional --lang lang: Lang
+        /// Use the stats, history, saved games and settings of this profile, such as `alice`,
+        /// so that people sharing a machine don't mix up each other's games.
+        optional --profile name: Profile
+        /// The sinner data to play with: `ptndle`, the default, or data in ptndle.com's format
+        /// described by a file in the `games` folder of the config folder, with its own
+        /// thresholds, stats and cache. See `help config`.
+        optional --game name: GameName
+        /// Only print results, warnings and errors, leaving out banners and instructions, for
+        /// scripts.
+        optional -q, --quiet
+        /// Also print diagnostics, such as where the sinner data came from, whether the cache was
+        /// used and how many sinners remain after each row.
+        optional -v, --verbose
          /// View in-depth help for a command
         cmd help {
             /// The command to view help for
@@ -62,15 +333,453 @@ xflags::xflags! {
         }
         /// Play every possible game of Path To Nowordle and gather statistical data about
         /// the solver's performance
-        cmd gather {}
+        cmd gather {
+            /// A comma-separated list of strategies to compare, or `all`. Strategies are `mean`
+            /// (default), `minimax`, `entropy`, `random` and any other registered player.
+            optional -s, --strategy strategy: StrategyNames
+            /// The number of guesses the solver looks ahead when picking a guess. Defaults to 1.
+            optional -d, --depth depth: u8
+            /// Allow the solver to guess sinners that have already been ruled out if they narrow
+            /// down the remaining sinners better.
+            optional -p, --probe
+            /// Write the solver's decision tree to a JSON file. Only one strategy may be used.
+            optional --emit-tree path: PathBuf
+            /// Write a row for every game to a CSV file, and a summary of each strategy to a
+            /// `-summary` file next to it.
+            optional --csv path: PathBuf
+            /// Write a report with tables of the results in markdown, to paste into GitHub
+            /// discussions or the wiki.
+            optional --markdown path: PathBuf
+            /// Draw the distribution of guesses and a comparison of the strategies as a chart in
+            /// an SVG file.
+            optional --chart path: PathBuf
+            /// Weight how likely each sinner is to be the target with a file of `name: weight`
+            /// lines. For more information, view the in-depth help.
+            optional --weights path: PathBuf
+            /// Write the guesses made against each sinner that takes the most guesses, and their
+            /// results, to a file.
+            optional --traces path: PathBuf
+            /// Pick up a run that was interrupted, skipping the games it already played.
+            optional --resume
+        }
         /// Play a game of Path to Nowordle from the terminal
-        cmd play {}
+        cmd play {
+            /// Pick the target from a seed instead of randomly, so the same seed always gives
+            /// the same game.
+            optional --seed seed: u64
+            /// Play today's daily puzzle. Each daily can only be played once.
+            optional --daily
+            /// The number of guesses before the game is lost. Defaults to 6, like ptndle.com.
+            /// Use 0 for unlimited guesses.
+            optional -m, --max-guesses max_guesses: u8
+            /// Copy the share text to the clipboard when the game ends.
+            optional --copy
+            /// Compare each guess to the solver's when the game ends.
+            optional --analyze
+            /// The number of sinners to find, each scored against every guess. Defaults to 1.
+            optional -t, --targets targets: usize
+            /// Practice on the sinners matching comma-separated conditions, such as
+            /// `alignment=Greed` or `birthplace=Syndicate,height=175..`.
+            optional --pool query: Query
+            /// Time each guess and the whole game, showing the time in the prompt and saving
+            /// personal bests.
+            optional --timed
+            /// Start a new game as soon as one ends, keeping a tally of the session until
+            /// `quit` is entered.
+            optional --endless
+            /// Race the solver on the same target, showing its guess after each of yours.
+            optional --race
+            /// Refuse guesses that earlier results ruled out, instead of asking to confirm them.
+            optional --strict
+            /// Print each result on the line after the sinner guessed, without column headers.
+            optional --compact
+            /// Show the values of the sinner guessed with each result, like the website.
+            optional --show-values
+            /// Resume the game that was left before it ended.
+            optional --resume
+            /// Play the game a challenge code printed at the end of a game is for, with the same
+            /// target and settings.
+            optional --challenge code: Challenge
+        }
+        /// Play a game of Path to Nowordle in a full-screen terminal interface
+        cmd tui {
+            /// Pick the target from a seed instead of randomly, so the same seed always gives
+            /// the same game.
+            optional --seed seed: u64
+            /// Play today's daily puzzle. Each daily can only be played once.
+            optional --daily
+            /// The number of guesses before the game is lost. Defaults to 6, like ptndle.com.
+            /// Use 0 for unlimited guesses.
+            optional -m, --max-guesses max_guesses: u8
+            /// Copy the share text to the clipboard when the game ends.
+            optional --copy
+            /// Compare each guess to the solver's when the game ends.
+            optional --analyze
+        }
+        /// View statistics about the games played with `play`
+        cmd stats {
+            /// Print the statistics, when no subcommand is given.
+            default cmd show {}
+            /// Write every recorded game to a JSON file, to back the stats up or move them to
+            /// another machine.
+            cmd export {
+                required file: PathBuf
+            }
+            /// Add the games in a file written by `stats export` to the recorded ones, leaving
+            /// out the games that were already recorded.
+            cmd import {
+                required file: PathBuf
+            }
+        }
+        /// Replay a game played with `play`
+        cmd replay {
+            /// The number of the game to replay, counting from 1. Defaults to the last game.
+            optional game: usize
+            /// Show what the solver would have guessed instead of each guess.
+            optional -a, --annotate
+        }
         /// Solve a game of Path to Nowordle from an optional set of starting guesses.
         cmd solve {
             /// A list of previous guesses to pass to the solver in the form of a comma-separated list of name:guess.
             /// For more information, view the in-depth help.
             optional guesses: NameAndGuesses
+            /// The strategy the solver uses to pick guesses. One of `mean` (default), `minimax`,
+            /// `entropy`, `random` or any other registered player.
+            optional -s, --strategy strategy: StrategyName
+            /// The number of guesses the solver looks ahead when picking a guess. Defaults to 1.
+            optional -d, --depth depth: u8
+            /// Allow the solver to guess sinners that have already been ruled out if they narrow
+            /// down the remaining sinners better.
+            optional -p, --probe
+            /// Show the top n guesses and their scores along with the recommendation.
+            optional -b, --best n: usize
+            /// Show the groups of remaining sinners that each of the top guesses would split
+            /// them into, which is why they are ranked where they are.
+            optional -e, --explain
+            /// Print the recommendation and remaining sinners for the given guesses and exit
+            /// instead of prompting for more rows.
+            optional -n, --non-interactive
+            /// Prompt for the sinner guessed and each cell of a row separately, with Tab
+            /// completion, instead of for whole rows.
+            optional -g, --guided
+            /// Weight how likely each sinner is to be the target with a file of `name: weight`
+            /// lines. For more information, view the in-depth help.
+            optional --weights path: PathBuf
+        }
+        /// Serve the solver as a JSON API over HTTP on localhost
+        cmd serve {
+            /// The port to listen on. Defaults to 8080.
+            optional --port port: u16
+            /// The strategy the solver uses to pick guesses. One of `mean` (default), `minimax`,
+            /// `entropy` or `random`.
+            optional -s, --strategy strategy: Strategy
+            /// The number of guesses the solver looks ahead when picking a guess. Defaults to 1.
+            optional -d, --depth depth: u8
+            /// Allow the solver to guess sinners that have already been ruled out if they narrow
+            /// down the remaining sinners better.
+            optional -p, --probe
+        }
+        /// Drive the solver from another program with a line-based protocol over stdin and
+        /// stdout
+        cmd engine {
+            /// The strategy the solver uses to pick guesses. One of `mean` (default), `minimax`,
+            /// `entropy` or `random`.
+            optional -s, --strategy strategy: Strategy
+            /// The number of guesses the solver looks ahead when picking a guess. Defaults to 1.
+            optional -d, --depth depth: u8
+            /// Allow the solver to guess sinners that have already been ruled out if they narrow
+            /// down the remaining sinners better.
+            optional -p, --probe
+        }
+        /// List the sinners and their data, optionally only those matching some conditions
+        cmd sinners {
+            /// Only list sinners with this alignment, such as `Greed`.
+            optional --alignment alignment: Alignment
+            /// Only list sinners with this tendency, such as `Fury`.
+            optional --tendency tendency: Tendency
+            /// Only list sinners from this birthplace: `Syndicate`, `Eastside` or `Other`.
+            optional --birthplace birthplace: BirthPlace
+            /// Only list sinners with heights in this range in cm, such as `160..175`, `..165`
+            /// or `170`.
+            optional --height range: HeightRange
+            /// Sort by `name` (default), `code`, `alignment`, `tendency`, `height` or
+            /// `birthplace`.
+            optional --sort key: SortKey
+        }
+        /// Show a sinner's data and the thresholds used when they are the target
+        cmd info {
+            /// The name of the sinner, or one of their aliases. Names with spaces don't need
+            /// quotes.
+            repeated name: String
+        }
+        /// Show the codes and heights of targets that give each arrow when a sinner is guessed
+        cmd bands {
+            /// The name of the sinner guessed, or one of their aliases. Names with spaces don't
+            /// need quotes.
+            repeated name: String
+        }
+        /// Manage the sinner data
+        cmd data {
+            /// Show the sinners added, removed and changed between two versions of the data. With
+            /// no files, compares the cache with the latest data, and with one, the cache with it.
+            cmd diff {
+                optional old: PathBuf
+                optional new: PathBuf
+            }
+            /// Save or restore named copies of the sinner data, which never change once saved.
+            cmd snapshot {
+                /// Save the current sinner data as a snapshot called `name`.
+                cmd save {
+                    required name: String
+                }
+                /// Replace the cached sinner data with the snapshot called `name`.
+                cmd load {
+                    required name: String
+                }
+                /// List the saved snapshots.
+                cmd list {}
+            }
+        }
+        /// Check the guess logic against rows recorded from the website, from a file with one
+        /// `target; guess: row` per line
+        cmd verify {
+            required file: PathBuf
+        }
+        /// Check the guess logic, the readers of rows and codes and the solvers against each
+        /// other on random sinners
+        cmd selftest {
+            /// The number of random datasets to check. Defaults to 20.
+            optional -n, --datasets datasets: usize
+            /// The number of sinners in each dataset. Defaults to 40, and can be at most 200.
+            optional --sinners sinners: usize
+            /// Make the datasets from a seed, so the same seed always checks the same sinners.
+            optional --seed seed: u64
         }
+        /// Time the solver's guesses and full games across strategies and pool sizes
+        cmd bench {
+            /// A comma-separated list of strategies to time, or `all` (default).
+            optional -s, --strategy strategy: Strategies
+            /// The number of guesses the solver looks ahead when picking a guess. Defaults to 1.
+            optional -d, --depth depth: u8
+            /// Allow the solver to guess sinners that have already been ruled out.
+            optional -p, --probe
+            /// A comma-separated list of pool sizes to time, such as `20,40`. Defaults to a
+            /// quarter, a half and all of the pool.
+            optional --sizes sizes: Sizes
+            /// The seed used to pick the sinners in smaller pools. Defaults to 0.
+            optional --seed seed: u64
+        }
+        /// Play random games with naive players and the solver, and compare how often they win
+        cmd simulate {
+            /// The number of games to play. Defaults to 1000.
+            optional -n, --games games: usize
+            /// A comma-separated list of players, or `all` (default). Players are `random`,
+            /// which ignores the results, `greedy`, which guesses any sinner that fits the
+            /// results, and `optimal`, which is the solver.
+            optional --player player: PlayerKinds
+            /// The number of guesses before a game is lost. Defaults to 6, like ptndle.com.
+            /// Use 0 for unlimited guesses.
+            optional -m, --max-guesses max_guesses: u8
+            /// Pick the targets from a seed, so the same seed always gives the same games.
+            optional --seed seed: u64
+            /// The strategy of the optimal player. Strategies are `mean` (default), `minimax`,
+            /// `entropy`, `random` and any other registered player.
+            optional -s, --strategy strategy: StrategyName
+            /// The number of guesses the optimal player looks ahead. Defaults to 1.
+            optional -d, --depth depth: u8
+            /// Allow the optimal player to guess sinners that have already been ruled out.
+            optional -p, --probe
+        }
+        /// Play every game with every registered player and rank them
+        cmd tournament {
+            /// A comma-separated list of players to rank. Defaults to every registered player.
+            optional --players players: String
+            /// The number of guesses the solvers look ahead. Defaults to 1.
+            optional -d, --depth depth: u8
+            /// Also play each target this many times starting from a random first guess.
+            optional --random-starts random_starts: usize
+            /// The seed used for the random first guesses and players. Defaults to 0.
+            optional --seed seed: u64
+            /// Rank by `mean` guesses (default) or the `worst` case, with the other breaking ties.
+            optional --rank-by rank_by: RankKey
+            /// Write the standings to a CSV file.
+            optional --csv path: PathBuf
+        }
+        /// Rank every sinner as the first guess
+        cmd openers {
+            /// The strategy to rank by. Strategies are `mean` (default), `minimax` and `entropy`.
+            optional -s, --strategy strategy: Strategy
+            /// Only print the best `count` openers.
+            optional -n, --top count: usize
+            /// Print the best guess to follow an opener with for every result it can give,
+            /// instead of ranking the openers.
+            optional --book
+            /// The opener of the book. Defaults to the best opener.
+            optional --opener name: String
+            /// The number of guesses the solver looks ahead when picking the guesses in the
+            /// book. Defaults to 1.
+            optional -d, --depth depth: u8
+            /// Allow the book to follow up with sinners that have already been ruled out.
+            optional -p, --probe
+            /// Print the book as a Markdown table.
+            optional --markdown
+        }
+        /// Find out what makes some sinners hard to find
+        cmd analyze {
+            /// Find the pairs of sinners that give the same result against the most guesses.
+            cmd confusions {
+                /// Only print the `count` most alike pairs. Defaults to 20.
+                optional -n, --top count: usize
+            }
+            /// Score how much each attribute narrows down the pool on its own.
+            cmd attributes {}
+        }
+        /// Inspect or clear the cache of sinner data and decision trees
+        cmd cache {
+            /// `status` (default) to show what is cached, `clear` to delete everything in the
+            /// cache, or `path` to print the path of the cache directory.
+            optional action: CacheAction
+        }
+        /// Print a script that completes the commands, flags and sinner names of this program
+        /// in `shell`: bash, zsh, fish or powershell
+        cmd completions {
+            optional shell: Shell
+            /// Print the names and aliases of every sinner, one per line, for the scripts to
+            /// complete.
+            optional --names
+        }
+        /// View or change the defaults in the config file
+        cmd config {
+            /// Print the value of a setting, or every setting and the path of the config file.
+            cmd get {
+                optional key: ConfigKey
+            }
+            /// Change a setting. Lists, like `exclude`, are separated by commas.
+            cmd set {
+                required key: ConfigKey
+                required value: String
+            }
+            /// Remove a setting, so the built-in default is used again.
+            cmd unset {
+                required key: ConfigKey
+            }
+        }
+    }
+}
+
+/// `tui` takes the same flags as `play`
+impl From<Tui> for Play {
+    fn from(
+        Tui {
+            seed,
+            daily,
+            max_guesses,
+            copy,
+            analyze,
+        }: Tui,
+    ) -> Self {
+        Self {
+            seed,
+            daily,
+            max_guesses,
+            copy,
+            analyze,
+            targets: None,
+            pool: None,
+            timed: false,
+            endless: false,
+            race: false,
+            strict: false,
+            compact: false,
+            show_values: false,
+            resume: false,
+            challenge: None,
+        }
+    }
+}
+
+impl PtndleCli {
+    /// The verbosity chosen with --quiet or --verbose
+    pub fn verbosity(&self) -> eyre::Result<Verbosity> {
+        match (self.quiet, self.verbose) {
+            | (true, true) => {
+                Err(
+                    Error::InvalidInput("--quiet can't be combined with --verbose".to_owned())
+                        .into(),
+                )
+            },
+            | (true, false) => Ok(Verbosity::Quiet),
+            | (false, true) => Ok(Verbosity::Verbose),
+            | (false, false) => Ok(Verbosity::Normal),
+        }
+    }
+
+    /// The format chosen with --format
+    pub fn format(&self) -> eyre::Result<Format> {
+        match self.format {
+            | Some(Format::Discord) if self.json => {
+                Err(Error::InvalidInput("--format can't be combined with --json".to_owned()).into())
+            },
+            | format => Ok(format.unwrap_or_default()),
+        }
+    }
 
+    /// The theme chosen with --theme, or else the default for `format`
+    pub fn theme(&self, format: Format) -> Theme {
+        self.theme.unwrap_or_else(|| {
+            match format {
+                | Format::Text => Theme::from_env(),
+                // Discord would show the escape codes of colors as they are
+                | Format::Discord => Theme::plain(),
+            }
+        })
+    }
+
+    /// Fills in the flags that weren't given with the defaults in `config`, so
+    /// flags take precedence over the config file, which takes precedence over
+    /// the built-in defaults
+    pub fn apply_config(&mut self, config: &ConfigFile) {
+        self.theme = self.theme.or_else(|| config.theme());
+        self.lang = self.lang.or(config.lang);
+        self.cache_ttl = self.cache_ttl.or(config.cache_ttl);
+        if self.exclude.is_none() && !config.exclude.is_empty() {
+            self.exclude = Some(config.exclude.join(","));
+        }
+        // The URLs would conflict with flags that load the data from elsewhere, and are for
+        // Path to Nowordle's data rather than another game's
+        let remote = self.data.is_none() && self.dataset.is_none() && !self.offline;
+        if remote && self.data_url.is_empty() && settings::game().is_builtin() {
+            self.data_url.clone_from(&config.data_urls);
+        }
+        match &mut self.subcommand {
+            | PtndleCliCmd::Gather(Gather { strategy, .. }) => {
+                if strategy.is_none() {
+                    *strategy = config
+                        .strategy
+                        .map(|x| StrategyNames(vec![StrategyName::Solver(x)]));
+                }
+            },
+            | PtndleCliCmd::Solve(Solve { strategy, .. }) => {
+                if strategy.is_none() {
+                    *strategy = config.strategy.map(StrategyName::Solver);
+                }
+            },
+            | PtndleCliCmd::Serve(Serve { strategy, .. }) |
+            PtndleCliCmd::Engine(Engine { strategy, .. }) => {
+                *strategy = strategy.or(config.strategy);
+            },
+            | PtndleCliCmd::Play(Play {
+                max_guesses,
+                strict,
+                ..
+            }) => {
+                *max_guesses = max_guesses.or(config.max_guesses);
+                *strict = *strict || config.strict == Some(true);
+            },
+            | PtndleCliCmd::Tui(Tui { max_guesses, .. }) => {
+                *max_guesses = max_guesses.or(config.max_guesses);
+            },
+            | _ => {},
+        }
     }
 }
@@ -0,0 +1,184 @@
+use std::fmt::Display;
+
+use crate::aliases::Aliases;
+use crate::data::Sinner;
+use crate::i18n::Translations;
+
+/// The names sinners can be looked up by besides their own
+#[derive(Debug, Clone, Default)]
+pub struct Names {
+    pub aliases: Aliases,
+    pub translations: Translations,
+}
+
+/// The most names suggested for a name that doesn't match any sinner
+const MAX_SUGGESTIONS: usize = 3;
+
+/// A name that doesn't match any sinner, with the names it might have been
+/// meant to be
+#[derive(Debug)]
+pub struct UnknownSinnerError {
+    name: String,
+    suggestions: Vec<String>,
+}
+
+impl Display for UnknownSinnerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown sinner: `{}`", self.name)?;
+        match self.suggestions.as_slice() {
+            | [] => Ok(()),
+            | [suggestion] => write!(f, ". Did you mean {suggestion}?"),
+            | [rest @ .., last] => write!(f, ". Did you mean {} or {last}?", rest.join(", ")),
+        }
+    }
+}
+impl std::error::Error for UnknownSinnerError {}
+
+/// The letters without accents of U+0100 to U+017F, Latin Extended-A, in
+/// order
+const LATIN_EXTENDED_A: &str = "AaAaAaCcCcCcCcDdDdEeEeEeEeEeGgGgGgGgHhHhIiIiIiIiIiIiJjKkkLlLlLlLlLlNnNnNnnNnOoOoOoOoRrRrRrSsSsSsSsTtTtTtUuUuUuUuUuUuWwYyYZzZzZzs";
+
+/// `c` without its accent, if it's a Latin letter with one
+fn strip_accent(c: char) -> char {
+    match c {
+        | 'À'..='Å' => 'A',
+        | 'Ç' => 'C',
+        | 'È'..='Ë' => 'E',
+        | 'Ì'..='Ï' => 'I',
+        | 'Ñ' => 'N',
+        | 'Ò'..='Ö' | 'Ø' => 'O',
+        | 'Ù'..='Ü' => 'U',
+        | 'Ý' => 'Y',
+        | 'à'..='å' => 'a',
+        | 'ç' => 'c',
+        | 'è'..='ë' => 'e',
+        | 'ì'..='ï' => 'i',
+        | 'ñ' => 'n',
+        | 'ò'..='ö' | 'ø' => 'o',
+        | 'ù'..='ü' => 'u',
+        | 'ý' | 'ÿ' => 'y',
+        | '\u{100}'..='\u{17f}' => {
+            let index = u32::from(c) - 0x100;
+            LATIN_EXTENDED_A.chars().nth(index as usize).unwrap_or(c)
+        },
+        | _ => c,
+    }
+}
+
+/// `c` as a normal-width character, if it's a full-width one like the ones
+/// Chinese and Japanese keyboards type, such as `Ｎ` for `N`
+fn narrow(c: char) -> char {
+    match c {
+        | '\u{3000}' => ' ',
+        | '\u{ff01}'..='\u{ff5e}' => char::from_u32(u32::from(c) - 0xfee0).unwrap_or(c),
+        | _ => c,
+    }
+}
+
+/// Folds `name` so that names that only differ in case, accents or
+/// full-width characters are the same, so `ＮＯＸ` and `nox` both match `NOX`
+/// and `cafe` matches `Café`
+pub fn fold(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(narrow)
+        // Accents typed as separate combining characters
+        .filter(|x| !('\u{300}'..='\u{36f}').contains(x))
+        .map(strip_accent)
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Folds `name` and removes everything but letters and digits, so `kk`
+/// matches `K.K.`
+fn normalize(name: &str) -> Vec<char> {
+    fold(name).chars().filter(|x| x.is_alphanumeric()).collect()
+}
+
+/// The number of characters that have to be inserted, removed or replaced
+/// to turn `from` into `to`
+fn edit_distance(from: &[char], to: &[char]) -> usize {
+    let mut previous = (0..=to.len()).collect::<Vec<_>>();
+    let mut current = vec![0; to.len() + 1];
+    for (i, a) in from.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b) in to.iter().enumerate() {
+            let replace = previous[j] + usize::from(a != b);
+            current[j + 1] = replace.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[to.len()]
+}
+
+/// How far `name` is from `other`, or `None` if it's too far to be a typo.
+/// Names that `name` is the start of are always close.
+fn distance(name: &[char], other: &str) -> Option<usize> {
+    let other = normalize(other);
+    if !name.is_empty() && other.starts_with(name) {
+        return Some(other.len() - name.len());
+    }
+    let distance = edit_distance(name, &other);
+    (distance <= (name.len() / 3).max(1)).then_some(distance)
+}
+
+/// The names of the sinners closest to `name`, or to one of their `names`,
+/// closest first
+pub fn suggestions(sinners: &[Sinner], name: &str, names: &Names) -> Vec<String> {
+    let name = normalize(name);
+    let mut close = sinners
+        .iter()
+        .filter_map(|x| {
+            names
+                .aliases
+                .of(&x.name)
+                .chain([x.name.as_str()])
+                .filter_map(|other| distance(&name, other))
+                .min()
+                .map(|distance| (distance, x))
+        })
+        .collect::<Vec<_>>();
+    close.sort_by_key(|(distance, _)| *distance);
+    close
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, x)| x.name.clone())
+        .collect()
+}
+
+/// The only sinner whose name is `name` after [`normalize`], such as `Mr. Fox`
+/// for `mr fox`
+fn find_normalized<'sinners>(sinners: &'sinners [Sinner], name: &str) -> Option<&'sinners Sinner> {
+    let name = normalize(name);
+    let mut matching = sinners.iter().filter(|x| normalize(&x.name) == name);
+    let sinner = matching.next()?;
+    matching.next().is_none().then_some(sinner)
+}
+
+/// Finds the sinner named `name`, or with `name` as one of their alias or
+/// translated `names`, after [`fold`]ing both and then ignoring punctuation.
+/// If there isn't one, the error suggests sinners with similar names.
+pub fn find_sinner<'sinners>(
+    sinners: &'sinners [Sinner],
+    name: &str,
+    names: &Names,
+) -> Result<&'sinners Sinner, UnknownSinnerError> {
+    sinners
+        .iter()
+        .find(|x| fold(&x.name) == fold(name))
+        .or_else(|| {
+            let name = names.aliases.resolve(name)?;
+            sinners.iter().find(|x| x.name == name)
+        })
+        .or_else(|| {
+            let name = names.translations.resolve(name)?;
+            sinners.iter().find(|x| x.name == name)
+        })
+        .or_else(|| find_normalized(sinners, name))
+        .ok_or_else(|| {
+            UnknownSinnerError {
+                name: name.to_owned(),
+                suggestions: suggestions(sinners, name, names),
+            }
+        })
+}
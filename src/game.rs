@@ -0,0 +1,183 @@
+//! Path to Nowordle played with other sinner data, picked with `--game`
+//!
+//! Each game has its own character data, thresholds, cache, stats and saved
+//! games. ptndle.com's data is the built-in `ptndle` game, and any other, such
+//! as a mirror or a fan-made pool of sinners, is described by a profile in the
+//! `games` folder of the config directory, such as `games/mirror.json`:
+//!
+//! ```json
+//! {
+//!     "title": "Path to Nowordle (mirror)",
+//!     "data_urls": ["https://example.com/characters.json"],
+//!     "thresholds": {"code_near": {"base": 5, "per_unit": 0.1}}
+//! }
+//! ```
+//!
+//! `thresholds` is in the same format as a `--thresholds` file and can be left
+//! out for the website's. The character data has to be in the same format as
+//! ptndle.com's, with the same [`Attribute`](crate::attribute::Attribute)s and
+//! values, since those are what the guesses are scored on. A profile can't
+//! declare attributes of its own, so guessing games other than Path to
+//! Nowordle can't be played with one.
+
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use eyre::Context;
+use facet::Facet;
+
+use crate::compare::{ThresholdConfig, ThresholdFile};
+use crate::data::DEFAULT_SINNER_DATA_URL;
+use crate::error::Error;
+use crate::paths::config_dir;
+
+/// The name of the built-in game, Path to Nowordle
+pub const DEFAULT_GAME: &str = "ptndle";
+
+/// The name of a game, which is also the name of its profile's file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameName(String);
+
+impl GameName {
+    pub fn name(&self) -> &str { &self.0 }
+}
+
+impl Display for GameName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str(&self.0) }
+}
+
+#[derive(Debug)]
+pub struct InvalidGameNameError(String);
+
+impl FromStr for GameName {
+    type Err = InvalidGameNameError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The name is used in paths, so it can't have separators or dots
+        let valid = |x: char| x.is_ascii_alphanumeric() || x == '-' || x == '_';
+        let name = s.trim();
+        if name.is_empty() || !name.chars().all(valid) {
+            return Err(InvalidGameNameError(s.to_owned()));
+        }
+        Ok(Self(name.to_owned()))
+    }
+}
+impl Display for InvalidGameNameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Invalid game name: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected letters, digits, `-` and `_`")
+    }
+}
+
+/// A game's profile as it's written in its file. Other fields, such as
+/// attributes or comparison rules, are rejected rather than ignored, since
+/// the attributes and how they're compared are fixed.
+#[derive(Facet)]
+#[facet(deny_unknown_fields)]
+struct GameFile {
+    title: String,
+    data_urls: Vec<String>,
+    #[facet(default)]
+    thresholds: Option<ThresholdFile>,
+}
+
+/// A dataset Path to Nowordle can be played with, with where its character
+/// data comes from and how its numbers are compared
+#[derive(Debug, Clone)]
+pub struct GameProfile {
+    pub name: String,
+    /// The name of the game as it's shown
+    pub title: String,
+    /// The URLs the character data is fetched from, tried in order
+    pub data_urls: Vec<String>,
+    pub thresholds: ThresholdConfig,
+}
+
+/// The folder game profiles are kept in
+fn games_dir() -> Option<PathBuf> { config_dir().map(|x| x.join("games")) }
+
+impl GameProfile {
+    /// Path to Nowordle, as ptndle.com plays it
+    pub fn builtin() -> Self {
+        Self {
+            name: DEFAULT_GAME.to_owned(),
+            title: "Path to Nowordle".to_owned(),
+            data_urls: vec![DEFAULT_SINNER_DATA_URL.to_owned()],
+            thresholds: ThresholdConfig::default(),
+        }
+    }
+
+    pub fn is_builtin(&self) -> bool { self.name == DEFAULT_GAME }
+
+    /// The names of every game that can be picked, the built-in one first
+    pub fn available() -> Vec<String> {
+        let mut names = games_dir()
+            .and_then(|x| std::fs::read_dir(x).ok())
+            .into_iter()
+            .flatten()
+            .filter_map(|x| {
+                let path = x.ok()?.path();
+                (path.extension()? == "json").then_some(path.file_stem()?.to_str()?.to_owned())
+            })
+            .filter(|x| x != DEFAULT_GAME && x.parse::<GameName>().is_ok())
+            .collect::<Vec<_>>();
+        names.sort();
+        names.insert(0, DEFAULT_GAME.to_owned());
+        names
+    }
+
+    /// Loads the game called `name`, from its profile unless it's the
+    /// built-in one
+    pub fn load(name: &GameName) -> eyre::Result<Self> {
+        if name.name() == DEFAULT_GAME {
+            return Ok(Self::builtin());
+        }
+        let path = games_dir()
+            .map(|x| x.join(format!("{name}.json")))
+            .filter(|x| x.exists());
+        let Some(path) = path else {
+            let expected = match Self::available().as_slice() {
+                | [rest @ .., last] if !rest.is_empty() => {
+                    format!("one of {} or {last}", rest.join(", "))
+                },
+                | _ => {
+                    format!("{DEFAULT_GAME} or a game in the `games` folder of the config folder")
+                },
+            };
+            return Err(Error::InvalidInput(format!(
+                "Unknown game: `{name}`. Expected {expected}"
+            ))
+            .into());
+        };
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| Error::Io(format!("Failed to read {}: {e}", path.display())))?;
+        let file = facet_json::from_str::<GameFile>(&json).map_err(|e| {
+            Error::InvalidInput(format!(
+                "Failed to parse {}: {e}. A profile has a `title`, `data_urls` and `thresholds`, \
+                 and can't declare attributes or comparison rules of its own",
+                path.display()
+            ))
+        })?;
+        if file.data_urls.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "{} doesn't have any `data_urls` to fetch the character data from",
+                path.display()
+            ))
+            .into());
+        }
+        let thresholds = match &file.thresholds {
+            | Some(thresholds) => {
+                ThresholdConfig::from_file(thresholds)
+                    .with_context(|| format!("Invalid thresholds in {}", path.display()))?
+            },
+            | None => ThresholdConfig::default(),
+        };
+        Ok(Self {
+            name: name.name().to_owned(),
+            title: file.title,
+            data_urls: file.data_urls,
+            thresholds,
+        })
+    }
+}
@@ -0,0 +1,254 @@
+//! Translations of the sinners' names and of the text shown while playing, for
+//! players who know the game in Chinese or Japanese
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::fuzzy::fold;
+use crate::paths::config_dir;
+
+/// A language the text shown while playing can be in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Lang {
+    #[default]
+    En,
+    Zh,
+    Ja,
+}
+
+impl Lang {
+    pub const ALL: [Self; 3] = [Self::En, Self::Zh, Self::Ja];
+    /// The language's code, which is also how it's named in translation files
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            | Self::En => "en",
+            | Self::Zh => "zh",
+            | Self::Ja => "ja",
+        }
+    }
+}
+
+impl Display for Lang {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownLangError(String);
+
+impl FromStr for Lang {
+    type Err = UnknownLangError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.to_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| UnknownLangError(s.to_owned()))
+    }
+}
+impl Display for UnknownLangError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Unknown language: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected `en`, `zh` or `ja`")
+    }
+}
+
+/// A piece of text shown while playing. Each `{}` in its text is replaced
+/// with an argument by [`message`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    /// The sinner guessed
+    Guessed,
+    /// The target, when the game is won
+    Won,
+    /// The targets, when a game with several is won
+    WonAll,
+    /// The number of guesses the game was won in
+    WonIn,
+    /// The target, when the game is lost
+    OutOfGuesses,
+    /// The targets, when a game with several is lost
+    OutOfGuessesAll,
+    /// The target, when the player gave up
+    GaveUp,
+    /// The targets, when the player gave up a game with several
+    GaveUpAll,
+    /// When the player has no guess to make, because the results rule out
+    /// every sinner
+    NoGuesses,
+    /// The number of sinners that could still be the target
+    Remaining,
+    /// Joins the names of several targets
+    And,
+    /// In place of the result for a target that was already found
+    Found,
+    /// The header of the column of the sinners guessed
+    Sinner,
+    /// The header of the column of the numbers of the targets
+    Target,
+    Code,
+    Alignment,
+    Tendency,
+    Height,
+    Birthplace,
+}
+
+impl Message {
+    /// The text of the message in `lang`
+    pub const fn text(self, lang: Lang) -> &'static str {
+        match lang {
+            | Lang::En => {
+                match self {
+                    | Self::Guessed => "Guessed {}",
+                    | Self::Won => "Won! The sinner was {}!",
+                    | Self::WonAll => "Won! The sinners were {}!",
+                    | Self::WonIn => "Won in {} guesses!",
+                    | Self::OutOfGuesses => "Out of guesses! The sinner was {}.",
+                    | Self::OutOfGuessesAll => "Out of guesses! The sinners were {}.",
+                    | Self::GaveUp => "Gave up! The sinner was {}.",
+                    | Self::GaveUpAll => "Gave up! The sinners were {}.",
+                    | Self::NoGuesses => {
+                        "No possible guesses in this state. There is likely a contradiction."
+                    },
+                    | Self::Remaining => "{} sinners remain consistent with your guesses",
+                    | Self::And => " and ",
+                    | Self::Found => "found",
+                    | Self::Sinner => "Sinner",
+                    | Self::Target => "Target",
+                    | Self::Code => "Code",
+                    | Self::Alignment => "Alignment",
+                    | Self::Tendency => "Tendency",
+                    | Self::Height => "Height",
+                    | Self::Birthplace => "Birthplace",
+                }
+            },
+            | Lang::Zh => {
+                match self {
+                    | Self::Guessed => "猜测：{}",
+                    | Self::Won | Self::WonAll => "胜利！目标是{}！",
+                    | Self::WonIn => "用了{}次猜中！",
+                    | Self::OutOfGuesses | Self::OutOfGuessesAll => "猜测次数用完了！目标是{}。",
+                    | Self::GaveUp | Self::GaveUpAll => "放弃了！目标是{}。",
+                    | Self::NoGuesses => "没有可以猜的罪人了。结果中可能有矛盾。",
+                    | Self::Remaining => "还有{}名罪人符合你的猜测",
+                    | Self::And => "和",
+                    | Self::Found => "已找到",
+                    | Self::Sinner => "罪人",
+                    | Self::Target => "目标",
+                    | Self::Code => "编号",
+                    | Self::Alignment => "阵营",
+                    | Self::Tendency => "倾向",
+                    | Self::Height => "身高",
+                    | Self::Birthplace => "出身",
+                }
+            },
+            | Lang::Ja => {
+                match self {
+                    | Self::Guessed => "推測：{}",
+                    | Self::Won | Self::WonAll => "正解！答えは{}でした！",
+                    | Self::WonIn => "{}回で正解！",
+                    | Self::OutOfGuesses | Self::OutOfGuessesAll => {
+                        "推測回数を使い切りました！答えは{}でした。"
+                    },
+                    | Self::GaveUp | Self::GaveUpAll => "ギブアップ！答えは{}でした。",
+                    | Self::NoGuesses => {
+                        "推測できる罪人がいません。結果に矛盾があるかもしれません。"
+                    },
+                    | Self::Remaining => "推測に合う罪人は残り{}人",
+                    | Self::And => "と",
+                    | Self::Found => "発見済み",
+                    | Self::Sinner => "罪人",
+                    | Self::Target => "答え",
+                    | Self::Code => "コード",
+                    | Self::Alignment => "陣営",
+                    | Self::Tendency => "傾向",
+                    | Self::Height => "身長",
+                    | Self::Birthplace => "出身",
+                }
+            },
+        }
+    }
+}
+
+/// The text of `message` in `lang`, with each `{}` replaced by the next of
+/// `args`
+pub fn message(lang: Lang, message: Message, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut parts = message.text(lang).split("{}");
+    let mut text = parts.next().unwrap_or_default().to_owned();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+fn names_path() -> Option<PathBuf> { config_dir().map(|x| x.join("translations.json")) }
+
+/// Translations of the sinners' names, as `(language, name, translation)`
+#[derive(Debug, Clone, Default)]
+pub struct Translations(Vec<(Lang, String, String)>);
+
+impl Translations {
+    /// Reads `translations.json` in the config directory, a JSON object from
+    /// language to an object from each sinner's name to its translation.
+    /// Without the file, there are no translations.
+    pub fn load() -> eyre::Result<Self> {
+        let Some(path) = names_path().filter(|x| x.exists()) else {
+            return Ok(Self::default());
+        };
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| Error::Io(format!("Failed to read {}: {e}", path.display())))?;
+        let languages: HashMap<String, HashMap<String, String>> = facet_json::from_str(&json)
+            .map_err(|e| Error::InvalidInput(format!("Failed to parse {}: {e}", path.display())))?;
+        let mut names = Vec::new();
+        for (lang, translations) in languages {
+            let lang = lang
+                .parse::<Lang>()
+                .map_err(|e| Error::InvalidInput(format!("In {}: {e}", path.display())))?;
+            names.extend(
+                translations
+                    .into_iter()
+                    .map(|(name, translation)| (lang, name, translation)),
+            );
+        }
+        Ok(Self(names))
+    }
+
+    /// The name of the sinner whose name in any language is `translation`,
+    /// ignoring case, accents and full-width characters
+    pub fn resolve(&self, translation: &str) -> Option<&str> {
+        let translation = fold(translation);
+        self.0
+            .iter()
+            .find(|(_, _, x)| fold(x) == translation)
+            .map(|(_, name, _)| name.as_str())
+    }
+
+    /// Every translation of the sinner named `name`
+    pub fn of<'names>(&'names self, name: &str) -> impl Iterator<Item = &'names str> {
+        let name = fold(name);
+        self.0
+            .iter()
+            .filter(move |(_, x, _)| fold(x) == name)
+            .map(|(_, _, translation)| -> &str { translation })
+    }
+
+    /// The name of the sinner named `name` in `lang`, or `name` if it hasn't
+    /// been translated
+    pub fn display_name<'name>(&'name self, lang: Lang, name: &'name str) -> &'name str {
+        if lang == Lang::En {
+            return name;
+        }
+        self.0
+            .iter()
+            .find(|(x, english, _)| *x == lang && fold(english) == fold(name))
+            .map_or(name, |(_, _, translation)| translation.as_str())
+    }
+}
@@ -0,0 +1,353 @@
+//! Checks of the invariants the guess logic and the solver rely on, run over
+//! random sets of sinners by `ptndle-cli selftest`
+//!
+//! The rows of a [`Guess`] are packed into bits and read back through
+//! [`Attribute`]s, and [`Sinner::matches_result`] works the thresholds out
+//! backwards with [`Threshold::inverse`](crate::compare::Threshold::inverse)
+//! rather than comparing. Both are easy to get subtly wrong in a way that only
+//! shows up for a few sinners, so the checks go over every pair of sinners in
+//! datasets made up to hit the edges of the thresholds, rather than over the
+//! real sinners alone.
+
+use std::fmt::Display;
+use std::sync::Arc;
+
+use crate::attribute::Attribute;
+use crate::challenge::Challenge;
+use crate::compare::ThresholdConfig;
+use crate::data::{variant_names, Alignment, BirthPlace, Sinner, Tendency};
+use crate::guess::Guess;
+use crate::matrix::GuessMatrix;
+use crate::play::{NameAndGuess, Player};
+use crate::rng::Rng;
+use crate::solver::{SolverConfig, Strategy, TieBreak};
+
+/// The number of failures of each check that are kept to be shown
+const KEPT_FAILURES: usize = 5;
+/// The most sinners in a dataset. The checks go over every pair of sinners,
+/// so they slow down quickly with more.
+pub const MAX_SINNERS: usize = 200;
+/// The number of random sinners made for each one needed before giving up on
+/// finding ones that differ from the rest
+const ATTEMPTS_PER_SINNER: usize = 100;
+
+/// An invariant that is checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// The target matches the result of every guess
+    Results,
+    /// The result of a guess is the only row the target matches
+    Inverse,
+    /// Rows and challenge codes read back the same as they were written
+    RoundTrips,
+    /// Every solver finds every target, in at most as many guesses as there
+    /// are sinners
+    Solvers,
+}
+
+impl Check {
+    pub const ALL: [Self; 4] = [
+        Self::Results,
+        Self::Inverse,
+        Self::RoundTrips,
+        Self::Solvers,
+    ];
+
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            | Self::Results => "results",
+            | Self::Inverse => "inverse",
+            | Self::RoundTrips => "round-trips",
+            | Self::Solvers => "solvers",
+        }
+    }
+
+    /// What the check makes sure of
+    pub const fn description(self) -> &'static str {
+        match self {
+            | Self::Results => "the target matches the result of every guess",
+            | Self::Inverse => "the result of a guess is the only row the target matches",
+            | Self::RoundTrips => "rows, sinner rows and challenge codes read back as written",
+            | Self::Solvers => "every solver finds every target within the number of sinners",
+        }
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+/// How a check went over every dataset
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub check: Check,
+    /// The number of cases checked
+    pub cases: usize,
+    /// The number of cases that failed
+    pub failed: usize,
+    /// The first few failures, described
+    pub failures: Vec<String>,
+}
+
+impl CheckResult {
+    const fn new(check: Check) -> Self {
+        Self {
+            check,
+            cases: 0,
+            failed: 0,
+            failures: Vec::new(),
+        }
+    }
+
+    pub const fn passed(&self) -> bool { self.failed == 0 }
+
+    /// Counts a case, which failed with `failure` unless it's `None`
+    fn record(&mut self, failure: Option<impl FnOnce() -> String>) {
+        self.cases += 1;
+        if let Some(failure) = failure {
+            self.failed += 1;
+            if self.failures.len() < KEPT_FAILURES {
+                self.failures.push(failure());
+            }
+        }
+    }
+}
+
+/// A random variant of the enum `T` in the sinner data
+fn random_variant<T: for<'facet> facet::Facet<'facet> + std::str::FromStr>(rng: &mut Rng) -> T {
+    let names = variant_names::<T>();
+    let name = names[rng.below(names.len())];
+    name.parse()
+        .unwrap_or_else(|_| unreachable!("{name} is a variant"))
+}
+
+/// `count` sinners with random attributes, up to [`MAX_SINNERS`], none of
+/// them with the same attributes as another, since nothing could tell those
+/// apart. The codes and heights are bunched up so that many of them are near
+/// each other. There are fewer if not enough different sinners turn up.
+pub fn random_sinners(rng: &mut Rng, count: usize) -> Vec<Sinner> {
+    let count = count.min(MAX_SINNERS);
+    let mut sinners = Vec::<Sinner>::with_capacity(count);
+    for _ in 0..count * ATTEMPTS_PER_SINNER {
+        if sinners.len() == count {
+            break;
+        }
+        let code = u16::try_from(1 + rng.below(300)).unwrap_or(1);
+        let height = u8::try_from(140 + rng.below(60)).unwrap_or(160);
+        // NOX is the only sinner without a code, so there's at most one
+        let nox = rng.below(20) == 0 && sinners.iter().all(|x| x.code.is_some());
+        let sinner = Sinner {
+            name: format!("Sinner {}", sinners.len() + 1),
+            code: (!nox).then_some(code),
+            alignment: random_variant::<Alignment>(rng),
+            tendency: random_variant::<Tendency>(rng),
+            height,
+            birthplace: random_variant::<BirthPlace>(rng),
+            released: None,
+        };
+        let same = |x: &Sinner| Attribute::ALL.iter().all(|a| a.same(x, &sinner));
+        if !sinners.iter().any(same) {
+            sinners.push(sinner);
+        }
+    }
+    sinners
+}
+
+/// Every row a guess can get
+pub fn possible_rows() -> Vec<Guess> {
+    let mut rows = vec![Vec::new()];
+    for attribute in Attribute::ALL {
+        rows = rows
+            .into_iter()
+            .flat_map(|row| {
+                attribute.cells().into_iter().map(move |cell| {
+                    let mut row = row.clone();
+                    row.push(cell);
+                    row
+                })
+            })
+            .collect();
+    }
+    rows.into_iter()
+        .filter_map(|row| Guess::from_cells(row.try_into().ok()?))
+        .collect()
+}
+
+fn check_results(sinners: &[Sinner], thresholds: &ThresholdConfig, result: &mut CheckResult) {
+    for target in sinners {
+        for guess in sinners {
+            let row = target.guess(guess, thresholds);
+            result.record(
+                (!guess.matches_result(row, target, thresholds)).then_some(|| {
+                    format!(
+                        "{} doesn't match its own result {} for {}",
+                        target.name,
+                        row.to_ascii(),
+                        guess.name
+                    )
+                }),
+            );
+        }
+    }
+}
+
+fn check_inverse(
+    sinners: &[Sinner],
+    rows: &[Guess],
+    thresholds: &ThresholdConfig,
+    result: &mut CheckResult,
+) {
+    for target in sinners {
+        for guess in sinners {
+            let row = target.guess(guess, thresholds).to_ascii();
+            let matching = rows
+                .iter()
+                .filter(|&&x| guess.matches_result(x, target, thresholds))
+                .map(|x| x.to_ascii())
+                .collect::<Vec<_>>();
+            result.record((matching != [row.as_str()]).then_some(|| {
+                format!(
+                    "{} matches {} for {} instead of only {row}",
+                    target.name,
+                    matching.join(", "),
+                    guess.name
+                )
+            }));
+        }
+    }
+}
+
+fn check_round_trips(sinners: &[Sinner], rows: &[Guess], result: &mut CheckResult) {
+    for &row in rows {
+        let ascii = row.to_ascii();
+        result
+            .record((ascii.parse() != Ok(row)).then_some(|| format!("`{ascii}` reads back wrong")));
+        let emoji = row.to_emoji();
+        result.record(
+            (Guess::from_emoji(&emoji) != Some(row))
+                .then_some(|| format!("`{emoji}` for `{ascii}` reads back wrong")),
+        );
+        result.record(
+            (Guess::from_cells(row.all_cells()) != Some(row))
+                .then_some(|| format!("The cells of `{ascii}` pack back wrong")),
+        );
+    }
+    for (sinner, &row) in sinners.iter().zip(rows.iter().cycle()) {
+        let line = format!("{}: {}", sinner.name, row.to_ascii());
+        let named = line.parse::<NameAndGuess>().ok();
+        result.record(
+            named
+                .is_none_or(|x| x.name != sinner.name || x.guess != row)
+                .then_some(|| format!("`{line}` reads back wrong")),
+        );
+        let challenge = Challenge::new(sinner, Some(6), true, None);
+        let code = challenge.to_string();
+        let challenged = code.parse::<Challenge>().ok();
+        result.record(
+            challenged
+                .is_none_or(|x| {
+                    x.to_string() != code || x.target(sinners).is_none_or(|x| x.name != sinner.name)
+                })
+                .then_some(|| {
+                    format!(
+                        "The challenge code {code} for {} reads back wrong",
+                        sinner.name
+                    )
+                }),
+        );
+    }
+}
+
+/// Plays every target with a solver described by `config`, checking that it
+/// finds it in at most as many guesses as there are sinners
+fn check_solver(matrix: &Arc<GuessMatrix>, config: SolverConfig, result: &mut CheckResult) {
+    let name = format!(
+        "{}{}",
+        config.strategy,
+        if config.probe { "+probe" } else { "" }
+    );
+    for target in matrix.sinners() {
+        let mut solver = config.solver(Arc::clone(matrix));
+        let mut failure = None;
+        for guesses in 1..=matrix.len() {
+            let Some(guess) = solver.next_guess().cloned() else {
+                failure = Some(format!(
+                    "{name} had no guess for {} after {guesses}",
+                    target.name
+                ));
+                break;
+            };
+            if guess.name == target.name {
+                break;
+            }
+            if guesses == matrix.len() {
+                failure = Some(format!("{name} didn't find {} in {guesses}", target.name));
+            }
+            solver.update(target.guess(&guess, matrix.thresholds()), &guess);
+        }
+        result.record(failure.map(|x| move || x));
+    }
+}
+
+/// Runs every [`Check`] with `thresholds` over `datasets` random datasets of
+/// `count` sinners, made from `seed`
+pub fn run(
+    seed: u64,
+    datasets: usize,
+    count: usize,
+    thresholds: &ThresholdConfig,
+) -> [CheckResult; 4] {
+    let mut results = Check::ALL.map(CheckResult::new);
+    let [matched, inverse, round_trips, solvers] = &mut results;
+    let rows = possible_rows();
+    let mut rng = Rng::new(seed);
+    for _ in 0..datasets {
+        let sinners = random_sinners(&mut rng, count);
+        check_results(&sinners, thresholds, matched);
+        check_inverse(&sinners, &rows, thresholds, inverse);
+        check_round_trips(&sinners, &rows, round_trips);
+        let matrix = Arc::new(GuessMatrix::new(sinners, *thresholds));
+        for probe in [false, true] {
+            for strategy in Strategy::ALL {
+                let config = SolverConfig {
+                    strategy,
+                    depth: 1,
+                    probe,
+                    seed: rng.next_u64(),
+                    tie_break: TieBreak::default(),
+                };
+                check_solver(&matrix, config, solvers);
+            }
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{random_sinners, run, MAX_SINNERS};
+    use crate::compare::ThresholdConfig;
+    use crate::rng::Rng;
+
+    #[test]
+    fn invariants_hold() {
+        for result in run(0x5eed, 3, 24, &ThresholdConfig::default()) {
+            assert!(
+                result.passed(),
+                "{} failed {} of {} cases: {:?}",
+                result.check,
+                result.failed,
+                result.cases,
+                result.failures
+            );
+        }
+    }
+
+    #[test]
+    fn random_sinners_are_capped() {
+        let sinners = random_sinners(&mut Rng::new(1), MAX_SINNERS + 50);
+        assert_eq!(sinners.len(), MAX_SINNERS, "the count is capped");
+    }
+}
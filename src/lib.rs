@@ -0,0 +1,39 @@
+//! The sinner data, game rules and solvers behind ptndle-cli, for tools that
+//! want to play or solve games of [Path to Nowordle](https://ptndle.com/)
+//! without going through the command line.
+//!
+//! A game is played by a [`Player`] trying to guess a target [`Sinner`], with
+//! each guess scored as a [`Guess`]. [`OptimalPlayer`] is the solver.
+
+pub mod aliases;
+pub mod attribute;
+pub mod challenge;
+pub mod checkpoint;
+pub mod compare;
+pub mod context;
+pub mod daily;
+pub mod data;
+pub mod error;
+pub mod fuzzy;
+pub mod game;
+pub mod guess;
+pub mod i18n;
+#[cfg(feature = "selftest")]
+pub mod invariants;
+pub mod matrix;
+pub mod paths;
+pub mod play;
+pub mod progress;
+pub mod query;
+pub mod registry;
+pub mod rng;
+pub mod solver;
+pub mod theme;
+pub mod tree;
+pub mod verbosity;
+pub mod weights;
+
+pub use data::Sinner;
+pub use guess::Guess;
+pub use play::{Game, Player};
+pub use solver::OptimalPlayer;
@@ -1,47 +1,390 @@
-use eyre::eyre;
-use getrandom::getrandom;
+use std::cell::RefCell;
+use std::io::{sink, stdout};
+use std::path::Path;
+use std::process::ExitCode;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::data::load_sinners;
-use crate::flags::{Help, HelpCommand, PtndleCli, PtndleCliCmd, Solve};
-use crate::play::{gather_data, play_game, solve, HumanPlayer};
+use eyre::{eyre, Context};
+use ptndle_core::challenge::Challenge;
+use ptndle_core::checkpoint::Checkpoint;
+use ptndle_core::compare::ThresholdConfig;
+use ptndle_core::daily::Day;
+use ptndle_core::data::{load_sinners, DataSource, Filter, Sinner};
+use ptndle_core::error::{exit_code, Error};
+use ptndle_core::game::GameProfile;
+use ptndle_core::guess::Guess;
+use ptndle_core::matrix::GuessMatrix;
+use ptndle_core::play::{play_game, play_targets, Player, RecordingPlayer, TimedPlayer,
+                        DEFAULT_MAX_GUESSES};
+use ptndle_core::query::Query;
+use ptndle_core::registry::StrategyName;
+use ptndle_core::rng::Rng;
+use ptndle_core::solver::{SolverConfig, Strategy};
+use ptndle_core::tree::DecisionTree;
+use ptndle_core::weights::Weights;
 
-mod compare;
-mod data;
+use crate::chart::write_svg;
+use crate::config::ConfigFile;
+use crate::console::{banner, status, verbose, warning};
+use crate::flags::{Engine, Format, Gather, Help, HelpCommand, Play, PtndleCli, PtndleCliCmd,
+                   Replay, Serve, Solve, StrategyNames};
+use crate::output::{copy_to_clipboard, escape_markdown, multi_share_text, print_json, share_text,
+                    write_csv, write_markdown, write_traces, MultiPlayReport, PlayReport};
+use crate::saved::SavedGame;
+use crate::server::serve;
+use crate::shell::{gather_data, print_analysis, print_race, solve, solve_once, solver_race,
+                   HumanPlayer, RaceStep, SolveStrategy};
+use crate::stats::{format_time, new_id, GameRecord, GuessRecord, Stats, TimeRecord};
+use crate::tui::{Terminal, TuiPlayer};
+
+mod analyze;
+mod bench;
+mod cache;
+mod chart;
+mod completions;
+mod config;
+mod console;
+mod dataset;
+mod engine;
 mod flags;
-mod guess;
-mod play;
+mod openers;
+mod output;
+mod prompt;
+mod saved;
+mod script;
+#[cfg(feature = "selftest")]
+mod selftest;
+mod server;
+mod settings;
+mod shell;
+mod simulate;
+mod sinners;
+mod stats;
+mod tournament;
+mod tui;
+mod verify;
 
 const HELP_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli help [command]
 
-View in-depth help for a command";
+View in-depth help for a command
+
+When a command fails, the exit code says why, for scripts that run ptndle-cli:
+    1: Any other error
+    2: A flag, sinner name, setting or other input, such as a thresholds file, isn't valid
+    3: The sinner data couldn't be fetched from any data URL
+    4: A file, such as the cached sinner data, couldn't be read or written
+    5: The sinner data couldn't be parsed
+    6: The results given to `solve` rule out every sinner
+When fetching the sinner data fails but the cached data can be used instead, the command
+doesn't fail: a warning is printed and it exits with 0 as usual.";
 
-const GATHER_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli gather
+const GATHER_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli gather [--strategy <strategies>] [--depth <depth>] [--probe]
+                         [--emit-tree <path>] [--csv <path>] [--markdown <path>]
+                         [--chart <path>] [--traces <path>] [--weights <path>] [--resume]
 
 Play every possible game of Path To Nowordle and gather statistical data about
 the solver's performance.
 
+The solver picks its guesses using one of the following strategies:
+    mean:    Minimize the mean number of remaining sinners (default)
+    minimax: Minimize the maximum number of remaining sinners
+    entropy: Maximize the expected information gained from a guess
+    random:  Guess a random remaining sinner, as a baseline for the other strategies
+
+--strategy takes a comma-separated list of strategies, or `all` for every strategy.
+When more than one strategy is given, the summaries of each strategy are printed
+side by side in a table instead.
+
+Any other registered player, such as `blind` or `mean+probe` from `tournament` or one
+registered by a program using ptndle-cli as a library, can be given to --strategy by name
+too. --depth is passed on to them, but only the solver's strategies can be resumed or have
+their tree written with --emit-tree.
+
+With the global --strategy-script <path> flag, a script of your own picks the guesses of a
+player named after the file without its extension, so `--strategy-script my.rhai` adds `my`.
+A script ending in .rhai is run by the Rhai engine built into ptndle-cli (https://rhai.rs).
+It defines a function that is given the names of the sinners that fit every result so far
+and the guesses made so far, and returns the name of the sinner to guess:
+    fn guess(candidates, history) {
+        // history is like [#{name: \"L.L.\", row: \"^ 0 0 vv 0\"}]
+        candidates[0]
+    }
+A Rhai script that takes more than 50 million operations to pick a guess, or calls functions
+more than 64 deep, is stopped and gives up the game.
+Any other file is run as a program, which can be written in any language, such as a script
+with a #! line, and is started for every game. Before each guess, it is sent a line of JSON
+with the same information:
+    {\"candidates\": [\"OwO\"], \"history\": [{\"name\": \"L.L.\", \"row\": \"^ 0 0 vv 0\"}]}
+and answers with a line with the name of the sinner to guess. Returning or answering with an
+empty name gives up the game.
+
+By default the solver only looks at the result of its next guess. With --depth,
+the solver instead looks that many guesses ahead, minimizing the mean or maximum
+number of guesses it takes to find the target. Depths above 2 get slow.
+
+With --probe, the solver may guess sinners that have already been ruled out when
+they narrow down the remaining sinners better than any remaining sinner would.
+
 The results of playing each game are sent to stdout along with a summary of the gathered data
 containing the following information:
     - The first sinner the solver chooses to play
     - The maximum number of guesses it takes to guess any sinner
     - The distribution of the number of guesses it takes to guess sinners
-    - The sinners that take the maximum number of guesses to guess
-    - The mean number of guesses it takes to guess a sinner";
+    - The sinners that take the maximum number of guesses to guess, and the guesses the
+      solver made against each of them
+    - The mean number of guesses it takes to guess a sinner
+Games a player gave up, such as when a --strategy-script program answers with an empty line,
+are counted separately and left out of the maximum, the distribution and the mean.
+
+With --csv, a row for every game is written to a CSV file with the strategy, the target, the
+number of guesses taken and the sinners guessed, separated by >. A summary of each strategy
+with the same information as the table is written to a file next to it with -summary added
+to its name, such as results-summary.csv for results.csv.
+
+With --traces, the games against the sinners that take the maximum number of guesses are
+written to a file for each strategy, with every guess the solver made and the result it
+got, written the same way they are entered in `solve`, such as \"^^ 0 0 ~ 1\". This shows
+why those sinners are hard to find, for tuning the strategies.
+
+With --markdown, a report is written to a markdown file with a table comparing the
+strategies, the distribution of the number of guesses for each strategy and the sinners
+that take the maximum number of guesses, ready to paste into a GitHub discussion or the
+community wiki.
+
+With --chart, the same results are drawn as a chart in an SVG file: the percentage of
+sinners that take each number of guesses, with a bar for each strategy side by side, and
+the mean and maximum number of guesses of each strategy under it. SVG files can be opened
+in a browser or embedded in posts, and converted to PNG with most image editors.
+
+While each strategy plays, a progress bar is drawn on stderr, or a line of JSON after each
+game with the global `--progress json`, as in `tournament`. `--progress none` hides it.
+
+Each game is saved to a checkpoint in the cache directory as soon as it ends, and the
+checkpoint is removed once every game has been played. If a run is interrupted, such as
+with Ctrl-C, run the same command again with --resume to pick up where it left off: the
+games in the checkpoint aren't played again, as long as the sinner data, --weights and
+thresholds haven't changed since. Otherwise, or without --resume, every game is played.
+
+With the global --json flag, the game logs are skipped and a JSON array is printed instead,
+with the summary and the number of guesses for every target for each strategy.
+
+With --emit-tree, the solver's full decision tree is also written to a JSON file. Each node
+of the tree has the sinner to guess, the number of sinners that could be the target, and a
+branch for every possible result of the guess other than winning. Results are written the
+same way they are entered in `solve`, such as \"^^ 0 0 ~ 1\".
+
+With --weights, the solver expects some sinners to be the target more often than others,
+such as when the website picks some sinners more often or hasn't picked a sinner recently.
+Each line of the file is a sinner and its weight, such as \"Hella: 2.5\", and lines starting
+with # are skipped. Sinners that aren't listed have a weight of 1, or the weight on a
+\"*: <weight>\" line. Only the ratios between weights matter, so counts of how often each
+sinner was picked work as they are. Weights must be positive, so every sinner stays possible.
+The expected number of remaining sinners and the information gained from a guess count each
+target by its weight. Every sinner is still played once, and the decision tree is rebuilt
+instead of cached.";
 
-const PLAY_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli play
+const PLAY_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli play [--seed <seed> | --daily] [--max-guesses <n>]
+                       [--copy] [--analyze] [--targets <n>] [--pool <conditions>] [--timed]
+                       [--endless] [--race] [--strict] [--compact] [--show-values]
+                       [--resume] [--challenge <code>]
 
 Play a game of Path to Nowordle from the terminal
 
+--seed <seed>:
+    Picks the target from the given number instead of randomly. Playing with the same
+    seed and the same sinner data always gives the same target, so a seed can be shared
+    with a friend to play the same game.
+
+--daily:
+    Plays the daily puzzle for the current date in UTC. The target is picked from the
+    sinners sorted by name with the number of days since 1970-01-01 as the seed, so
+    everyone gets the same daily. Results are saved and each daily can only be played
+    once. Can't be combined with --seed or --filter, but --exclude and --only still narrow
+    the pool so it can match the website's.
+
+--max-guesses <n>:
+    The number of guesses you get before the game is lost and the target is revealed.
+    Defaults to 6, the same as ptndle.com. 0 allows unlimited guesses.
+
+--copy:
+    Copies the share text to the clipboard when the game ends. This uses pbcopy on macOS,
+    clip on Windows, and wl-copy, xclip or xsel on Linux, whichever is installed.
+
+--analyze:
+    When the game ends, compares each of your guesses to what the solver would have
+    guessed with the same information. The expected number of sinners left after each
+    guess is shown, along with your accuracy: how close your guesses were to the
+    solver's on average.
+
+--targets <n>:
+    Hides n different sinners instead of one, like the website's duos mode with 2. Every
+    guess is scored against each target that hasn't been found yet, with a numbered row
+    for each, and the game is won once every target has been guessed. You get one extra
+    guess for each extra target unless --max-guesses is given. `hint` covers each target
+    left. The share text puts the rows for the targets side by side, blank once a target
+    is found. With --json, the summary has the targets and the guess each was found on.
+    Games with more than one target aren't recorded in the stats unless they are timed, and
+    can't be combined with --daily or --analyze.
+
+--pool <conditions>:
+    Practices on a smaller pool of sinners, to learn the differences between similar ones.
+    Conditions are comma-separated and use the same keys and values as the flags of
+    `sinners`, such as \"alignment=Greed\", \"birthplace=Syndicate\" or
+    \"tendency=Fury,height=175..\". The target is picked from the sinners that match, and
+    guesses, Tab completion and hints only cover them. Can't be combined with --daily.
+
+--timed:
+    Times each guess, from the prompt appearing to the guess being entered, and shows the
+    time since the game started in the prompt. When the game ends, the total time and the
+    time of each guess are printed and saved, and `stats` lists your fastest wins for each
+    number of targets. Timed games with more than one target are saved too. With --json,
+    the summary has the total time in milliseconds.
+
+--endless:
+    Starts a new game with a new target as soon as one ends, until you enter `quit`. After
+    each game, a tally of the session is printed with the games won, the mean number of
+    guesses and the current streak, and `quit` ends the session with a summary. Each game
+    is recorded in the stats like any other. With --seed, the whole session of targets is
+    the same every time. Can't be combined with --daily or --json.
+
+--race:
+    Races the solver on the same target. After each of your guesses, the guess the solver
+    made at the same point in its own game is shown with how many sinners it has left, and
+    once it finds the target it says so without naming it. When the game ends, both games
+    are listed side by side with the sinners left after each guess, followed by who won.
+    With --json, the summary has the number of guesses the solver took. Can't be combined
+    with --targets.
+
+--strict:
+    Refuses to guess a sinner that the results of earlier guesses already ruled out. By
+    default, such a guess prints a note saying which guess ruled the sinner out, like
+    \"Note: Langley was already ruled out by guess 2\", and asks whether to guess it anyway.
+    With --targets, a guess is only ruled out once it can't be any target left.
+
+--compact:
+    Prints each result on its own line after the name of the sinner guessed, without the
+    column headers. By default, a header line with the name of each column is printed
+    when the game starts, and each result is printed on the same line as the name of the
+    sinner guessed, or the number of the target with --targets.
+
+--show-values:
+    After each guess, shows the value of each attribute of the sinner guessed next to its
+    result, like \"Height 172cm  ↑\", the same as the website does. With --targets, there
+    is a line for each target left.
+
+--resume:
+    Resumes the game that was left before it ended. Games with one target are saved after
+    each guess in the Path-To-Nowordle-CLI folder of your data directory, with the target
+    obfuscated so that a look at the file doesn't spoil it: the daily in
+    `saved_daily.json` and any other game in `saved_game.json`, so that starting another
+    game doesn't lose the daily. Leaving with Ctrl-C also records the game in the stats as
+    abandoned, which counts as a loss until it is resumed. --resume guesses the sinners
+    that were guessed before again and carries on from there, picking the saved daily if
+    there is one. --daily also picks up today's daily where it was left. The guesses
+    allowed are those of the saved game, and other flags, such as --strict, apply to the
+    resumed game as usual. Can't be combined with --seed, --daily, --targets, --pool or
+    --endless.
+
+--challenge <code>:
+    Plays the game a challenge code is for. When a game with one target ends, its code is
+    printed after the share text, like `Challenge code: 4C3D-7PQA`. The code holds the
+    target, the number of guesses allowed, whether --strict was used and the --pool, so
+    anyone can play the same puzzle later, with those settings instead of their own, and
+    compare share texts, which name the code. Codes are case-insensitive and the dashes
+    can be left out. The target is only stored as a hash of its name, so the code works
+    with any sinner data that has the target, and doesn't spoil it. Dailies don't get a
+    code, as everyone gets the same daily anyway. Can't be combined with --seed, --daily,
+    --targets, --pool, --endless or --resume.
+
+With the global --lang flag, or the `lang` setting of `config`, the messages and column
+headers are shown in Chinese (`zh`) or Japanese (`ja`). The sinners' names are translated
+using `translations.json` next to `aliases.json`, a JSON object from each language to the
+names to use for it, like {\"zh\": {\"Hella\": \"赫拉\"}}. Guesses and `info` take the name
+in any language, but share texts and JSON summaries stay in English.
+
+When the game ends, a spoiler-free share text is printed with a row of emoji squares
+for each guess, like on the website. With the global `--format discord` flag, anything
+Discord would read as markdown in it is escaped and the challenge code is put in backticks,
+so that a bot can post the output as it is.
+
+With the global --json flag, a JSON summary with the target, the number of guesses taken
+(null if the game was lost), the date of the daily and the share text is printed when the
+game ends.
+
 You will be put into an interactive shell with the following commands:
 
 info [sinner]:  View info on a sinner
 guess [sinner]: Guess a sinner
-quit:           Quit";
+compare [a] [b]: Show the result of guessing a with b as the target and the other way around,
+                without using a guess. Separate names with a comma if they have spaces
+hint:           Show how many sinners could still be the target
+hint full:      Show the best next guess as well
+list:           List every sinner that could still be the target
+board:          Show every guess so far as a table, with the values and results of each
+giveup:         End the game as a loss and show the target's attributes
+giveup full:    Show the guesses the solver would have found the target with as well
+reveal:         The same as giveup
+quit:           Quit
+
+Commands from earlier sessions can be recalled with the up arrow or searched with Ctrl-R.
+They are kept in `play_history.txt` in the Path-To-Nowordle-CLI folder of your data
+directory.
+
+Sinners can be named by common nicknames, such as `chelsea` for Countess Chelsea. More
+nicknames can be added in `aliases.json` in the Path-To-Nowordle-CLI folder of your config
+directory, as a JSON object from nickname to name, like {\"bestgirl\": \"Hella\"}. Nicknames
+work anywhere a sinner is named, including `solve` and --filter. Names and nicknames are
+matched ignoring case, accents and full-width characters, so `ｎｏｘ` is NOX, and Tab
+completes them the same way.";
 
 const SOLVE_IN_DEPTH_HELP: &str = "
-USAGE: ptndle-cli solve [guesses]
+USAGE: ptndle-cli solve [guesses] [--strategy <strategy>] [--depth <depth>] [--probe]
+                        [--best <n>] [--explain] [--non-interactive] [--guided]
+                        [--weights <path>]
 
 Solve a game of Path to Nowordle from an optional set of starting guesses.
+The strategy, depth, probe and weights options used by the solver are the same as in
+`gather`.
+
+When --strategy is a registered player rather than one of the solver's strategies, that
+player recommends the guesses, and --best and --explain rank them with the `mean` strategy.
+
+The solver's decision tree is stored in the cache directory the first time a set of
+options is used, so later solves are instant. It is rebuilt whenever the sinner data changes.
+
+When several guesses are equally good, the solver picks the one whose name comes first,
+and then the one with the lowest code, so that the recommendations don't change when the
+sinner data lists the sinners in another order. The global --tie-break flag picks another
+rule: `code` for the lowest code first, with NOX last, then the name, or `order` for the
+sinner that comes first in the sinner data. Ties in the lists of --best, --explain and the
+candidates are broken the same way. The random strategy still guesses at random.
+
+--best <n>:
+    Lists the n best guesses along with the recommendation, with the average and maximum
+    number of sinners that remain after each one. Guesses are ranked by looking one guess
+    ahead, so with --depth the recommendation may not be at the top.
+
+--explain:
+    Shows why the best guesses are ranked where they are: for each of them, every row it
+    could give and the sinners that would remain after that row, largest group first. The
+    n guesses from --best are explained, or the best 3 without it. With --non-interactive
+    and --json, each of the best guesses gets a partition field with the groups. While
+    solving, `explain` does the same for the current recommendation.
+
+--non-interactive:
+    Prints the recommendation and the remaining sinners after the given guesses and exits
+    instead of prompting for rows, for use in scripts. With the global --json flag, the
+    output is a single JSON object with the fields recommendation, solved, candidates and
+    best. With the global `--format discord` flag, the output is put in a code block
+    without colors, so that a bot can post it to Discord as it is with the columns lined up.
+
+--guided:
+    Asks for the sinner guessed and then each entry of the row on its own, instead of the
+    whole row on one line. Each entry is checked as soon as it is entered, and Tab shows
+    what can be entered. Press Enter at the sinner prompt if you guessed the recommended
+    sinner, and Ctrl-C to start the row over.
 
 Guesses are made up of 5 whitespace-separated components, Code (comparison),
 Alignment (boolean), Tendency (boolean), Height (comparison), and Birthplace (boolean).
@@ -49,14 +392,485 @@ Booleans are entered as 0 or 1 and comparisons are entered as follows:
 
     N/A:         x
     Correct:     =
-    Far Less:    vv
-    Less:        v
-    Near:        ~
-    Greater:     ^
-    Far Greater: ^^
+    Far Less:    vv or ↓↓
+    Less:        v or ↓
+    Near:        ~ or ≅
+    Greater:     ^ or ↑
+    Far Greater: ^^ or ↑↑
 
 An example input for a guess is ^^ 0 0 ~ 1 and an example input for the guesses argument
-is \"L.L.:^ 0 0 vv 0,Angell:^^ 0 0 vv 0\"";
+is \"L.L.:^ 0 0 vv 0,Angell:^^ 0 0 vv 0\". Rows printed by this program, such as by `play`,
+can be pasted as they are, colors included.
+
+Rows can also be entered as the emoji from ptndle.com's share text, such as ⏫🟥🟥🟨🟩 for
+^^ 0 0 ~ 1, with ⬛ for a code that isn't compared. The share text doesn't name the sinners
+guessed, so each row is for the sinner the solver recommended at that point. If those are
+the sinners you played, the whole share text can be pasted at once, and lines that aren't
+rows, like the title, are skipped.
+
+After each row, the sinners that could still be the target are listed with the chance that
+each one is the target, most likely first. Every sinner is equally likely unless --weights
+is given, and ties are broken by how many of the remaining sinners would single each one out
+if guessed.
+
+When solving, rows are for the sinner the solver recommended. If you guessed a different
+sinner, enter the row in the same name:row form, such as \"Angell: ^^ 0 0 vv 0\".
+Enter `undo` to remove the last row entered, `history` to list every row entered or `explain`
+to see why the recommendation is best. In a terminal, sinner names and row symbols can be
+completed with Tab, and rows from this and earlier sessions recalled with the up arrow or
+searched with Ctrl-R. They are kept in `solve_history.txt` in the Path-To-Nowordle-CLI folder
+of your data directory.";
+
+const STATS_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli stats
+       ptndle-cli stats export <file>
+       ptndle-cli stats import <file>
+
+View statistics about the games played with `play`, including the number of games
+played, the win rate, the current and maximum number of games won in a row, and the
+number of games won with each number of guesses.
+
+Every game that ends in a win or a loss is recorded in the data directory, including
+daily puzzles. Games that are quit early are not recorded.
+
+With the global --profile flag, such as `--profile alice`, the stats are the profile's own,
+so that people sharing a machine each have their own streaks. A profile keeps its stats,
+shell history and saved games in `profiles/<name>` in the Path-To-Nowordle-CLI folder of
+your data directory, and is created the first time it is used.
+
+Games played with `play --timed` are also listed as personal bests: the 5 fastest wins for
+each number of targets, with the number of guesses and the date.
+
+With the global --json flag, every recorded game is printed as JSON instead.
+
+`stats export` writes every recorded game to a JSON file in the same format as --json, to
+back the stats up or move them to another machine. `stats import` adds the games in such a
+file to the recorded ones, so the stats of two machines can be merged. Games that were
+already recorded are left out, so importing the same file twice changes nothing: a daily is
+already recorded if there is a daily from the same day, and any other game if a game with
+the same id was. Each game is given a random id when it is recorded, so games with the same
+target and guesses on the same day are still told apart. Games recorded before games had ids
+are already recorded if a game on the same day had the same target and guesses. The games are
+kept in the order of the days they were played on, which the streaks are counted in.";
+
+const TUI_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli tui [--seed <seed> | --daily] [--max-guesses <n>] [--copy]
+                      [--analyze]
+
+Play a game of Path to Nowordle in a full-screen terminal interface. The options are the
+same as for `play`.
+
+The screen shows the sinners on the left, the guesses made so far on the right, and the
+sinners that could still be the target below them. Sinners that could still be the target
+are marked with a dot in the list.
+
+Keys:
+    Typing:         Search for a sinner by name
+    Backspace:      Delete the last character of the search
+    Up/Down:        Select a sinner (PageUp/PageDown move 10 at a time)
+    Enter:          Guess the selected sinner
+    Tab:            Toggle showing only the sinners that could still be the target
+    Esc or Ctrl+C:  Quit";
+
+const SERVE_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli serve [--port <port>] [--strategy <strategy>]
+                        [--depth <depth>] [--probe]
+
+Serve the solver as a JSON API over HTTP on localhost, so tools such as Discord bots can
+use it without running the CLI for every request. The solver options are the same as for
+`solve`, and --filter narrows the sinners the server knows about.
+
+Endpoints:
+
+GET /sinners:
+    Returns every sinner with their data.
+
+POST /solve:
+    Takes the rows entered so far, in the same form as `solve`, and optionally the number
+    of top guesses to rank:
+        {\"guesses\": [\"Hella: vv 0 0 ^ 0\"], \"best\": 3}
+    Returns the same report as `solve --non-interactive --json`: the recommendation,
+    whether it's solved, the remaining candidates and the top guesses.
+
+Errors are returned with a 4xx status and a JSON body like {\"error\": \"...\"}.
+
+Up to 32 connections are handled at once, and more are answered with 503 until one is done.
+A request that isn't sent in full within 5 seconds is answered with 408.";
+
+const ENGINE_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli engine [--strategy <strategy>] [--depth <depth>]
+                         [--probe]
+
+Drive the solver from another program, such as a bot or a different frontend, with a
+line-based protocol similar to UCI for chess engines. The host writes one command per line
+to stdin and the engine answers on stdout. Warnings are only ever written to stderr. The
+solver options are the same as for `solve`.
+
+Commands:
+
+ptndle:              Answered with `id name ptndle-cli <version>`, an `option <name> <value>`
+                     line for each solver option, and `ptndleok`
+isready:             Answered with `readyok`
+newgame:             Forgets every row and starts a new game
+go:                  Answered with `info candidates <n>`, `candidates <names>` with the
+                     names separated by commas, then `bestguess <name>`. `solved` is added
+                     after the name if it is the only candidate left, and the name is
+                     `none` if the rows contradict each other.
+result <row>:        The result of guessing the last `bestguess`, such as ^^ 0 0 ~ 1
+result <name: row>:  The result of guessing some other sinner, such as Hella: ^^ 0 0 ~ 1
+undo:                Forgets the last row
+quit:                Exits
+
+Invalid commands are answered with `error <message>`.";
+
+const SINNERS_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli sinners [--alignment <alignment>] [--tendency <tendency>]
+                          [--birthplace <birthplace>] [--height <range>] [--sort <key>]
+
+List the sinners with their code, alignment, tendency, height and birthplace in a table,
+as a reference while playing. Every condition given has to match, and --filter narrows the
+sinners the same way as for other commands.
+
+--alignment, --tendency and --birthplace take a name, ignoring case, such as `greed`.
+
+--height takes an inclusive range in cm. Either end can be left out:
+    160..175:  Between 160cm and 175cm
+    ..165:     165cm or shorter
+    180..:     180cm or taller
+    168:       Exactly 168cm
+
+--sort orders the sinners by `name` (default), `code`, `alignment`, `tendency`, `height` or
+`birthplace`, then by name. NOX, which has no numeric code, comes last by code.
+
+With --json, the sinners are printed as a JSON list instead.";
+
+const INFO_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli info <name>
+
+Show a sinner's code, alignment, tendency, height and birthplace, and their aliases, without
+starting a game. Handy for quick lookups while playing on the website. The name can be an
+alias or a close misspelling, and doesn't need quotes if it has spaces.
+
+The thresholds show how the arrows for code and height are picked when the sinner is the
+target. A guess within the near threshold gets ≅, and one further away than the far
+threshold gets ↑↑ or ↓↓ instead of ↑ or ↓. Sinners further from the most common height get
+wider height thresholds, and sinners with higher codes get wider code thresholds.
+
+Each threshold is `base + per_unit × value`, where the value is the target's code, or how
+far their height is from the most common height. If ptndle.com changes these, the global
+--thresholds flag takes a JSON file with the new ones. Anything left out keeps the website's
+current value, which are:
+    {
+        \"code_near\": { \"base\": 5, \"per_unit\": 0.1 },
+        \"code_far\": { \"base\": 50, \"per_unit\": 0.35 },
+        \"height_near\": { \"base\": 3, \"per_unit\": 0.1 },
+        \"height_far\": { \"base\": 15, \"per_unit\": 0.35 },
+        \"most_common_height\": 168
+    }
+
+With --json, the profile is printed as JSON instead.";
+
+const BANDS_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli bands <name>
+
+Show which targets give each arrow for code and height when the sinner is guessed, as a
+table with a row for every result from ↓↓ to ↑↑. This works out the threshold formulas for
+every possible target, so it's a reference for reading the arrows while playing: after
+guessing the sinner and getting ↑ for height, the target's height is in the ↑ row.
+
+The thresholds depend on the target rather than the guess, which is why `info` shows them
+for the sinner as the target, while this shows them for the sinner as the guess. Because of
+that, a result can come from more than one range of values, such as ≅ on both sides of =.
+Ranges are written with `≤` and `≥` when they reach the lowest or highest possible value.
+
+Targets without a numeric code, like NOX, give x for the code. The global --thresholds
+flag changes the formulas like everywhere else. With --json, the ranges are printed as
+JSON instead, with a band for every result as it is entered in `solve`.";
+
+const DATA_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli data diff [old] [new]
+       ptndle-cli data snapshot save <name>
+       ptndle-cli data snapshot load <name>
+       ptndle-cli data snapshot list
+
+Manage the sinner data.
+
+diff:
+    Shows the sinners added, removed and changed between two versions of the sinner data,
+    with the fields that changed for each. New sinners change how the solver plays, so this
+    shows what an update changes before trusting new stats.
+
+    With no files, the cache is compared with the latest data, which is fetched without
+    updating the cache. Use --force-cache-update on another command to update it. With
+    --data, the cache is compared with that file instead. With one file, the cache is
+    compared with the file, and with two, the files are compared with each other.
+
+    Sinners are matched by name, so a renamed sinner shows up as removed and added. With
+    --json, the differences are printed as JSON instead.
+
+snapshot save <name>:
+    Saves the current sinner data as a snapshot called <name>, which can have letters,
+    digits, `-`, `_` and `.`. Snapshots never change once saved, so the global --dataset
+    flag can reproduce results such as `gather`'s against the same data after the sinners
+    change upstream:
+        ptndle-cli --dataset before-summer gather
+    The data is saved whole, even if --filter is given.
+
+snapshot load <name>:
+    Replaces the cached sinner data with the snapshot. It is used until the cache is next
+    updated, which is after the cache TTL unless --offline is used.
+
+snapshot list:
+    Lists the saved snapshots with how many sinners they have. Snapshots are kept in the
+    `snapshots` folder of the cache directory and aren't removed by `cache clear`.
+
+Release dates:
+    A sinner in the data can have a `released` field with the date it was added to the game,
+    such as `\"released\": \"2024-06-01\"`. The data from ptndle.com doesn't have them, so
+    they have to be added to a file given with --data or saved as a snapshot. With the global
+    --as-of flag, sinners released after that date are left out, which gives the pool as it
+    was back then:
+        ptndle-cli --dataset dated --as-of 2024-06-01 gather
+    Sinners without a date are always kept.";
+
+const VERIFY_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli verify <file>
+
+Check the guess logic against rows recorded from the real website, to catch it drifting from
+ptndle.com, such as after the website changes its thresholds. Each line of the file is a
+target, the sinner guessed and the row the website showed, in the same format as `solve`:
+    # target; guess: row
+    Hella; L.L.: ~ 0 0 v 0
+    Shalom; Mr. Fox: vv 0 0 v 0
+Blank lines and lines starting with `#` are ignored, and names can be aliases.
+
+Every row that doesn't match is printed with the row this program gives and the columns that
+differ, and the command fails if any don't match. Rows next to the near and far thresholds are
+the most useful to record. With --json, the results are printed as JSON instead.";
+
+const SELFTEST_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli selftest [--datasets <n>] [--sinners <n>]
+                           [--seed <seed>]
+
+Check the invariants the guess logic and the solvers rely on, over --datasets random datasets
+(20 by default) of --sinners made-up sinners each (40 by default, and 200 at most). The codes
+and heights of the sinners are bunched up, so that many pairs land next to the near and far
+thresholds. The checks are:
+    results      guessing any sinner with any target gives a row the target matches
+    inverse      that row is the only one the target matches for that guess
+    round-trips  rows, `name: row` lines, emoji and challenge codes read back as written
+    solvers      every strategy, with and without --probe, finds every target in at most as
+                 many guesses as there are sinners
+
+The datasets are made from --seed, so the same seed always checks the same sinners. The seed
+is random when not given, and printed so that a failure can be reproduced. The first few
+failures of each check are printed, and the command fails if any check does. The thresholds
+are the installed ones, so --thresholds and --game profiles can be checked too. Neither the
+sinner data nor the network is used. With --json, the results are printed as JSON instead.";
+
+const COMPLETIONS_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli completions <bash|zsh|fish|powershell>
+
+Print a script that completes the commands and flags of ptndle-cli in a shell, along with the
+values of flags that take one of a few values, such as --strategy and --theme, and the names
+of sinners for `info` and --exclude. Load it when the shell starts:
+    bash:       source <(ptndle-cli completions bash)               in ~/.bashrc
+    zsh:        source <(ptndle-cli completions zsh)                in ~/.zshrc
+    fish:       ptndle-cli completions fish | source                in ~/.config/fish/config.fish
+    powershell: ptndle-cli completions powershell | Out-String | Invoke-Expression   in $PROFILE
+
+The scripts are made from this version's flags, so print them again after updating. The sinner
+names aren't part of the script: it runs `ptndle-cli --offline completions --names` to get them,
+which prints the names and aliases of every sinner in the cached data, one per line.";
+
+const CONFIG_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli config get [setting]
+       ptndle-cli config set <setting> <value>
+       ptndle-cli config unset <setting>
+
+View or change the config file, which sets defaults for flags that aren't given. Flags given on
+the command line take precedence over the config file, which takes precedence over the
+built-in defaults. The config file is `config.toml` in the Path-To-Nowordle-CLI folder of
+your config directory, and `config get` prints where it is.
+
+With the global --profile flag, `config set` and `config unset` change the profile's own
+config file, `profiles/<name>.toml` next to the shared one. Its settings take precedence
+over the shared file's, and settings it doesn't have come from the shared file.
+
+The global --game flag plays Path to Nowordle with other sinner data, such as a mirror of
+ptndle.com's or a fan-made pool with thresholds of its own. The data is described by a JSON
+file in the `games` folder of the Path-To-Nowordle-CLI folder of your config directory, such
+as `games/mirror.json` for `--game mirror`:
+    {
+        \"title\": \"Path to Nowordle (mirror)\",
+        \"data_urls\": [\"https://example.com/characters.json\"],
+        \"thresholds\": {\"code_near\": {\"base\": 5, \"per_unit\": 0.1}}
+    }
+The data has to be in the same format as ptndle.com's, with the same attributes and values,
+since guesses are always scored on Path to Nowordle's code, alignment, tendency, height and
+birthplace. Other guessing games with attributes of their own can't be played this way.
+`thresholds` is in the format of a --thresholds file, and the website's are used if it's left
+out. Each game has its own cache, stats and saved games in `games/<name>`, and the data-url
+setting only applies to `ptndle`, the built-in game.
+
+The settings are named after the flags they set:
+    strategy:    The strategy for `solve`, `serve`, `engine` and `gather`, such as `entropy`
+    theme:       The colors to use, such as `colorblind`. Takes precedence over `NO_COLOR`
+    data-url:    The URLs to fetch the sinner data from, in the order they are tried
+    cache-ttl:   How long the cached sinner data is used before it is fetched again, like `7d`
+    max-guesses: The number of guesses in `play` and `tui`, where 0 is no limit
+    strict:      Whether `play` refuses guesses that earlier results ruled out, `true` or `false`
+    exclude:     The sinners to leave out of the pool
+    lang:        The language to play in, `en`, `zh` or `ja`
+
+`config set` checks the value before saving it, and takes lists separated by commas. The file
+can also be edited by hand. It is a TOML file with one `setting = value` per line:
+    strategy = \"entropy\"
+    max-guesses = 8
+    strict = true
+    exclude = [\"Hella\", \"Mr. Fox\"]
+Note that `config set` and `config unset` rewrite the file, which drops any comments.";
+
+const BENCH_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli bench [--strategy <strategies>] [--depth <depth>] [--probe]
+                        [--sizes <sizes>] [--seed <seed>]
+
+Time how fast the solver is, so that slowdowns, such as after changing how it looks ahead, can be
+measured without external tools. Every game on the pool is played one at a time with each
+strategy, timing every guess the solver picks. The strategies and --depth and --probe are the
+same as for `gather`, but every strategy is timed by default.
+
+--sizes takes a comma-separated list of pool sizes, to see how the solver scales with the number
+of sinners. Smaller pools are picked from the full pool using --seed, so the same seed always
+times the same sinners. By default, a quarter, a half and all of the pool are timed.
+
+A row is printed for each strategy and pool size with:
+    - The number of sinners in the pool, which is also the number of games played
+    - The number of guesses made across every game
+    - The time taken to play every game, and how many games were played per second
+    - The mean and longest time taken to pick a guess
+    - The mean time taken to pick the first guess, which is usually the slowest
+
+With --json, a JSON array with a row for each strategy and pool size is printed instead, with
+the times in microseconds. Times vary between runs, so compare runs on the same machine.";
+
+const SIMULATE_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli simulate [--games <n>] [--player <players>] [--max-guesses <n>]
+                           [--seed <seed>] [--strategy <strategy>] [--depth <depth>] [--probe]
+
+Play random games with naive players and the solver, to see how much the solver helps compared
+to naive play. The players are:
+    random:  Guess random sinners that haven't been guessed yet, ignoring the results
+    greedy:  Guess a random sinner that fits every result so far, without planning ahead
+    optimal: Guess what the solver recommends
+
+--player takes a comma-separated list of players, or `all` for every player (default). Every
+player plays the same --games games (1000 by default), with targets picked from --seed, so the
+same seed always gives the same games. The seed is random when not given, and printed so that
+the games can be played again.
+
+A game is lost after --max-guesses guesses, which defaults to 6 like ptndle.com, or never with
+0. A table is printed with a column for each player, giving the number of games won in each
+number of guesses, the number of games lost, the percentage of games won and the mean number
+of guesses in the games won.
+
+--strategy, --depth and --probe choose how the optimal player picks its guesses, like in
+`solve`. With --json, the results are printed as JSON instead.";
+
+const TOURNAMENT_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli tournament [--players <players>] [--depth <depth>]
+                             [--random-starts <n>] [--seed <seed>] [--rank-by <mean|worst>]
+                             [--csv <path>]
+
+Play every possible game with every registered player and rank them against each other. The
+registered players are:
+    mean, minimax, entropy, random:  The solver with each strategy, as in `gather`
+    mean+probe, minimax+probe, ...:  The solver with each strategy but random and --probe
+    blind:                           Guesses random sinners, ignoring the results
+
+Programs using ptndle-cli as a library can register more players by name, and so can the
+global --strategy-script flag described in `help gather`. They are ranked along with these.
+
+--players takes a comma-separated list of players to rank instead of all of them. The solvers
+look --depth guesses ahead, which defaults to 1.
+
+With --random-starts, each target is also played that many more times with a random first
+guess that every player is made to take, to see how well the players recover from an opening
+they didn't pick. The first guesses are picked from --seed, which defaults to 0, so every
+tournament with the same flags plays the same games.
+
+The players are ranked by their mean number of guesses, or by the most guesses they took in
+any game with `--rank-by worst`. Ties are broken by the other of the two, then by the number of
+games that took the most guesses, and then by name. A table is printed with each player's rank,
+mean, worst case, number of games at the worst case and number of games that would have been
+lost on ptndle.com. Games a player gave up are counted in a column of their own and left out
+of the rest, and players that gave up fewer games are always ranked higher.
+
+With --csv, the table is also written to a CSV file. With --json, it is printed as JSON instead.
+
+While each player plays, a progress bar is drawn on stderr. With the global `--progress json`,
+a line of JSON is written to stderr instead after each game, with the task, the number of
+games done, the total, the percentage done and the target of the game that just ended.";
+
+const OPENERS_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli openers [--strategy <strategy>] [--top <count>]
+       ptndle-cli openers --book [--opener <name>] [--strategy <strategy>] [--depth <depth>]
+                          [--probe] [--markdown]
+
+Rank every sinner as the first guess, to compare openers rather than only seeing the one the
+solver picks. Each sinner is scored by the results it could give for every possible target:
+    Expected: The expected number of sinners remaining after guessing it
+    Worst:    The most sinners that could remain after guessing it
+    Entropy:  The expected information gained from guessing it, in bits
+
+The sinners are ranked by --strategy, like the solver picks its guesses: `mean` (default)
+ranks by the expected number remaining, `minimax` by the most remaining and `entropy` by the
+information gained. The other scores break ties. Only the best --top sinners are printed if
+it is given. With --json, the ranking is printed as JSON instead.
+
+With --book, a cheat-sheet for an opener is printed instead: every result the opener can give,
+with the number of sinners left, the best sinner to guess next and the sinners that could be
+the target. The opener is the best one under --strategy unless --opener is given, and the next
+guesses are picked like `solve` does with the same --strategy, --depth and --probe. The book is
+printed as a table, as a Markdown table with --markdown, or as JSON with --json.";
+
+const ANALYZE_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli analyze confusions [--top <count>]
+       ptndle-cli analyze attributes
+
+Find out what makes some sinners hard to find, for players and for whoever maintains the
+sinner data. The global pool flags, such as --filter and --exclude, narrow down the sinners
+analyzed.
+
+confusions:
+    Guesses every sinner against every pair of sinners, and prints the pairs that give the
+    same result against the most guesses, which are the hardest to tell apart. Each pair
+    is printed with the share of guesses it can't be told apart by, and the attribute whose
+    cell tells them apart against the most of the other guesses, with the two sinners'
+    values of it. If no attribute does, only guessing one of them tells them apart. Only the
+    --top pairs are printed, 20 by default. With --json, the pairs are printed as JSON
+    instead.
+attributes:
+    Scores how much each attribute (code, alignment, tendency, height and birthplace)
+    narrows down the pool on its own, as if the result of a guess only had its cell. Each
+    attribute is scored like `openers` scores guesses, averaged over every guess:
+        Entropy:  The information gained from the cell, in bits
+        Expected: The expected number of sinners left after seeing the cell
+    The attributes are printed most informative first, followed by the whole result as
+    `all`, for comparison. Attributes tell apart some of the same sinners, so their entropies
+    add up to more than that of the whole result. With --json, the scores are printed as
+    JSON instead.";
+
+const CACHE_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli cache [status|clear|path]
+
+Inspect or clear the cache, which holds the sinner data fetched from ptndle.com and the
+solver's decision trees.
+
+status:
+    Shows the cache directory, how long ago the sinner data was fetched and whether it is
+    outdated, and every cached file with its size. This is the default.
+clear:
+    Deletes everything in the cache. The sinner data is fetched and the decision trees are
+    built again when next needed.
+path:
+    Prints the path of the cache directory, for use in scripts.
+
+The sinner data is fetched again once it is older than the cache TTL, 24 hours by default.
+Change it with the global --cache-ttl flag, such as `--cache-ttl 7d`. The status uses
+the same TTL to tell whether the data is outdated.";
+
+const REPLAY_IN_DEPTH_HELP: &str = "USAGE: ptndle-cli replay [game] [--annotate]
+
+Replay a game played with `play` guess by guess. Games are numbered from 1 in the
+order they were played, and the last game is replayed by default.
+
+--annotate:
+    Before each guess, shows what the solver would have guessed instead if it differs.
+    The solver uses the current sinner data, so annotations may be wrong for games
+    played before the data changed.";
 
 const PLAY_WELCOME: &str = r"
       __
@@ -80,6 +894,7 @@ const PLAY_WELCOME: &str = r"
 Welcome to Path to Nowordle CLI edition.
 To guess a sinner, use the `guess` command.
 To view a sinner's info, use the `info` command.
+If you're stuck, use the `hint` command.
 To quit, type `quit` or press Ctrl + C.
 
 You can press tab to attempt to complete a command at any time";
@@ -89,37 +904,1113 @@ fn get_in_depth_help(cmd: &HelpCommand) -> &'static str {
         | HelpCommand::Gather => GATHER_IN_DEPTH_HELP,
         | HelpCommand::Solve => SOLVE_IN_DEPTH_HELP,
         | HelpCommand::Play => PLAY_IN_DEPTH_HELP,
+        | HelpCommand::Stats => STATS_IN_DEPTH_HELP,
+        | HelpCommand::Replay => REPLAY_IN_DEPTH_HELP,
+        | HelpCommand::Tui => TUI_IN_DEPTH_HELP,
+        | HelpCommand::Serve => SERVE_IN_DEPTH_HELP,
+        | HelpCommand::Engine => ENGINE_IN_DEPTH_HELP,
+        | HelpCommand::Cache => CACHE_IN_DEPTH_HELP,
+        | HelpCommand::Sinners => SINNERS_IN_DEPTH_HELP,
+        | HelpCommand::Info => INFO_IN_DEPTH_HELP,
+        | HelpCommand::Bands => BANDS_IN_DEPTH_HELP,
+        | HelpCommand::Data => DATA_IN_DEPTH_HELP,
+        | HelpCommand::Verify => VERIFY_IN_DEPTH_HELP,
+        | HelpCommand::Selftest => SELFTEST_IN_DEPTH_HELP,
+        | HelpCommand::Completions => COMPLETIONS_IN_DEPTH_HELP,
+        | HelpCommand::Config => CONFIG_IN_DEPTH_HELP,
+        | HelpCommand::Bench => BENCH_IN_DEPTH_HELP,
+        | HelpCommand::Simulate => SIMULATE_IN_DEPTH_HELP,
+        | HelpCommand::Tournament => TOURNAMENT_IN_DEPTH_HELP,
+        | HelpCommand::Openers => OPENERS_IN_DEPTH_HELP,
+        | HelpCommand::Analyze => ANALYZE_IN_DEPTH_HELP,
         | HelpCommand::Help => HELP_IN_DEPTH_HELP,
     }
 }
-fn main() -> eyre::Result<()> {
-    let cli = PtndleCli::from_env_or_exit();
+/// Plays every possible game with each strategy in `flags` and prints the
+/// results
+fn gather(
+    Gather {
+        strategy,
+        depth,
+        probe,
+        emit_tree,
+        csv,
+        markdown,
+        chart,
+        weights: _,
+        traces,
+        resume,
+    }: Gather,
+    matrix: &Arc<GuessMatrix>,
+    json: bool,
+) -> eyre::Result<()> {
+    let strategies = strategy.map_or_else(|| vec![<_>::default()], |StrategyNames(x)| x);
+    let options = SolverConfig {
+        depth: depth.unwrap_or(1),
+        probe,
+        tie_break: settings::tie_break(),
+        ..SolverConfig::default()
+    };
+    let factories = strategies
+        .iter()
+        .map(|x| x.factory(options, script::registry(options)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| Error::InvalidInput(e.to_string()))?;
+    // Only the solver's strategies can be checkpointed and have a decision tree
+    let configs = strategies
+        .iter()
+        .map(|x| {
+            x.solver().map(|solver| {
+                SolverConfig {
+                    strategy: solver,
+                    ..options
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    let tree_config = match (&emit_tree, configs.as_slice()) {
+        | (None, _) => None,
+        | (Some(_), [Some(config)]) => Some(*config),
+        | (Some(_), [None]) => {
+            return Err(Error::InvalidInput(
+                "--emit-tree can only be used with a strategy of the solver".to_owned(),
+            )
+            .into());
+        },
+        | (Some(_), _) => {
+            return Err(Error::InvalidInput(
+                "--emit-tree can only be used with a single strategy".to_owned(),
+            )
+            .into());
+        },
+    };
+    let checkpoints = configs
+        .iter()
+        .map(|config| {
+            config
+                .map(|config| Checkpoint::open(&settings::context(), config, matrix, resume))
+                .transpose()
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+    let results = gather_data(matrix, &factories, &checkpoints, json)?;
+    for checkpoint in checkpoints.into_iter().flatten() {
+        checkpoint.remove()?;
+    }
+    if let Some(path) = csv {
+        let summary = write_csv(&path, &results)?;
+        if !json {
+            println!(
+                "Wrote games to {} and a summary to {}",
+                path.display(),
+                summary.display()
+            );
+        }
+    }
+    if let Some(path) = markdown {
+        write_markdown(&path, &results)?;
+        if !json {
+            println!("Wrote a report to {}", path.display());
+        }
+    }
+    if let Some(path) = traces {
+        write_traces(&path, &results, matrix)?;
+        if !json {
+            println!(
+                "Wrote the traces of the hardest sinners to {}",
+                path.display()
+            );
+        }
+    }
+    if let Some(path) = chart {
+        write_svg(&path, &results)?;
+        if !json {
+            println!("Wrote a chart to {}", path.display());
+        }
+    }
+
+    if let (Some(path), Some(config)) = (emit_tree, tree_config) {
+        let tree = DecisionTree::load_or_build(&settings::context(), config, matrix)?.ok_or_else(
+            || Error::InvalidInput("The random strategy has no decision tree".to_owned()),
+        )?;
+        std::fs::write(&path, facet_json::to_string(&tree))
+            .map_err(|e| Error::Io(format!("Failed to write tree to {}: {e}", path.display())))?;
+    }
+    Ok(())
+}
+
+/// Where to load the sinner data from, based on the global flags
+fn data_source(cli: &PtndleCli) -> eyre::Result<DataSource> {
+    let chosen = [
+        ("--data", cli.data.is_some()),
+        ("--dataset", cli.dataset.is_some()),
+        ("--offline", cli.offline),
+    ]
+    .into_iter()
+    .filter_map(|(flag, set)| set.then_some(flag))
+    .collect::<Vec<_>>();
+    // These only make sense when fetching the data
+    let remote_only = [
+        ("--force-cache-update", cli.force_cache_update),
+        ("--data-url", !cli.data_url.is_empty()),
+    ]
+    .into_iter()
+    .find_map(|(flag, set)| set.then_some(flag));
+    match (chosen.as_slice(), remote_only) {
+        | (&[first, second, ..], _) | (&[first], Some(second)) => {
+            return Err(
+                Error::InvalidInput(format!("{first} can't be combined with {second}")).into(),
+            );
+        },
+        | _ => {},
+    }
+    if let Some(path) = &cli.data {
+        return Ok(DataSource::File(path.clone()));
+    }
+    if let Some(name) = &cli.dataset {
+        return dataset::snapshot_source(name);
+    }
+    if cli.offline {
+        return Ok(DataSource::Offline);
+    }
+    Ok(DataSource::Remote {
+        force_update: cli.force_cache_update,
+        ttl: cli.cache_ttl.unwrap_or_default(),
+        urls: if cli.data_url.is_empty() {
+            settings::game().data_urls.clone()
+        } else {
+            cli.data_url.clone()
+        },
+    })
+}
+
+/// The filter from --filter, with the pool narrowed by --exclude, --only and
+/// --as-of
+fn pool_filter(
+    filter: Option<Filter>,
+    exclude: Option<&str>,
+    only: Option<&Path>,
+    as_of: Option<Day>,
+) -> eyre::Result<Filter> {
+    let mut filter = filter.unwrap_or_default();
+    if let Some(exclude) = exclude {
+        filter.exclude = exclude.split(',').map(|x| x.trim().to_owned()).collect();
+    }
+    if let Some(path) = only {
+        let list = std::fs::read_to_string(path)
+            .map_err(|e| Error::Io(format!("Failed to read {}: {e}", path.display())))?;
+        filter.only = list
+            .lines()
+            .filter(|x| !x.trim_start().starts_with('#'))
+            .flat_map(|x| x.split(','))
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .map(str::to_owned)
+            .collect();
+        if filter.only.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "{} doesn't list any sinners",
+                path.display()
+            ))
+            .into());
+        }
+    }
+    filter.as_of = as_of;
+    Ok(filter)
+}
+
+/// The matrix for `sinners`, weighted by the weights file at `path` if there
+/// is one. Names in the file are matched against every sinner in `source`, so
+/// a file can be shared between filtered pools.
+fn weighted_matrix(
+    sinners: Vec<Sinner>,
+    path: Option<&Path>,
+    source: &DataSource,
+) -> eyre::Result<Arc<GuessMatrix>> {
+    let matrix = GuessMatrix::new(sinners, *settings::thresholds());
+    let Some(path) = path else {
+        return Ok(Arc::new(matrix));
+    };
+    let all = load_sinners(&settings::context(), source, &<_>::default())?;
+    let weights = Weights::load(path, &all, settings::names())?;
+    Ok(Arc::new(matrix.with_weights(&weights)))
+}
+
+/// The solver options shared by `solve`, `serve` and `engine`
+fn solver_config(
+    strategy: Option<Strategy>,
+    depth: Option<u8>,
+    probe: bool,
+) -> eyre::Result<SolverConfig> {
+    Ok(SolverConfig {
+        strategy: strategy.unwrap_or_default(),
+        depth: depth.unwrap_or(1),
+        probe,
+        seed: Rng::from_entropy()?.next_u64(),
+        tie_break: settings::tie_break(),
+    })
+}
+
+/// Options for playing a single game
+#[expect(clippy::struct_excessive_bools, reason = "They're independent options.")]
+struct PlayOptions {
+    max_guesses: Option<u8>,
+    /// The date of the daily puzzle, if the game is the daily
+    daily: Option<String>,
+    copy: bool,
+    analyze: bool,
+    /// Whether to play in the full-screen interface
+    tui: bool,
+    /// Whether to time the guesses and save personal bests
+    timed: bool,
+    /// Whether to race the solver on the same target
+    race: bool,
+    /// Whether to refuse guesses that were ruled out
+    strict: bool,
+    /// Whether to print results without column headers
+    compact: bool,
+    /// Whether to show the values of each sinner guessed
+    show_values: bool,
+    json: bool,
+    /// The session the game is part of with `--endless`, which is summarized
+    /// when the player quits
+    session: Option<Rc<RefCell<Session>>>,
+    /// The sinners guessed in the saved game being resumed, which are guessed
+    /// again before asking for more
+    resumed: Vec<String>,
+    /// The query the sinners were narrowed down to with --pool
+    pool: Option<Query>,
+    /// Whether the game is played from a challenge code, which the share text
+    /// names
+    challenge: bool,
+    /// How the share text is formatted
+    format: Format,
+}
+
+/// Prints how long each guess of a timed game took and saves the time, returning
+/// the total in milliseconds
+fn finish_timed(
+    times: &[Duration],
+    targets: usize,
+    guesses: Option<u8>,
+    json: bool,
+) -> eyre::Result<u64> {
+    let guess_ms = times
+        .iter()
+        .map(|x| u64::try_from(x.as_millis()).unwrap_or(u64::MAX))
+        .collect::<Vec<_>>();
+    let record = TimeRecord {
+        id: new_id()?,
+        date: Day::today()?.to_string(),
+        targets,
+        guesses,
+        guess_ms,
+    };
+    let total = record.total();
+    if !json {
+        let splits = times.iter().map(|x| format_time(*x)).collect::<Vec<_>>();
+        println!("Time: {} ({})", format_time(total), splits.join(", "));
+        let best = Stats::load()?
+            .personal_bests(targets)
+            .first()
+            .map(|x| x.total());
+        if guesses.is_some() && best.is_none_or(|best| total < best) {
+            println!("New personal best!");
+        }
+    }
+    Stats::record_time(record)?;
+    Ok(u64::try_from(total.as_millis()).unwrap_or(u64::MAX))
+}
+
+/// Creates the player for a game in the shell with `targets` targets
+fn human_player(sinners: Vec<Sinner>, targets: usize, options: &PlayOptions) -> HumanPlayer {
+    let mut human = HumanPlayer::new(sinners, targets);
+    if options.timed {
+        human = human.timed();
+    }
+    if options.strict {
+        human = human.strict();
+    }
+    if options.show_values {
+        human = human.show_values();
+    }
+    if let Some(session) = &options.session {
+        let session = Rc::clone(session);
+        human = human.on_quit(move || session.borrow().print_summary());
+    }
+    if !options.resumed.is_empty() {
+        human = human.resuming(&options.resumed);
+    }
+    human
+}
+
+/// The saved game guessing `target` before any guesses are made
+fn saved_game(target: &Sinner, options: &PlayOptions) -> SavedGame {
+    SavedGame {
+        target: target.name.clone(),
+        daily: options.daily.clone(),
+        max_guesses: options.max_guesses,
+        guessed: Vec::new(),
+    }
+}
+
+/// Saves `saved` after `guessed` were guessed and records it as abandoned,
+/// for when the game guessing `target` is left with Ctrl-C
+fn abandon(saved: &SavedGame, target: &Sinner, guessed: &[Sinner]) {
+    let saved = SavedGame {
+        guessed: guessed.iter().map(|x| x.name.clone()).collect(),
+        ..saved.clone()
+    };
+    match saved.abandon(target, guessed) {
+        | Ok(()) => status!("Saved the game. Resume it with `ptndle-cli play --resume`."),
+        | Err(e) => warning!("Failed to save the game: {e}"),
+    }
+}
+
+/// Saves the game guessing `target` after each guess and when Ctrl-C is
+/// pressed, so that it can be resumed with `play --resume`
+fn autosave(human: HumanPlayer, target: &Sinner, options: &PlayOptions) -> HumanPlayer {
+    let target = target.clone();
+    let saved = saved_game(&target, options);
+    let in_progress = saved.clone();
+    let human = human.on_guess(move |guessed| {
+        let game = SavedGame {
+            guessed: guessed.iter().map(|x| x.name.clone()).collect(),
+            ..in_progress.clone()
+        };
+        if let Err(e) = game.save() {
+            warning!("Failed to save the game: {e}");
+        }
+    });
+    human.on_abort(move |guessed| abandon(&saved, &target, guessed))
+}
+
+/// A game played by a human, before its result is shared and recorded
+struct Played {
+    /// The number of guesses it took, or `None` if the game was lost
+    guesses: Option<u8>,
+    /// The name of each sinner guessed and its result
+    history: Vec<(String, Guess)>,
+    /// How long each guess took, if they were timed
+    times: Vec<Duration>,
+}
+
+/// Plays a game guessing `target` in the full-screen interface. Leaving with
+/// Esc or Ctrl-C abandons the game like Ctrl-C at the prompt.
+fn play_in_tui(
+    target: &Sinner,
+    sinners: Vec<Sinner>,
+    options: &PlayOptions,
+) -> eyre::Result<Played> {
+    let terminal = Terminal::enter()?;
+    let mut player = RecordingPlayer::new(TuiPlayer::new(terminal, sinners, options.max_guesses));
+    let guesses = play_game(
+        &settings::context(),
+        target,
+        &mut player,
+        settings::thresholds(),
+        options.max_guesses,
+        true,
+        &mut sink(),
+    )?;
+    if player.player.aborted() {
+        let so_far = player.player.guessed();
+        // Leaves the full-screen interface before anything is printed
+        drop(player);
+        eprintln!("Aborted!");
+        abandon(&saved_game(target, options), target, &so_far);
+        std::process::exit(1);
+    }
+    player.player.finish(target, guesses)?;
+    Ok(Played {
+        guesses,
+        history: player.guessed,
+        times: Vec::new(),
+    })
+}
+
+/// Plays a game guessing `target` at the prompt, racing the solver's line
+/// `race` if there is one
+fn play_in_shell(
+    target: &Sinner,
+    sinners: Vec<Sinner>,
+    options: &PlayOptions,
+    race: Option<&[RaceStep]>,
+) -> eyre::Result<Played> {
+    let mut human = autosave(human_player(sinners, 1, options), target, options);
+    if let Some(race) = race {
+        human = human.racing(race.to_vec());
+    }
+    let mut player = TimedPlayer::new(RecordingPlayer::new(human));
+    let guesses = play_game(
+        &settings::context(),
+        target,
+        &mut player,
+        settings::thresholds(),
+        options.max_guesses,
+        options.compact,
+        &mut stdout(),
+    )?;
+    SavedGame::clear(options.daily.is_some())?;
+    if player.gave_up() {
+        player.player.player.reveal(&[target]);
+    }
+    Ok(Played {
+        guesses,
+        times: player.times.take(),
+        history: player.player.guessed,
+    })
+}
+
+/// Plays a game guessing `target`, then prints the share text and records the
+/// result in the stats. Returns the number of guesses it took, or `None` if
+/// the game was lost.
+fn play_target(
+    target: &Sinner,
+    sinners: Vec<Sinner>,
+    options: &PlayOptions,
+) -> eyre::Result<Option<u8>> {
+    let matrix = (options.analyze || options.race)
+        .then(|| Arc::new(GuessMatrix::new(sinners.clone(), *settings::thresholds())));
+    let race = matrix
+        .as_ref()
+        .filter(|_| options.race)
+        .map(|x| solver_race(x, target));
+    let Played {
+        guesses,
+        history,
+        times,
+    } = if options.tui {
+        play_in_tui(target, sinners, options)?
+    } else {
+        play_in_shell(target, sinners, options, race.as_deref())?
+    };
+    let results = history.iter().map(|(_, x)| *x).collect::<Vec<_>>();
+    let challenge = options.daily.is_none().then(|| {
+        Challenge::new(target, options.max_guesses, options.strict, options.pool).to_string()
+    });
+    let label = options
+        .daily
+        .as_deref()
+        .or(challenge.as_deref().filter(|_| options.challenge));
+    let share = share_text(&results, guesses, options.max_guesses, label);
+    let share = match options.format {
+        | Format::Text => share,
+        | Format::Discord => escape_markdown(&share),
+    };
+    let time_ms = if options.timed {
+        Some(finish_timed(&times, 1, guesses, options.json)?)
+    } else {
+        None
+    };
+    if options.json {
+        print_json(&PlayReport {
+            target: target.name.clone(),
+            guesses,
+            daily: options.daily.clone(),
+            share: share.clone(),
+            time_ms,
+            solver_guesses: race.as_ref().and_then(|x| u8::try_from(x.len()).ok()),
+            challenge,
+        });
+    } else {
+        println!("{share}");
+        if let Some(code) = &challenge {
+            match options.format {
+                | Format::Text => println!("Challenge code: {code}"),
+                | Format::Discord => println!("Challenge code: `{code}`"),
+            }
+        }
+        if let (Some(matrix), Some(race)) = (&matrix, &race) {
+            print_race(matrix, target, &history, guesses, race);
+        }
+        if let Some(matrix) = matrix.as_ref().filter(|_| options.analyze) {
+            print_analysis(matrix, &history);
+        }
+    }
+    if options.copy {
+        match copy_to_clipboard(&share) {
+            | Ok(()) => status!("Copied the result to the clipboard"),
+            | Err(e) => warning!("Failed to copy the result to the clipboard: {e}"),
+        }
+    }
+    Stats::record(GameRecord {
+        id: new_id()?,
+        date: Day::today()?.to_string(),
+        target: target.name.clone(),
+        guesses,
+        daily: options.daily.is_some(),
+        guessed: history
+            .into_iter()
+            .map(|(name, result)| {
+                GuessRecord {
+                    name,
+                    result: result.to_ascii(),
+                }
+            })
+            .collect(),
+        abandoned: false,
+    })?;
+    Ok(guesses)
+}
+
+/// Plays a game where every sinner in `targets` has to be found, then prints
+/// the share text. Games with several targets are only recorded in the stats
+/// when they are timed. Returns the number of guesses it took to find every
+/// target, or `None` if the game was lost.
+fn play_multi(
+    targets: Vec<&Sinner>,
+    sinners: Vec<Sinner>,
+    options: &PlayOptions,
+) -> eyre::Result<Option<u8>> {
+    let count = targets.len();
+    let mut player = TimedPlayer::new(human_player(sinners, count, options));
+    let game = play_targets(
+        &settings::context(),
+        targets,
+        &mut player,
+        settings::thresholds(),
+        options.max_guesses,
+        options.compact,
+        &mut stdout(),
+    )?;
+    if player.gave_up() {
+        player.player.reveal(game.targets());
+    }
+    let share = multi_share_text(&game, options.max_guesses);
+    let share = match options.format {
+        | Format::Text => share,
+        | Format::Discord => escape_markdown(&share),
+    };
+    let guesses = game.is_won().then(|| game.guess_num());
+    let time_ms = if options.timed {
+        Some(finish_timed(&player.times.take(), count, guesses, options.json)?)
+    } else {
+        None
+    };
+    if options.json {
+        print_json(&MultiPlayReport {
+            targets: game.targets().iter().map(|x| x.name.clone()).collect(),
+            found: game.found().to_vec(),
+            guesses,
+            share: share.clone(),
+            time_ms,
+        });
+    } else {
+        println!("{share}");
+    }
+    if options.copy {
+        match copy_to_clipboard(&share) {
+            | Ok(()) => status!("Copied the result to the clipboard"),
+            | Err(e) => warning!("Failed to copy the result to the clipboard: {e}"),
+        }
+    }
+    Ok(guesses)
+}
+
+/// Plays today's daily puzzle if it hasn't been played yet
+fn play_daily(source: &DataSource, pool: &Filter, mut options: PlayOptions) -> eyre::Result<()> {
+    let day = Day::today()?;
+    let date = day.to_string();
+    let saved = SavedGame::load(true)?.filter(|x| x.daily.as_ref() == Some(&date));
+    if let Some(saved) = saved {
+        if options.tui {
+            return Err(eyre!(
+                "You left the daily for {date} before it ended. Resume it with `ptndle-cli play \
+                 --daily`"
+            ));
+        }
+        return play_saved(source, pool, options, saved);
+    }
+    if let Some(played) = Stats::load()?.daily(&date) {
+        if played.abandoned {
+            return Err(eyre!(
+                "You left the daily for {date} before it ended, so it counts as lost. Come back \
+                 tomorrow!"
+            ));
+        }
+        let result = played
+            .guesses
+            .map_or_else(|| "lost".to_owned(), |x| format!("won in {x} guesses"));
+        return Err(eyre!(
+            "You already played the daily for {date} and {result}. Come back tomorrow!"
+        ));
+    }
+    if !options.json && !options.tui {
+        banner!("{PLAY_WELCOME}");
+        println!("Daily puzzle for {date}");
+    }
+    let sinner_data = load_sinners(&settings::context(), source, pool)?;
+    let target = day.target(&sinner_data);
+    options.daily = Some(date);
+    play_target(target, sinner_data.clone(), &options)?;
+    Ok(())
+}
+
+/// Resumes a saved game, guessing the sinners that were guessed before again
+fn play_saved(
+    source: &DataSource,
+    pool: &Filter,
+    mut options: PlayOptions,
+    saved: SavedGame,
+) -> eyre::Result<()> {
+    let sinner_data = load_sinners(&settings::context(), source, pool)?;
+    let gone = |name: &str| {
+        eyre!("{name} from the saved game isn't in the sinner data, so the game can't be resumed")
+    };
+    let target = sinner_data
+        .iter()
+        .find(|x| x.name == saved.target)
+        .ok_or_else(|| gone(&saved.target))?;
+    let missing = saved
+        .guessed
+        .iter()
+        .find(|name| sinner_data.iter().all(|x| x.name != **name));
+    if let Some(name) = missing {
+        return Err(gone(name));
+    }
+    if !options.json && !options.tui {
+        banner!("{PLAY_WELCOME}");
+        match &saved.daily {
+            | Some(date) => println!("Resuming the daily puzzle for {date}"),
+            | None => println!("Resuming the saved game"),
+        }
+    }
+    Stats::forget_abandoned(&saved.target)?;
+    options.max_guesses = saved.max_guesses;
+    options.daily = saved.daily;
+    options.resumed = saved.guessed;
+    play_target(target, sinner_data.clone(), &options)?;
+    Ok(())
+}
+
+/// Plays the game `challenge` was made for, with its target and settings
+fn play_challenge(
+    source: &DataSource,
+    filter: &Filter,
+    mut options: PlayOptions,
+    challenge: Challenge,
+) -> eyre::Result<()> {
+    let mut sinner_data = load_sinners(&settings::context(), source, filter)?;
+    if let Some(pool) = challenge.pool() {
+        sinner_data.retain(|x| pool.matches(x));
+    }
+    let target = challenge.target(&sinner_data).ok_or_else(|| {
+        Error::InvalidInput(format!(
+            "The target of the challenge {challenge} isn't in the sinner data. The code may have \
+             been made with newer data or with sinners left out."
+        ))
+    })?;
+    if !options.json && !options.tui {
+        banner!("{PLAY_WELCOME}");
+        println!("Challenge {challenge}");
+    }
+    options.max_guesses = challenge.max_guesses();
+    options.strict = challenge.strict();
+    options.pool = challenge.pool();
+    options.challenge = true;
+    play_target(target, sinner_data.clone(), &options)?;
+    Ok(())
+}
+
+/// Plays a game with `targets` targets picked from `sinners` with `rng`,
+/// returning the number of guesses it took, or `None` if it was lost
+fn play_random(
+    rng: &mut Rng,
+    sinners: &[Sinner],
+    targets: usize,
+    options: &PlayOptions,
+) -> eyre::Result<Option<u8>> {
+    if targets == 1 {
+        let target = &sinners[rng.below(sinners.len())];
+        return play_target(target, sinners.to_vec(), options);
+    }
+    if targets > sinners.len() {
+        return Err(Error::InvalidInput(format!(
+            "There are only {} sinners to pick {targets} targets from",
+            sinners.len()
+        ))
+        .into());
+    }
+    let mut picked = Vec::with_capacity(targets);
+    while picked.len() < targets {
+        let index = rng.below(sinners.len());
+        if !picked.contains(&index) {
+            picked.push(index);
+        }
+    }
+    let picked = picked.into_iter().map(|x| &sinners[x]).collect();
+    play_multi(picked, sinners.to_vec(), options)
+}
+
+/// The running tally of a `play --endless` session
+#[derive(Debug, Default)]
+struct Session {
+    games: usize,
+    wins: usize,
+    /// The total number of guesses in the games that were won
+    guesses: u32,
+    /// The number of games won in a row
+    streak: usize,
+    best_streak: usize,
+}
+
+impl Session {
+    fn record(&mut self, guesses: Option<u8>) {
+        self.games += 1;
+        if let Some(guesses) = guesses {
+            self.wins += 1;
+            self.guesses += u32::from(guesses);
+            self.streak += 1;
+            self.best_streak = self.best_streak.max(self.streak);
+        } else {
+            self.streak = 0;
+        }
+    }
+
+    /// The mean number of guesses in the games that were won
+    #[expect(clippy::float_arithmetic, reason = "statistics")]
+    #[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
+    fn mean(&self) -> f64 { f64::from(self.guesses) / self.wins.max(1) as f64 }
+
+    /// Prints the tally on one line, between games
+    fn print_tally(&self) {
+        println!(
+            "Session: {} won of {} | {:.2} guesses on average | streak {}",
+            self.wins,
+            self.games,
+            self.mean(),
+            self.streak
+        );
+    }
+
+    /// Prints the tally when the session ends
+    fn print_summary(&self) {
+        println!("Session over!");
+        println!("Games played:   {}", self.games);
+        println!("Games won:      {}", self.wins);
+        println!("Mean guesses:   {:.2}", self.mean());
+        println!("Current streak: {}", self.streak);
+        println!("Best streak:    {}", self.best_streak);
+    }
+}
+
+/// Plays games with new targets one after another until `quit` is entered,
+/// keeping a tally of the session
+fn play_endless(
+    rng: &mut Rng,
+    sinners: &[Sinner],
+    targets: usize,
+    mut options: PlayOptions,
+) -> eyre::Result<()> {
+    let session = Rc::new(RefCell::new(Session::default()));
+    options.session = Some(Rc::clone(&session));
+    loop {
+        let game = session.borrow().games + 1;
+        banner!("Game {game}. Enter `quit` to end the session.");
+        let guesses = play_random(rng, sinners, targets, &options)?;
+        session.borrow_mut().record(guesses);
+        session.borrow().print_tally();
+        println!();
+    }
+}
+/// Plays a game from the terminal, in the full-screen interface if `tui` is set
+fn play(
+    Play {
+        seed,
+        daily,
+        max_guesses,
+        copy,
+        analyze,
+        targets,
+        pool,
+        timed,
+        endless,
+        race,
+        strict,
+        compact,
+        show_values,
+        resume,
+        challenge,
+    }: Play,
+    tui: bool,
+    source: &DataSource,
+    filter: &Filter,
+    json: bool,
+    format: Format,
+) -> eyre::Result<()> {
+    let targets = targets.unwrap_or(1);
+    if targets == 0 {
+        return Err(Error::InvalidInput("--targets must be at least 1".to_owned()).into());
+    }
+    if targets > 1 && (daily || analyze) {
+        return Err(Error::InvalidInput(
+            "--targets can't be combined with --daily or --analyze".to_owned(),
+        )
+        .into());
+    }
+    if daily && pool.is_some() {
+        return Err(Error::InvalidInput("--pool can't be combined with --daily".to_owned()).into());
+    }
+    if race && targets > 1 {
+        return Err(
+            Error::InvalidInput("--race can't be combined with --targets".to_owned()).into(),
+        );
+    }
+    let picks_target = seed.is_some() || daily || targets > 1 || pool.is_some() || endless;
+    for (given, flag) in [(resume, "--resume"), (challenge.is_some(), "--challenge")] {
+        if given && picks_target {
+            return Err(Error::InvalidInput(format!(
+                "{flag} can't be combined with --seed, --daily, --targets, --pool or --endless"
+            ))
+            .into());
+        }
+    }
+    if resume && challenge.is_some() {
+        return Err(
+            Error::InvalidInput("--resume can't be combined with --challenge".to_owned()).into(),
+        );
+    }
+    if endless && (daily || json) {
+        return Err(Error::InvalidInput(
+            "--endless can't be combined with --daily or --json".to_owned(),
+        )
+        .into());
+    }
+    // Every extra target gets an extra guess
+    let default_max = u8::try_from(targets - 1)
+        .ok()
+        .and_then(|x| DEFAULT_MAX_GUESSES.checked_add(x))
+        .unwrap_or(u8::MAX);
+    let options = PlayOptions {
+        max_guesses: match max_guesses.unwrap_or(default_max) {
+            | 0 => None,
+            | max => Some(max),
+        },
+        daily: None,
+        copy,
+        analyze,
+        tui,
+        timed,
+        race,
+        strict,
+        compact,
+        show_values,
+        json,
+        session: None,
+        resumed: Vec::new(),
+        pool,
+        challenge: false,
+        format,
+    };
+    if let Some(challenge) = challenge {
+        return play_challenge(source, filter, options, challenge);
+    }
+    if resume {
+        let Some(saved) = SavedGame::load_any()? else {
+            return Err(Error::InvalidInput("There is no saved game to resume".to_owned()).into());
+        };
+        return play_saved(source, filter, options, saved);
+    }
+    if daily {
+        if seed.is_some() || filter.has_conditions() {
+            return Err(Error::InvalidInput(
+                "--daily can't be combined with --seed or --filter".to_owned(),
+            )
+            .into());
+        }
+        return play_daily(source, filter, options);
+    }
+    if !json && !tui {
+        banner!("{PLAY_WELCOME}");
+    }
+    let mut rng = match seed {
+        | Some(seed) => Rng::new(seed),
+        | None => Rng::from_entropy()?,
+    };
+    let mut sinner_data = load_sinners(&settings::context(), source, filter)?;
+    if let Some(pool) = pool {
+        sinner_data.retain(|x| pool.matches(x));
+        if sinner_data.is_empty() {
+            return Err(Error::InvalidInput("No sinners match --pool".to_owned()).into());
+        }
+    }
+    if endless {
+        return play_endless(&mut rng, &sinner_data, targets, options);
+    }
+    play_random(&mut rng, &sinner_data, targets, &options)?;
+    Ok(())
+}
+/// Replays a game from the stats, counting from 1 and defaulting to the last
+fn replay(Replay { game, annotate }: Replay, source: &DataSource) -> eyre::Result<()> {
+    let stats = Stats::load()?;
+    let index = match game {
+        | Some(0) => None,
+        | Some(game) => Some(game - 1),
+        | None => stats.games.len().checked_sub(1),
+    };
+    let (number, record) = index
+        .and_then(|x| Some((x + 1, stats.games.get(x)?)))
+        .ok_or_else(|| {
+            Error::InvalidInput("No game with that number has been played".to_owned())
+        })?;
+    let daily = if record.daily { " (daily)" } else { "" };
+    println!("Game {number} played on {}{daily}", record.date);
+    let sinners = if annotate {
+        Some(load_sinners(&settings::context(), source, &<_>::default())?)
+    } else {
+        None
+    };
+    record.replay(sinners);
+    Ok(())
+}
+/// Runs the command given on the command line
+#[expect(clippy::too_many_lines, reason = "every command is dispatched here")]
+fn run() -> eyre::Result<()> {
+    let mut cli = PtndleCli::from_env_or_exit();
+    console::install(cli.verbosity()?, cli.progress);
+    if let Some(name) = cli.game.take() {
+        let game = GameProfile::load(&name)?;
+        verbose!("Playing {}", game.title);
+        settings::install_game(game);
+    }
+    if let Some(profile) = cli.profile.take() {
+        settings::install_profile(profile);
+        verbose!(
+            "Using the profile's files in {}",
+            settings::data_dir().display()
+        );
+    }
+    for path in &cli.strategy_script {
+        let name = script::register(path)?;
+        verbose!("Registered {} as the player `{name}`", path.display());
+    }
+    if let PtndleCliCmd::Config(flags) = cli.subcommand {
+        // Handled before the config file is applied to the flags, which it doesn't use
+        return config::run(flags);
+    }
+    cli.apply_config(&ConfigFile::load()?);
+    let format = cli.format()?;
+    settings::install_theme(cli.theme(format));
+    let source = data_source(&cli)?;
+    let mut filter = pool_filter(
+        cli.filter,
+        cli.exclude.as_deref(),
+        cli.only.as_deref(),
+        cli.as_of,
+    )?;
+    settings::install_lang(cli.lang.unwrap_or_default());
+    settings::install_tie_break(cli.tie_break.unwrap_or_default());
+    filter.thresholds = match &cli.thresholds {
+        | Some(path) => ThresholdConfig::load(path)?,
+        | None => settings::game().thresholds,
+    };
+    settings::install_thresholds(filter.thresholds);
     match cli.subcommand {
         | PtndleCliCmd::Help(Help { command }) => {
             eprintln!("{}", get_in_depth_help(&command));
         },
-        | PtndleCliCmd::Gather(_) => {
-            let sinners = load_sinners(cli.force_cache_update, &cli.filter.unwrap_or_default())
-                .map_err(|e| eyre!("Failed to load sinners: {e}"))?;
-            gather_data(&sinners)?;
+        | PtndleCliCmd::Gather(flags) => {
+            let sinners = load_sinners(&settings::context(), &source, &filter)
+                .wrap_err("Failed to load sinners")?;
+            let matrix = weighted_matrix(sinners, flags.weights.as_deref(), &source)?;
+            gather(flags, &matrix, cli.json)?;
         },
-        | PtndleCliCmd::Play(_) => {
-            println!("{PLAY_WELCOME}");
-            let random_num = {
-                let mut buf = 0usize.to_ne_bytes();
-                getrandom(&mut buf).map_err(|e| eyre!("Failed to get random number: {e}"))?;
-                usize::from_le_bytes(buf)
+        | PtndleCliCmd::Play(flags) => {
+            play(flags, false, &source, &filter, cli.json, format)?;
+        },
+        | PtndleCliCmd::Tui(flags) => {
+            play(flags.into(), true, &source, &filter, cli.json, format)?;
+        },
+        | PtndleCliCmd::Replay(flags) => replay(flags, &source)?,
+        | PtndleCliCmd::Stats(flags) => stats::run(flags, cli.json)?,
+        | PtndleCliCmd::Solve(Solve {
+            guesses,
+            strategy,
+            depth,
+            probe,
+            best,
+            explain,
+            non_interactive,
+            guided,
+            weights,
+        }) => {
+            let sinners = load_sinners(&settings::context(), &source, &filter)?;
+            let matrix = weighted_matrix(sinners, weights.as_deref(), &source)?;
+            let strategy = strategy.unwrap_or_default();
+            let config = solver_config(strategy.solver(), depth, probe)?;
+            let player = match strategy {
+                | StrategyName::Solver(_) => None,
+                | StrategyName::Player(_) => {
+                    Some(
+                        strategy
+                            .factory(config, script::registry(config))
+                            .map_err(|e| Error::InvalidInput(e.to_string()))?,
+                    )
+                },
             };
-            let sinner_data =
-                load_sinners(cli.force_cache_update, &cli.filter.unwrap_or_default())?;
-            let target = &sinner_data[random_num % sinner_data.len()];
-            play_game(target, HumanPlayer::new(sinner_data.clone()));
+            let solver = SolveStrategy { config, player };
+            let guesses = guesses.map(|x| x.0).unwrap_or_default();
+            if non_interactive {
+                solve_once(&guesses, matrix, solver, best, explain, cli.json, format)?;
+            } else if cli.json {
+                return Err(Error::InvalidInput(
+                    "--json can only be used with --non-interactive".to_owned(),
+                )
+                .into());
+            } else {
+                solve(&guesses, matrix, solver, best, explain, guided)?;
+            }
+        },
+        | PtndleCliCmd::Serve(Serve {
+            port,
+            strategy,
+            depth,
+            probe,
+        }) => {
+            let sinners = load_sinners(&settings::context(), &source, &filter)?;
+            serve(port.unwrap_or(8080), sinners, solver_config(strategy, depth, probe)?)?;
+        },
+        | PtndleCliCmd::Engine(Engine {
+            strategy,
+            depth,
+            probe,
+        }) => {
+            let sinners = load_sinners(&settings::context(), &source, &filter)?;
+            engine::run(sinners, solver_config(strategy, depth, probe)?)?;
         },
-        | PtndleCliCmd::Solve(Solve { guesses }) => {
-            let filter = cli.filter.unwrap_or_default();
-            let sinners = load_sinners(cli.force_cache_update, &filter)?;
-            solve(&guesses.map(|x| x.0).unwrap_or_default(), sinners)?;
+        | PtndleCliCmd::Cache(flags) => cache::run(flags, cli.cache_ttl.unwrap_or_default())?,
+        | PtndleCliCmd::Sinners(flags) => sinners::list(flags, &source, &filter, cli.json)?,
+        | PtndleCliCmd::Info(flags) => sinners::info(flags, &source, cli.json)?,
+        | PtndleCliCmd::Bands(flags) => sinners::bands(flags, &source, cli.json)?,
+        | PtndleCliCmd::Data(flags) => dataset::run(flags, &source, cli.json)?,
+        | PtndleCliCmd::Verify(flags) => verify::run(flags, &source, cli.json)?,
+        #[cfg(feature = "selftest")]
+        | PtndleCliCmd::Selftest(flags) => selftest::run(flags, cli.json)?,
+        #[cfg(not(feature = "selftest"))]
+        | PtndleCliCmd::Selftest(_) => {
+            return Err(Error::InvalidInput(
+                "selftest isn't in this build, which was built without the `selftest` feature"
+                    .to_owned(),
+            )
+            .into());
         },
+        | PtndleCliCmd::Bench(flags) => bench::run(flags, &source, &filter, cli.json)?,
+        | PtndleCliCmd::Simulate(flags) => simulate::run(flags, &source, &filter, cli.json)?,
+        | PtndleCliCmd::Tournament(flags) => tournament::run(flags, &source, &filter, cli.json)?,
+        | PtndleCliCmd::Openers(flags) => openers::run(flags, &source, &filter, cli.json)?,
+        | PtndleCliCmd::Analyze(flags) => analyze::run(flags, &source, &filter, cli.json)?,
+        | PtndleCliCmd::Completions(flags) => completions::run(flags, &source)?,
+        | PtndleCliCmd::Config(_) => unreachable!("The config command is handled first"),
     }
     Ok(())
 }
+
+fn main() -> ExitCode {
+    match run() {
+        | Ok(()) => ExitCode::SUCCESS,
+        | Err(e) => {
+            eprintln!("Error: {e:?}");
+            ExitCode::from(exit_code(&e))
+        },
+    }
+}
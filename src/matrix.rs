@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::compare::ThresholdConfig;
+use crate::data::Sinner;
+use crate::guess::Guess;
+use crate::weights::Weights;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A set of indices into a [`GuessMatrix`], stored as a bitset
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SinnerSet(Box<[u64]>);
+
+impl SinnerSet {
+    /// Creates an empty set that can hold indices up to `capacity`
+    pub fn empty(capacity: usize) -> Self { Self(vec![0; capacity.div_ceil(WORD_BITS)].into()) }
+    /// Creates a set containing every index in `0..len`
+    pub fn full(len: usize) -> Self {
+        let mut set = Self::empty(len);
+        for index in 0..len {
+            set.insert(index);
+        }
+        set
+    }
+    pub fn insert(&mut self, index: usize) {
+        self.0[index / WORD_BITS] |= 1 << (index % WORD_BITS);
+    }
+    pub fn remove(&mut self, index: usize) {
+        self.0[index / WORD_BITS] &= !(1 << (index % WORD_BITS));
+    }
+    pub fn contains(&self, index: usize) -> bool {
+        self.0[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0
+    }
+    pub fn len(&self) -> usize { self.0.iter().map(|x| x.count_ones() as usize).sum() }
+    pub fn is_empty(&self) -> bool { self.0.iter().all(|x| *x == 0) }
+    /// The number of indices in both `self` and `other`
+    pub fn intersection_len(&self, other: &Self) -> usize {
+        self.0
+            .iter()
+            .zip(&other.0)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+    /// The indices in both `self` and `other`
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self(self.0.iter().zip(&other.0).map(|(a, b)| a & b).collect())
+    }
+    /// Iterates over the indices in the set in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * WORD_BITS + bit)
+            })
+        })
+    }
+    /// Removes every index for which `f` returns false
+    pub fn retain(&mut self, mut f: impl FnMut(usize) -> bool) {
+        let removed = self.iter().filter(|x| !f(*x)).collect::<Vec<_>>();
+        for index in removed {
+            self.remove(index);
+        }
+    }
+}
+
+/// The results of guessing every sinner with every other sinner as the target,
+/// computed once and shared between players
+#[derive(Debug)]
+pub struct GuessMatrix {
+    sinners: Vec<Sinner>,
+    /// `results[guess * sinners.len() + target]` is the result of guessing
+    /// `guess` when `target` is the target
+    results: Vec<Guess>,
+    /// `buckets[guess]` are the groups of targets that give the same result
+    /// when `guess` is guessed, leaving out `guess` itself
+    buckets: Vec<Vec<SinnerSet>>,
+    /// How likely each sinner is to be the target, relative to the others, or
+    /// `None` if every sinner is equally likely
+    weights: Option<Box<[f64]>>,
+    /// The formulas the numbers in the results were compared with
+    thresholds: ThresholdConfig,
+}
+
+impl GuessMatrix {
+    /// Guesses every sinner with every other sinner as the target, comparing
+    /// numbers with the thresholds in `thresholds`
+    pub fn new(sinners: Vec<Sinner>, thresholds: ThresholdConfig) -> Self {
+        let results = sinners
+            .iter()
+            .flat_map(|guess| {
+                sinners
+                    .iter()
+                    .map(move |target| target.guess(guess, &thresholds))
+            })
+            .collect::<Vec<_>>();
+        let buckets = (0..sinners.len())
+            .map(|guess| {
+                let mut buckets = BTreeMap::<Guess, SinnerSet>::new();
+                for target in (0..sinners.len()).filter(|x| *x != guess) {
+                    buckets
+                        .entry(results[guess * sinners.len() + target])
+                        .or_insert_with(|| SinnerSet::empty(sinners.len()))
+                        .insert(target);
+                }
+                buckets.into_values().collect()
+            })
+            .collect();
+        Self {
+            sinners,
+            results,
+            buckets,
+            weights: None,
+            thresholds,
+        }
+    }
+    /// The formulas the numbers in the results were compared with
+    pub fn thresholds(&self) -> &ThresholdConfig { &self.thresholds }
+    /// Weights every sinner by `weights`, for solvers that expect some sinners
+    /// to be the target more often than others
+    #[must_use]
+    pub fn with_weights(mut self, weights: &Weights) -> Self {
+        self.weights = Some(self.sinners.iter().map(|x| weights.of(x)).collect());
+        self
+    }
+    /// The weights of the sinners, if they aren't all equally likely
+    pub fn weights(&self) -> Option<&[f64]> { self.weights.as_deref() }
+    /// The weight of the sinner at `index`, which is 1 when every sinner is
+    /// equally likely
+    pub fn weight(&self, index: usize) -> f64 {
+        self.weights.as_ref().map_or(1., |weights| weights[index])
+    }
+    /// The total weight of the sinners in `set`, which is the number of
+    /// sinners when every sinner is equally likely
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "there are not that many sinners"
+    )]
+    pub fn mass(&self, set: &SinnerSet) -> f64 {
+        match &self.weights {
+            | Some(weights) => set.iter().map(|x| weights[x]).sum(),
+            | None => set.len() as f64,
+        }
+    }
+    pub fn sinners(&self) -> &[Sinner] { &self.sinners }
+    pub fn len(&self) -> usize { self.sinners.len() }
+    pub fn is_empty(&self) -> bool { self.sinners.is_empty() }
+    /// The result of guessing the sinner at `guess` when the sinner at `target`
+    /// is the target
+    pub fn get(&self, guess: usize, target: usize) -> Guess {
+        self.results[guess * self.len() + target]
+    }
+    /// Finds the index of `sinner` in the matrix
+    pub fn index_of(&self, sinner: &Sinner) -> Option<usize> {
+        self.sinners.iter().position(|x| x == sinner)
+    }
+    /// The groups of sinners that give the same result when `guess` is
+    /// guessed, leaving out `guess` itself
+    pub fn buckets(&self, guess: usize) -> &[SinnerSet] { &self.buckets[guess] }
+    /// Gets the sizes of the groups of sinners in `set` that give the same
+    /// result when `guess` is guessed, leaving out `guess` itself
+    pub fn bucket_sizes<'set>(
+        &'set self,
+        guess: usize,
+        set: &'set SinnerSet,
+    ) -> impl Iterator<Item = usize> + 'set {
+        self.buckets[guess]
+            .iter()
+            .map(|bucket| bucket.intersection_len(set))
+            .filter(|len| *len != 0)
+    }
+    /// Like [`Self::bucket_sizes`], along with the total weight of each group
+    pub fn bucket_masses<'set>(
+        &'set self,
+        guess: usize,
+        set: &'set SinnerSet,
+    ) -> impl Iterator<Item = (usize, f64)> + 'set {
+        self.buckets[guess].iter().filter_map(|bucket| {
+            let len = bucket.intersection_len(set);
+            (len != 0).then(|| (len, self.intersection_mass(bucket, set)))
+        })
+    }
+    /// The total weight of the sinners in both `a` and `b`
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "there are not that many sinners"
+    )]
+    pub fn intersection_mass(&self, a: &SinnerSet, b: &SinnerSet) -> f64 {
+        match &self.weights {
+            | Some(_) => self.mass(&a.intersection(b)),
+            | None => a.intersection_len(b) as f64,
+        }
+    }
+}
+
+/// The sinners that could still be the target, as a set of indices into a
+/// [`GuessMatrix`]
+#[derive(Debug, Clone)]
+pub struct Candidates {
+    matrix: Arc<GuessMatrix>,
+    set: SinnerSet,
+}
+
+impl Candidates {
+    /// Creates a set containing every sinner in `matrix`
+    pub fn new(matrix: Arc<GuessMatrix>) -> Self {
+        Self {
+            set: SinnerSet::full(matrix.len()),
+            matrix,
+        }
+    }
+    pub fn matrix(&self) -> &GuessMatrix { &self.matrix }
+    pub fn set(&self) -> &SinnerSet { &self.set }
+    pub fn len(&self) -> usize { self.set.len() }
+    pub fn is_empty(&self) -> bool { self.set.is_empty() }
+    pub fn contains(&self, index: usize) -> bool { self.set.contains(index) }
+    /// Iterates over the indices of the candidates
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ { self.set.iter() }
+    pub fn sinners(&self) -> impl Iterator<Item = &Sinner> {
+        self.set.iter().map(|x| &self.matrix.sinners()[x])
+    }
+    /// Removes every candidate that is inconsistent with guessing `character`
+    /// and getting `result`
+    pub fn update(&mut self, result: Guess, character: &Sinner) {
+        if let Some(guess) = self.matrix.index_of(character) {
+            self.set
+                .retain(|target| self.matrix.get(guess, target) == result);
+        } else {
+            let (sinners, thresholds) = (self.matrix.sinners(), self.matrix.thresholds());
+            self.set.retain(|target| {
+                character.matches_result(result, &sinners[target], thresholds) &&
+                    sinners[target].code != character.code
+            });
+        }
+    }
+    /// Gets the sizes of the groups of candidates that give the same result
+    /// when the sinner at `guess` is guessed, leaving out `guess` itself
+    pub fn bucket_sizes(&self, guess: usize) -> impl Iterator<Item = usize> + '_ {
+        self.matrix.bucket_sizes(guess, &self.set)
+    }
+    /// Like [`Self::bucket_sizes`], along with the total weight of each group
+    pub fn bucket_masses(&self, guess: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        self.matrix.bucket_masses(guess, &self.set)
+    }
+    /// The total weight of the candidates
+    pub fn mass(&self) -> f64 { self.matrix.mass(&self.set) }
+    /// Splits the candidates into the groups that [`Self::bucket_sizes`]
+    /// counts, keyed by the result that guessing the sinner at `guess` gives
+    /// for every candidate in the group
+    pub fn partition(&self, guess: usize) -> BTreeMap<Guess, Vec<usize>> {
+        let mut partition = BTreeMap::<Guess, Vec<usize>>::new();
+        for target in self.iter().filter(|&x| x != guess) {
+            partition
+                .entry(self.matrix.get(guess, target))
+                .or_default()
+                .push(target);
+        }
+        partition
+    }
+}
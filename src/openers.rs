@@ -0,0 +1,193 @@
+//! The `openers` command, for ranking every sinner as the first guess and
+//! writing a book of the guesses to follow an opener with
+
+use std::sync::Arc;
+
+use eyre::eyre;
+use ptndle_core::data::{load_sinners, DataSource, Filter};
+use ptndle_core::error::Error;
+use ptndle_core::fuzzy::find_sinner;
+use ptndle_core::matrix::{Candidates, GuessMatrix};
+use ptndle_core::play::Player;
+use ptndle_core::solver::{rank_guesses, SolverConfig};
+
+use crate::flags::Openers;
+use crate::output::{print_json, BookEntry, BookReport, OpenerReport};
+use crate::settings;
+
+/// Prints the openers as a table, best first
+fn print_openers(openers: &[OpenerReport]) {
+    let name_width = openers
+        .iter()
+        .map(|x| x.name.len())
+        .chain(std::iter::once("Sinner".len()))
+        .max()
+        .unwrap_or_default();
+    println!("Rank  {:name_width$}  Expected  Worst  Entropy", "Sinner");
+    for opener in openers {
+        println!(
+            "{:>4}  {:name_width$}  {:>8.2}  {:>5}  {:>7.3}",
+            opener.rank,
+            opener.name,
+            opener.expected_remaining,
+            opener.worst_remaining,
+            opener.entropy
+        );
+    }
+}
+
+/// The best guess to follow `opener` with for every result it can give, in
+/// the order of the results
+fn book(matrix: &Arc<GuessMatrix>, opener: usize, config: SolverConfig) -> BookReport {
+    let results = Candidates::new(Arc::clone(matrix)).partition(opener);
+    let opener = &matrix.sinners()[opener];
+    let entries = results
+        .into_iter()
+        .map(|(result, targets)| {
+            let mut solver = config.solver(Arc::clone(matrix));
+            solver.update(result, opener);
+            BookEntry {
+                row: result.to_ascii(),
+                next: solver
+                    .next_guess()
+                    .map_or_else(String::new, |x| x.name.clone()),
+                candidates: targets
+                    .into_iter()
+                    .map(|x| matrix.sinners()[x].name.clone())
+                    .collect(),
+            }
+        })
+        .collect();
+    BookReport {
+        opener: opener.name.clone(),
+        strategy: config.strategy.to_string(),
+        entries,
+    }
+}
+
+/// Prints the book as a table, or a Markdown table if `markdown` is set
+fn print_book(book: &BookReport, markdown: bool) {
+    if markdown {
+        let escape = |text: &str| text.replace('|', "\\|");
+        println!(
+            "## Following {} ({})\n",
+            escape(&book.opener),
+            book.strategy
+        );
+        println!("| Row | Left | Guess next | Could be |");
+        println!("| --- | ---: | --- | --- |");
+        for entry in &book.entries {
+            println!(
+                "| `{}` | {} | {} | {} |",
+                entry.row,
+                entry.candidates.len(),
+                escape(&entry.next),
+                escape(&entry.candidates.join(", "))
+            );
+        }
+        return;
+    }
+    let next_width = book
+        .entries
+        .iter()
+        .map(|x| x.next.len())
+        .chain(std::iter::once("Guess next".len()))
+        .max()
+        .unwrap_or_default();
+    println!("Following {} ({}):", book.opener, book.strategy);
+    println!(
+        "Row              Left  {:next_width$}  Could be",
+        "Guess next"
+    );
+    for entry in &book.entries {
+        println!(
+            "{:15}  {:>4}  {:next_width$}  {}",
+            entry.row,
+            entry.candidates.len(),
+            entry.next,
+            entry.candidates.join(", ")
+        );
+    }
+}
+
+/// Ranks every sinner as the first guess under the strategy and prints the
+/// best `top` of them, or prints the book for an opener with `--book`
+pub fn run(
+    Openers {
+        strategy,
+        top,
+        book: write_book,
+        opener,
+        depth,
+        probe,
+        markdown,
+    }: Openers,
+    source: &DataSource,
+    filter: &Filter,
+    json: bool,
+) -> eyre::Result<()> {
+    if !write_book && (opener.is_some() || depth.is_some() || probe || markdown) {
+        return Err(Error::InvalidInput(
+            "--opener, --depth, --probe and --markdown can only be used with --book".to_owned(),
+        )
+        .into());
+    }
+    if markdown && json {
+        return Err(
+            Error::InvalidInput("--markdown can't be combined with --json".to_owned()).into(),
+        );
+    }
+    let matrix = Arc::new(GuessMatrix::new(
+        load_sinners(&settings::context(), source, filter)?,
+        filter.thresholds,
+    ));
+    let strategy = strategy.unwrap_or_default();
+    let candidates = Candidates::new(Arc::clone(&matrix));
+    let ranked = rank_guesses(&candidates, strategy, false, settings::tie_break());
+    if write_book {
+        let opener = match opener {
+            | Some(name) => find_sinner(matrix.sinners(), &name, settings::names())?,
+            | None => {
+                ranked
+                    .first()
+                    .map(|x| x.sinner)
+                    .ok_or_else(|| eyre!("There are no sinners to open with"))?
+            },
+        };
+        let config = SolverConfig {
+            strategy,
+            depth: depth.unwrap_or(1),
+            probe,
+            seed: 0,
+            tie_break: settings::tie_break(),
+        };
+        let opener = matrix.index_of(opener).unwrap_or_default();
+        let book = book(&matrix, opener, config);
+        if json {
+            print_json(&book);
+        } else {
+            print_book(&book, markdown);
+        }
+        return Ok(());
+    }
+    let openers = ranked
+        .into_iter()
+        .take(top.unwrap_or(usize::MAX))
+        .enumerate()
+        .map(|(index, guess)| {
+            OpenerReport {
+                rank: index + 1,
+                name: guess.sinner.name.clone(),
+                expected_remaining: guess.expected_remaining,
+                worst_remaining: guess.worst_remaining,
+                entropy: guess.entropy,
+            }
+        })
+        .collect::<Vec<_>>();
+    if json {
+        print_json(&openers);
+    } else {
+        print_openers(&openers);
+    }
+    Ok(())
+}
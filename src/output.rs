@@ -0,0 +1,660 @@
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use eyre::eyre;
+use facet::Facet;
+use ptndle_core::compare::Thresholds;
+use ptndle_core::data::Sinner;
+use ptndle_core::error::Error;
+use ptndle_core::guess::Guess;
+use ptndle_core::matrix::GuessMatrix;
+use ptndle_core::play::{Game, GatherResults};
+use unicode_width::UnicodeWidthStr;
+
+/// Prints `report` as a single line of JSON
+pub fn print_json<'facet, T: Facet<'facet>>(report: &T) {
+    println!("{}", facet_json::to_string(report));
+}
+
+/// A ranked guess in a [`SolveReport`]
+#[derive(Debug, Facet)]
+pub struct RankedGuessReport {
+    pub name: String,
+    pub expected_remaining: f64,
+    pub worst_remaining: usize,
+    pub is_candidate: bool,
+    /// The groups the guess splits the candidates into, largest first. Only
+    /// filled in with `--explain`.
+    pub partition: Vec<PartitionReport>,
+}
+
+/// A group of candidates in a [`RankedGuessReport`] that give the same result
+#[derive(Debug, Facet)]
+pub struct PartitionReport {
+    /// The result, such as `vv 0 0 ^ 0`
+    pub row: String,
+    pub sinners: Vec<String>,
+}
+
+/// The result of solving from a set of guesses without prompting
+#[derive(Debug, Facet)]
+pub struct SolveReport {
+    /// The sinner to guess next, or `None` if the guesses contradict each other
+    pub recommendation: Option<String>,
+    /// Whether the recommendation is the only sinner left
+    pub solved: bool,
+    /// The sinners that could still be the target
+    pub candidates: Vec<String>,
+    /// The top guesses when `--best` is used
+    pub best: Vec<RankedGuessReport>,
+}
+
+/// A single game played when gathering data
+#[derive(Debug, Facet)]
+pub struct GameReport {
+    pub target: String,
+    /// The number of guesses it took, or `None` if the strategy gave up
+    pub guesses: Option<u8>,
+    /// The sinners guessed, in order, including the target
+    pub sequence: Vec<String>,
+}
+
+/// The results of playing every possible game with a single strategy
+#[derive(Debug, Facet)]
+pub struct GatherReport {
+    pub strategy: String,
+    pub first_guess: String,
+    /// The most guesses taken in a game that wasn't given up
+    pub max_guesses: u8,
+    /// The mean number of guesses in the games that weren't given up
+    pub mean: f64,
+    /// The number of games the strategy gave up
+    pub losses: usize,
+    /// The sinners that take the maximum number of guesses
+    pub hardest: Vec<String>,
+    pub games: Vec<GameReport>,
+}
+
+impl From<&GatherResults<'_>> for GatherReport {
+    fn from(results: &GatherResults<'_>) -> Self {
+        Self {
+            strategy: results.name.clone(),
+            first_guess: results.first_guess.clone(),
+            max_guesses: results.max_guesses(),
+            mean: results.mean(),
+            losses: results.losses(),
+            hardest: results.hardest().map(|x| x.name.clone()).collect(),
+            games: results
+                .games
+                .iter()
+                .map(|game| {
+                    GameReport {
+                        target: game.target.name.clone(),
+                        guesses: game.guesses,
+                        sequence: game.sequence.clone(),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// How fast one strategy was on one pool in `bench`. Times are in
+/// microseconds.
+#[derive(Debug, Facet)]
+pub struct BenchReport {
+    pub strategy: String,
+    /// The number of sinners in the pool, which is also the number of games
+    pub sinners: usize,
+    /// The number of guesses made across every game
+    pub turns: usize,
+    /// The time taken to play every game, including setting up the solver
+    pub total: u64,
+    pub games_per_second: u64,
+    /// The mean time taken to pick a guess
+    pub mean_turn: u64,
+    /// The longest time taken to pick a guess
+    pub max_turn: u64,
+    /// The mean time taken to pick the first guess, which is usually the
+    /// slowest
+    pub first_guess: u64,
+}
+
+/// How one kind of player did in `simulate`
+#[derive(Debug, Facet)]
+pub struct SimulatedPlayerReport {
+    pub player: String,
+    pub wins: usize,
+    /// The fraction of games won, from 0 to 1
+    pub win_rate: f64,
+    /// The mean number of guesses in the won games
+    pub mean: f64,
+    /// The number of games won in 1 guess, 2 guesses and so on
+    pub distribution: Vec<usize>,
+}
+
+/// The result of `simulate`
+#[derive(Debug, Facet)]
+pub struct SimulateReport {
+    /// The seed that picks the targets, which gives the same games when reused
+    pub seed: u64,
+    pub games: usize,
+    /// The number of guesses before a game is lost, or `None` for no limit
+    pub max_guesses: Option<u8>,
+    pub players: Vec<SimulatedPlayerReport>,
+}
+
+/// A player's place in a `tournament`
+#[derive(Debug, Facet)]
+pub struct StandingReport {
+    /// The player's place, counting from 1 for the best player
+    pub rank: usize,
+    pub player: String,
+    /// The number of rounds played
+    pub games: usize,
+    pub mean: f64,
+    /// The most guesses taken in any round
+    pub worst: u8,
+    /// The number of rounds that took the most guesses
+    pub at_worst: usize,
+    /// The number of rounds that would have been lost on ptndle.com, not
+    /// counting the ones that were given up
+    pub over_max: usize,
+    /// The number of rounds the player gave up, which aren't counted in the
+    /// mean or the worst case
+    pub lost: usize,
+}
+
+/// A sinner ranked as the first guess by `openers`
+#[derive(Debug, Facet)]
+pub struct OpenerReport {
+    /// The sinner's place, counting from 1 for the best opener
+    pub rank: usize,
+    pub name: String,
+    /// The expected number of sinners remaining after the guess
+    pub expected_remaining: f64,
+    /// The most sinners that could remain after the guess
+    pub worst_remaining: usize,
+    /// The expected information gained from the guess, in bits
+    pub entropy: f64,
+}
+
+/// Two sinners that are hard to tell apart, found by `analyze confusions`
+#[derive(Debug, Facet)]
+pub struct ConfusionReport {
+    pub first: String,
+    pub second: String,
+    /// The number of guesses the two give the same result against
+    pub same_guesses: usize,
+    /// The number of guesses tried, which is every sinner in the pool
+    pub guesses: usize,
+    /// The attribute whose cell tells the two apart against the most guesses,
+    /// or `None` if only guessing one of them does
+    pub told_apart_by: Option<String>,
+    /// The values of that attribute for the first and second sinner
+    pub values: Vec<String>,
+}
+
+/// How much an attribute narrows down the pool on its own, found by
+/// `analyze attributes`. Both scores are averaged over every guess.
+#[derive(Debug, Facet)]
+pub struct AttributeReport {
+    /// The attribute, or `all` for the whole result
+    pub attribute: String,
+    /// The information gained from the attribute's cell, in bits
+    pub entropy: f64,
+    /// The expected number of sinners left after seeing only its cell
+    pub expected_remaining: f64,
+}
+
+/// A result an opener can give in a [`BookReport`], and what to guess next
+#[derive(Debug, Facet)]
+pub struct BookEntry {
+    /// The result, such as `vv 0 0 ^ 0`
+    pub row: String,
+    /// The sinner to guess next
+    pub next: String,
+    /// The sinners that could be the target after the result
+    pub candidates: Vec<String>,
+}
+
+/// The guesses to follow an opener with, from `openers --book`
+#[derive(Debug, Facet)]
+pub struct BookReport {
+    pub opener: String,
+    pub strategy: String,
+    pub entries: Vec<BookEntry>,
+}
+
+/// A sinner's profile from `info`
+#[derive(Debug, Facet)]
+pub struct InfoReport {
+    pub sinner: Sinner,
+    pub aliases: Vec<String>,
+    /// How close a guess has to be to the sinner's code and height to be near,
+    /// and how far to be far, when the sinner is the target. These are in
+    /// hundredths, so 530 is 5.3.
+    pub thresholds: Thresholds,
+}
+
+/// The targets that give each comparison when a sinner is guessed, found by
+/// `bands`
+#[derive(Debug, Facet)]
+pub struct BandsReport {
+    pub guess: String,
+    /// A band for every comparison, from the lowest targets to the highest
+    pub bands: Vec<BandReport>,
+}
+
+/// The codes and heights of targets that give a comparison in a
+/// [`BandsReport`]
+#[derive(Debug, Facet)]
+pub struct BandReport {
+    /// The comparison as it is entered when solving, such as `^^`
+    pub result: String,
+    pub codes: Vec<RangeReport>,
+    /// Heights in cm
+    pub heights: Vec<RangeReport>,
+}
+
+/// A run of consecutive values, including both ends
+#[derive(Debug, Facet)]
+pub struct RangeReport {
+    pub min: u16,
+    pub max: u16,
+}
+
+/// A row recorded from the website that [`Sinner::guess`] disagrees with
+#[derive(Debug, Facet)]
+pub struct VerifyMismatch {
+    pub line: usize,
+    pub target: String,
+    pub guess: String,
+    /// The row the website gave, such as `vv 0 0 ^ 0`
+    pub expected: String,
+    /// The row this program gives
+    pub actual: String,
+    /// The columns that differ, such as `height`
+    pub columns: Vec<String>,
+}
+
+/// The result of `verify`
+#[derive(Debug, Facet)]
+pub struct VerifyReport {
+    /// How many rows were checked
+    pub checked: usize,
+    pub mismatches: Vec<VerifyMismatch>,
+}
+
+/// How one of the checks of `selftest` went
+#[cfg(feature = "selftest")]
+#[derive(Debug, Facet)]
+pub struct SelftestCheck {
+    /// The name of the check, such as `results`
+    pub name: String,
+    /// How many cases were checked
+    pub cases: usize,
+    /// How many of them failed
+    pub failed: usize,
+    /// The first few failures, described
+    pub failures: Vec<String>,
+}
+
+/// The result of `selftest`
+#[cfg(feature = "selftest")]
+#[derive(Debug, Facet)]
+pub struct SelftestReport {
+    /// The seed the datasets were made from
+    pub seed: u64,
+    pub datasets: usize,
+    /// The number of sinners in each dataset
+    pub sinners: usize,
+    pub checks: Vec<SelftestCheck>,
+}
+
+/// The end of a game of `play`
+#[derive(Debug, Facet)]
+pub struct PlayReport {
+    pub target: String,
+    /// The number of guesses it took, or `None` if the game was lost
+    pub guesses: Option<u8>,
+    /// The date of the daily puzzle, if the game was a daily
+    pub daily: Option<String>,
+    /// The text from [`share_text`]
+    pub share: String,
+    /// How long the game took in milliseconds, if it was timed
+    pub time_ms: Option<u64>,
+    /// The number of guesses the solver took, if the game was a race
+    pub solver_guesses: Option<u8>,
+    /// The code to play the same game with `play --challenge`, unless the
+    /// game was a daily
+    pub challenge: Option<String>,
+}
+
+/// The end of a game of `play` with more than one target
+#[derive(Debug, Facet)]
+pub struct MultiPlayReport {
+    pub targets: Vec<String>,
+    /// The guess each target was found on, or `None` if it wasn't found
+    pub found: Vec<Option<u8>>,
+    /// The number of guesses it took to find every target, or `None` if the
+    /// game was lost
+    pub guesses: Option<u8>,
+    /// The text from [`multi_share_text`]
+    pub share: String,
+    /// How long the game took in milliseconds, if it was timed
+    pub time_ms: Option<u64>,
+}
+
+/// Creates a spoiler-free summary of a game with a row of emoji for each of
+/// the `results` of the guesses that didn't win, like the website's. `label`
+/// names the puzzle after the title, such as the date of a daily or a
+/// challenge code.
+pub fn share_text(
+    results: &[Guess],
+    guesses: Option<u8>,
+    max_guesses: Option<u8>,
+    label: Option<&str>,
+) -> String {
+    let mut text = "Path to Nowordle".to_owned();
+    if let Some(label) = label {
+        _ = write!(text, " {label}");
+    }
+    let score = guesses.map_or_else(|| "X".to_owned(), |x| x.to_string());
+    match max_guesses {
+        | Some(max) => _ = write!(text, " {score}/{max}"),
+        | None => _ = write!(text, " {score}"),
+    }
+    for result in results {
+        _ = write!(text, "\n{}", result.to_emoji());
+    }
+    if guesses.is_some() {
+        _ = write!(text, "\n{}", "🟩".repeat(5));
+    }
+    text
+}
+
+/// Creates a spoiler-free summary of a game with several targets, like
+/// [`share_text`], with the rows for each target side by side. Targets that
+/// have been found are left blank.
+pub fn multi_share_text(game: &Game<'_>, max_guesses: Option<u8>) -> String {
+    let mut text = format!("Path to Nowordle ×{}", game.targets().len());
+    let score = if game.is_won() {
+        (game.guess_num()).to_string()
+    } else {
+        "X".to_owned()
+    };
+    match max_guesses {
+        | Some(max) => _ = write!(text, " {score}/{max}"),
+        | None => _ = write!(text, " {score}"),
+    }
+    for (guess, results) in (1..).zip(game.rows()) {
+        let cells = results
+            .iter()
+            .zip(game.found())
+            .map(|(result, found)| {
+                match (result, found) {
+                    | (Some(result), _) => result.to_emoji(),
+                    | (None, Some(found)) if *found == guess => "🟩".repeat(5),
+                    | (None, _) => "⬜".repeat(5),
+                }
+            })
+            .collect::<Vec<_>>();
+        _ = write!(text, "\n{}", cells.join(" "));
+    }
+    text
+}
+
+/// Escapes what Discord would read as markdown in `text`, so that a message
+/// with it shows the text as it is
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (index, line) in text.split('\n').enumerate() {
+        if index > 0 {
+            escaped.push('\n');
+        }
+        // Headers, quotes and lists only start at the start of a line
+        if line.starts_with(['#', '>', '-']) {
+            escaped.push('\\');
+        }
+        for c in line.chars() {
+            if matches!(
+                c,
+                '\\' | '*' | '_' | '~' | '`' | '|' | '[' | ']' | '<' | ':'
+            ) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Copies `text` to the clipboard using whichever clipboard program is
+/// installed
+pub fn copy_to_clipboard(text: &str) -> eyre::Result<()> {
+    const PROGRAMS: [(&str, &[&str]); 6] = [
+        ("pbcopy", &[]),
+        ("clip", &[]),
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+        ("clip.exe", &[]),
+    ];
+    for (program, args) in PROGRAMS {
+        let Ok(mut child) = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        if child.wait()?.success() {
+            return Ok(());
+        }
+    }
+    Err(eyre!("No clipboard program found"))
+}
+
+/// Quotes `field` if it can't be written to a CSV file as is
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+/// Writes a row for every game in `results` to `path`, and a summary of each
+/// strategy to a `-summary` file next to it. Returns the path of the summary.
+pub fn write_csv(path: &Path, results: &[GatherResults<'_>]) -> eyre::Result<PathBuf> {
+    let mut games = "strategy,target,guesses,sequence\n".to_owned();
+    for result in results {
+        for game in &result.games {
+            _ = writeln!(
+                games,
+                "{},{},{},{}",
+                csv_field(&result.name),
+                csv_field(&game.target.name),
+                game.guesses.map_or_else(String::new, |x| x.to_string()),
+                csv_field(&game.sequence.join(" > "))
+            );
+        }
+    }
+    std::fs::write(path, games)
+        .map_err(|e| Error::Io(format!("Failed to write {}: {e}", path.display())))?;
+
+    let max_rounds = results
+        .iter()
+        .map(GatherResults::max_guesses)
+        .max()
+        .unwrap_or_default();
+    let mut summary = "strategy,first_guess,max_guesses,mean,losses".to_owned();
+    for rounds in 1..=max_rounds {
+        _ = write!(summary, ",{rounds}");
+    }
+    summary.push('\n');
+    for result in results {
+        _ = write!(
+            summary,
+            "{},{},{},{:.4},{}",
+            csv_field(&result.name),
+            csv_field(&result.first_guess),
+            result.max_guesses(),
+            result.mean(),
+            result.losses()
+        );
+        for rounds in 1..=max_rounds {
+            _ = write!(summary, ",{}", result.count(rounds));
+        }
+        summary.push('\n');
+    }
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let summary_path = path.with_file_name(match path.extension() {
+        | Some(extension) => format!("{stem}-summary.{}", extension.to_string_lossy()),
+        | None => format!("{stem}-summary"),
+    });
+    std::fs::write(&summary_path, summary)
+        .map_err(|e| Error::Io(format!("Failed to write {}: {e}", summary_path.display())))?;
+    Ok(summary_path)
+}
+
+/// Writes a report of `results` in markdown to `path`, with tables comparing
+/// the strategies, the distribution of guesses and the hardest sinners, to be
+/// pasted into GitHub discussions or the wiki
+#[expect(clippy::float_arithmetic, reason = "statistics")]
+pub fn write_markdown(path: &Path, results: &[GatherResults<'_>]) -> eyre::Result<()> {
+    let sinners = results.first().map_or(0, |x| x.games.len());
+    let max_rounds = results
+        .iter()
+        .map(GatherResults::max_guesses)
+        .max()
+        .unwrap_or_default();
+    let mut text = "# Path to Nowordle solver report\n\n".to_owned();
+    _ = writeln!(
+        text,
+        "Every possible game was played with each strategy, {sinners} games per strategy.\n"
+    );
+
+    text.push_str("## Strategies\n\n");
+    text.push_str("| Strategy | First guess | Max guesses | Mean guesses | Given up |\n");
+    text.push_str("| --- | --- | ---: | ---: | ---: |\n");
+    for result in results {
+        _ = writeln!(
+            text,
+            "| {} | {} | {} | {:.2} | {} |",
+            escape_markdown(&result.name),
+            escape_markdown(&result.first_guess),
+            result.max_guesses(),
+            result.mean(),
+            result.losses()
+        );
+    }
+
+    text.push_str("\n## Distribution\n\n| Guesses |");
+    for result in results {
+        _ = write!(text, " {} |", escape_markdown(&result.name));
+    }
+    text.push_str("\n| ---: |");
+    text.push_str(&" ---: |".repeat(results.len()));
+    text.push('\n');
+    for rounds in 1..=max_rounds {
+        _ = write!(text, "| {rounds} |");
+        for result in results {
+            let count = result.count(rounds);
+            #[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
+            let percent = count as f64 * 100. / result.games.len() as f64;
+            _ = write!(text, " {count} ({percent:.1}%) |");
+        }
+        text.push('\n');
+    }
+
+    text.push_str("\n## Hardest sinners\n\n");
+    text.push_str("| Strategy | Guesses | Sinners |\n| --- | ---: | --- |\n");
+    for result in results {
+        let hardest = result
+            .hardest()
+            .map(|x| escape_markdown(&x.name))
+            .collect::<Vec<_>>();
+        _ = writeln!(
+            text,
+            "| {} | {} | {} |",
+            escape_markdown(&result.name),
+            result.max_guesses(),
+            hardest.join(", ")
+        );
+    }
+    std::fs::write(path, text)
+        .map_err(|e| Error::Io(format!("Failed to write {}: {e}", path.display())).into())
+}
+
+/// Writes the guesses made against each sinner that takes the maximum number
+/// of guesses in `results` to `path`, with the result of each guess written
+/// the way it is entered in `solve`. The guesses are looked up in `matrix`.
+pub fn write_traces(
+    path: &Path,
+    results: &[GatherResults<'_>],
+    matrix: &GuessMatrix,
+) -> eyre::Result<()> {
+    let mut text = String::new();
+    for result in results {
+        let max = result.max_guesses();
+        _ = writeln!(
+            text,
+            "{}: {} sinners take {max} guesses",
+            result.name,
+            result.count(max)
+        );
+        for game in result.hardest_games() {
+            let trace = game.trace(matrix);
+            let width = trace
+                .iter()
+                .map(|(sinner, _)| sinner.name.width())
+                .max()
+                .unwrap_or_default();
+            _ = writeln!(text, "\n    {}", game.target.name);
+            for (index, (sinner, guess)) in trace.iter().enumerate() {
+                let padding = " ".repeat(width - sinner.name.width());
+                _ = writeln!(
+                    text,
+                    "        {}. {}{padding}  {}",
+                    index + 1,
+                    sinner.name,
+                    guess.to_ascii()
+                );
+            }
+        }
+        text.push('\n');
+    }
+    std::fs::write(path, text)
+        .map_err(|e| Error::Io(format!("Failed to write {}: {e}", path.display())).into())
+}
+
+/// Writes a row for each player in a `tournament` to `path`, best first
+pub fn write_standings_csv(path: &Path, standings: &[StandingReport]) -> eyre::Result<()> {
+    let mut text = "rank,player,games,mean,worst,at_worst,over_max,lost\n".to_owned();
+    for standing in standings {
+        _ = writeln!(
+            text,
+            "{},{},{},{:.4},{},{},{},{}",
+            standing.rank,
+            csv_field(&standing.player),
+            standing.games,
+            standing.mean,
+            standing.worst,
+            standing.at_worst,
+            standing.over_max,
+            standing.lost
+        );
+    }
+    std::fs::write(path, text)
+        .map_err(|e| Error::Io(format!("Failed to write {}: {e}", path.display())).into())
+}
@@ -0,0 +1,104 @@
+//! Where files are kept, which depends on the profile, so that people sharing
+//! a machine each have their own stats, history and settings
+
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::game::GameProfile;
+
+/// The name of a player whose stats, history and settings are kept apart from
+/// everyone else's
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile(String);
+
+impl Profile {
+    pub fn name(&self) -> &str { &self.0 }
+}
+
+impl Display for Profile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str(&self.0) }
+}
+
+#[derive(Debug)]
+pub struct InvalidProfileError(String);
+
+impl FromStr for Profile {
+    type Err = InvalidProfileError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The name is used in paths, so it can't have separators or dots
+        let valid = |x: char| x.is_ascii_alphanumeric() || x == '-' || x == '_';
+        let name = s.trim();
+        if name.is_empty() || !name.chars().all(valid) {
+            return Err(InvalidProfileError(s.to_owned()));
+        }
+        Ok(Self(name.to_owned()))
+    }
+}
+impl Display for InvalidProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Invalid profile name: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected letters, digits, `-` and `_`")
+    }
+}
+
+/// The directory of `game`'s files in `shared`, which is `shared` itself for
+/// Path to Nowordle and `games/<name>` in it for any other game
+fn game_dir(shared: PathBuf, game: &GameProfile) -> PathBuf {
+    if game.is_builtin() {
+        shared
+    } else {
+        shared.join("games").join(&game.name)
+    }
+}
+
+/// The directory `game`'s sinner data is cached in, which every profile
+/// shares
+pub fn cache_dir(game: &GameProfile) -> PathBuf {
+    game_dir(
+        dirs::cache_dir().map_or_else(
+            || "path-to-nowordle-cli-cache".into(),
+            |x| x.join("Path-To-Nowordle-CLI"),
+        ),
+        game,
+    )
+}
+
+/// The directory `game`'s stats, shell history and saved games are kept in,
+/// which is `profiles/<name>` in it for a profile
+pub fn data_dir(game: &GameProfile, profile: Option<&Profile>) -> PathBuf {
+    let shared = game_dir(
+        dirs::data_dir().map_or_else(
+            || "path-to-nowordle-cli-data".into(),
+            |x| x.join("Path-To-Nowordle-CLI"),
+        ),
+        game,
+    );
+    match profile {
+        | Some(profile) => shared.join("profiles").join(profile.name()),
+        | None => shared,
+    }
+}
+
+/// The directory the config files, aliases, translations and games are kept
+/// in
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|x| x.join("Path-To-Nowordle-CLI"))
+}
+
+/// The config file shared by every profile, `config.toml` in the
+/// [`config_dir`]
+pub fn config_file() -> PathBuf {
+    config_dir().map_or_else(
+        || "ptndle-cli-config.toml".into(),
+        |x| x.join("config.toml"),
+    )
+}
+
+/// `profile`'s config file, `profiles/<name>.toml` in the [`config_dir`],
+/// whose settings take precedence over the shared one's
+pub fn profile_config_file(profile: &Profile) -> PathBuf {
+    let dir = config_dir().map_or_else(|| "profiles".into(), |x| x.join("profiles"));
+    dir.join(format!("{profile}.toml"))
+}
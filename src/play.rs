@@ -1,39 +1,90 @@
 use std::cell::RefCell;
 use std::fmt::Display;
-use std::io::{stdin, stdout, Write};
+use std::io::Write;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use eyre::eyre;
 use facet::Facet;
-use ordered_float::NotNan;
-use owo_colors::OwoColorize;
-use reedline::{default_emacs_keybindings, ColumnarMenu, DefaultCompleter, DefaultPrompt, Emacs,
-               ExampleHighlighter, KeyModifiers, MenuBuilder, Reedline, ReedlineEvent, Signal};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use unicode_width::UnicodeWidthStr;
 
+use crate::attribute::Attribute;
+use crate::checkpoint::{Checkpoint, CheckpointGame};
+use crate::compare::ThresholdConfig;
+use crate::context::Context;
 use crate::data::Sinner;
 use crate::guess::Guess;
+use crate::i18n::{message, Lang, Message};
+use crate::matrix::GuessMatrix;
+use crate::progress::Progress;
+use crate::solver::SolverConfig;
+use crate::theme::{Role, Theme};
 
+/// A game with one or more targets, which is won once every target has been
+/// guessed
 #[derive(Debug, Clone)]
 pub struct Game<'game> {
-    target: &'game Sinner,
+    targets: Vec<&'game Sinner>,
+    /// The guess each target was found on, if it has been found
+    found: Vec<Option<u8>>,
+    /// The results of every guess so far, as returned by [`Self::guess_all`]
+    rows: Vec<Vec<Option<Guess>>>,
     guess_num: u8,
+    /// The formulas numbers are compared with
+    thresholds: ThresholdConfig,
 }
 
 impl<'game> Game<'game> {
-    pub fn new(target: &'game Sinner) -> Self {
+    /// Creates a game guessing `target`, comparing numbers with the thresholds
+    /// in `thresholds`
+    pub fn new(target: &'game Sinner, thresholds: ThresholdConfig) -> Self {
+        Self::with_targets(vec![target], thresholds)
+    }
+    /// Creates a game where every sinner in `targets` has to be found
+    pub fn with_targets(targets: Vec<&'game Sinner>, thresholds: ThresholdConfig) -> Self {
         Self {
-            target,
+            found: vec![None; targets.len()],
+            targets,
+            rows: Vec::new(),
             guess_num: 1,
+            thresholds,
         }
     }
     pub fn guess_num(&self) -> u8 { self.guess_num }
+    pub fn targets(&self) -> &[&'game Sinner] { &self.targets }
+    /// The guess each target was found on, in the order of the targets
+    pub fn found(&self) -> &[Option<u8>] { &self.found }
+    /// The results of every guess so far, with a result for each target
+    pub fn rows(&self) -> &[Vec<Option<Guess>>] { &self.rows }
+    /// Whether every target has been found
+    pub fn is_won(&self) -> bool { self.found.iter().all(Option::is_some) }
+    /// Guesses `character` in a game with a single target, returning `None` if
+    /// it was the target
     pub fn guess(&mut self, character: &'_ Sinner) -> Option<Guess> {
-        if character == self.target {
-            return None;
+        self.guess_all(character).into_iter().next().flatten()
+    }
+    /// Guesses `character`, returning its result for each target, in order.
+    /// Targets that have been found, including by this guess, have no result.
+    pub fn guess_all(&mut self, character: &'_ Sinner) -> Vec<Option<Guess>> {
+        let results = self
+            .targets
+            .iter()
+            .zip(&mut self.found)
+            .map(|(target, found)| {
+                if found.is_none() && character == *target {
+                    *found = Some(self.guess_num);
+                }
+                found
+                    .is_none()
+                    .then(|| target.guess(character, &self.thresholds))
+            })
+            .collect::<Vec<_>>();
+        self.rows.push(results.clone());
+        if !self.is_won() {
+            self.guess_num += 1;
         }
-        let guess = self.target.guess(character);
-        self.guess_num += 1;
-        Some(guess)
+        results
     }
 }
 
@@ -42,322 +93,478 @@ pub trait Player {
     /// guessed
     fn update(&mut self, result: Guess, character: &Sinner);
     /// Gets the next guess from the player. May return `None` if there is a
-    /// contradiction in the state.
+    /// contradiction in the state or the player gave up.
     fn next_guess(&self) -> Option<&Sinner>;
+    /// Whether the player stopped guessing because it gave up, rather than
+    /// because it had no guess left
+    fn gave_up(&self) -> bool { false }
+    /// Updates the state of the player in a game with several targets, with
+    /// the result of the guess for each target as given by
+    /// [`Game::guess_all`]. By default, the player only follows the first
+    /// target, as if it were the only one.
+    fn update_targets(&mut self, results: &[Option<Guess>], character: &Sinner) {
+        if let Some(Some(result)) = results.first() {
+            self.update(*result, character);
+        }
+    }
 }
 
-/// A [`Player`] that guesses sinners based on the mean number of sinners
-/// remaining after a guess.
-#[derive(Debug, Clone)]
-pub struct OptimalPlayer {
-    candidates: Vec<Sinner>,
+impl<P: Player + ?Sized> Player for Box<P> {
+    fn update(&mut self, result: Guess, character: &Sinner) { (**self).update(result, character); }
+    fn next_guess(&self) -> Option<&Sinner> { (**self).next_guess() }
+    fn update_targets(&mut self, results: &[Option<Guess>], character: &Sinner) {
+        (**self).update_targets(results, character);
+    }
+    fn gave_up(&self) -> bool { (**self).gave_up() }
 }
 
-impl Player for OptimalPlayer {
-    fn update(&mut self, result: Guess, character: &Sinner) {
-        self.candidates = std::mem::take(&mut self.candidates)
-            .into_iter()
-            .filter(|x| character.matches_result(result, x) && x.code != character.code)
-            .collect();
+impl<P: Player + ?Sized> Player for &mut P {
+    fn update(&mut self, result: Guess, character: &Sinner) { (**self).update(result, character); }
+    fn next_guess(&self) -> Option<&Sinner> { (**self).next_guess() }
+    fn update_targets(&mut self, results: &[Option<Guess>], character: &Sinner) {
+        (**self).update_targets(results, character);
     }
-    #[expect(clippy::float_arithmetic, reason = "statistics")]
-    fn next_guess(&self) -> Option<&Sinner> {
-        if self.candidates.len() == 1 {
-            return Some(&self.candidates[0]);
+    fn gave_up(&self) -> bool { (**self).gave_up() }
+}
+
+/// A [`Player`] that records the sinners another player guessed without
+/// winning, along with their results
+pub struct RecordingPlayer<P> {
+    pub player: P,
+    pub guessed: Vec<(String, Guess)>,
+}
+
+impl<P: Player> RecordingPlayer<P> {
+    pub fn new(player: P) -> Self {
+        Self {
+            player,
+            guessed: Vec::new(),
         }
-        #[expect(
-            clippy::cast_precision_loss,
-            reason = "The sum will not get big enough for it to be an issue"
-        )]
-        Some(
-            self.candidates
-                .iter()
-                .map(|guess| {
-                    (
-                        guess,
-                        self.candidates
-                            .iter()
-                            .filter(|target| guess != *target)
-                            .map(|target| {
-                                self.candidates
-                                    .iter()
-                                    .filter(|x| guess.matches_result(target.guess(guess), x))
-                                    .count()
-                            })
-                            .sum::<usize>() as f64 /
-                            self.candidates.len() as f64,
-                    )
-                })
-                .min_by_key(|(_, mean)| NotNan::new(*mean).unwrap())?
-                .0,
-        )
     }
 }
 
-impl OptimalPlayer {
-    pub fn new(candidates: Vec<Sinner>) -> OptimalPlayer { OptimalPlayer { candidates } }
+impl<P: Player> Player for RecordingPlayer<P> {
+    fn update(&mut self, result: Guess, character: &Sinner) {
+        self.guessed.push((character.name.clone(), result));
+        self.player.update(result, character);
+    }
+    fn next_guess(&self) -> Option<&Sinner> { self.player.next_guess() }
+    fn gave_up(&self) -> bool { self.player.gave_up() }
 }
 
-/// A [`Player`] connected to the terminal
-pub struct HumanPlayer {
-    line_editor: RefCell<Reedline>,
-    choices: Vec<Sinner>,
+/// A [`Player`] that times how long another player takes to make each guess
+pub struct TimedPlayer<P> {
+    pub player: P,
+    /// How long each guess took, in order, including the one that won
+    pub times: RefCell<Vec<Duration>>,
 }
-impl HumanPlayer {
-    pub fn new(choices: Vec<Sinner>) -> Self {
-        let commands = choices
-            .iter()
-            .map(|x| "info ".to_owned() + &x.name)
-            .chain(choices.iter().map(|x| "guess ".to_owned() + &x.name))
-            .chain(std::iter::once("quit".to_owned()))
-            .collect();
-        let mut completer = DefaultCompleter::with_inclusions(&['.', '-']);
-        completer.insert(commands);
-        let completer = Box::new(completer);
-        let highlighter = Box::new(ExampleHighlighter::new(vec![
-            "info".to_owned(),
-            "guess".to_owned(),
-            "quit".to_owned(),
-        ]));
-        let mut keybindings = default_emacs_keybindings();
-        keybindings.add_binding(
-            KeyModifiers::NONE,
-            reedline::KeyCode::Tab,
-            ReedlineEvent::UntilFound(vec![
-                ReedlineEvent::Menu("completion_menu".to_owned()),
-                ReedlineEvent::MenuNext,
-            ]),
-        );
-        let completion_menu = Box::new(ColumnarMenu::default().with_name("completion_menu"));
-        let line_editor = RefCell::new(
-            Reedline::create()
-                .with_edit_mode(Box::new(Emacs::new(keybindings)))
-                .with_completer(completer)
-                .with_menu(reedline::ReedlineMenu::EngineCompleter(completion_menu))
-                .with_highlighter(highlighter),
-        );
+
+impl<P: Player> TimedPlayer<P> {
+    pub fn new(player: P) -> Self {
         Self {
-            line_editor,
-            choices,
+            player,
+            times: RefCell::new(Vec::new()),
         }
     }
 }
-impl Player for HumanPlayer {
+
+impl<P: Player> Player for TimedPlayer<P> {
+    fn update(&mut self, result: Guess, character: &Sinner) {
+        self.player.update(result, character);
+    }
     fn next_guess(&self) -> Option<&Sinner> {
-        loop {
-            match self.line_editor.borrow_mut().read_line(&DefaultPrompt::new(
-                reedline::DefaultPromptSegment::Basic("ptndle >>".to_owned()),
-                reedline::DefaultPromptSegment::Basic("Hella yeah!".to_owned()),
-            )) {
-                | Ok(Signal::Success(buffer)) => {
-                    let buffer = buffer.trim();
-                    if buffer == "quit" {
-                        std::process::exit(0);
-                    }
-                    let Some((cmd, arg)) = buffer.split_once(' ') else {
-                        eprintln!("Unknown command: `{buffer}`");
-                        continue;
-                    };
+        let start = Instant::now();
+        let guess = self.player.next_guess();
+        self.times.borrow_mut().push(start.elapsed());
+        guess
+    }
+    fn update_targets(&mut self, results: &[Option<Guess>], character: &Sinner) {
+        self.player.update_targets(results, character);
+    }
+    fn gave_up(&self) -> bool { self.player.gave_up() }
+}
 
-                    match cmd {
-                        | "info" => {
-                            let Some(sinner) = self
-                                .choices
-                                .iter()
-                                .find(|x| x.name.eq_ignore_ascii_case(arg))
-                            else {
-                                eprintln!("Unknown Sinner: `{arg}`");
-                                continue;
-                            };
-                            println!("Name: {}", sinner.name);
-                            println!(
-                                "Code: {}",
-                                sinner
-                                    .code
-                                    .as_ref()
-                                    .map_or_else(|| "NOX".to_owned(), <_>::to_string)
-                            );
-                            println!("Alignment: {:?}", sinner.alignment);
-                            println!("Tendency: {:?}", sinner.tendency);
-                            println!("Height: {}cm", sinner.height);
-                            println!("Birthplace: {:?}", sinner.birthplace);
-                        },
-                        | "guess" => {
-                            let Some(to_play) = self
-                                .choices
-                                .iter()
-                                .find(|x| x.name.eq_ignore_ascii_case(arg))
-                            else {
-                                eprintln!("Unknown Sinner: `{arg}`");
-                                continue;
-                            };
-                            break Some(to_play);
-                        },
-                        | _ => {
-                            eprintln!("Unknown command: `{cmd}`");
-                        },
-                    }
-                },
-                | Ok(Signal::CtrlC | Signal::CtrlD) => {
-                    eprintln!("Aborted!");
-                    std::process::exit(1);
-                },
-                | Err(e) => {
-                    eprintln!("Failed to read line of input: {e}");
-                    std::process::exit(1);
-                },
-            }
-        }
+/// The number of guesses ptndle.com gives to find the target
+pub const DEFAULT_MAX_GUESSES: u8 = 6;
+
+/// The width of the names in front of results, which fits every sinner's name
+const NAME_WIDTH: usize = 16;
+
+/// The cells of the guess that finds the target, for printing under a header
+fn winning_cells(theme: &Theme) -> [String; Attribute::COUNT] {
+    Attribute::ALL.map(|x| theme.paint(x.correct().to_str(), Role::Correct).to_string())
+}
+
+/// The row for the guess that finds the target
+fn winning_row(theme: &Theme) -> String {
+    let cells = Attribute::ALL.map(|x| x.correct().to_str());
+    theme.paint(cells.join(" "), Role::Correct).to_string()
+}
+
+/// `text` followed by enough spaces to take up `width` columns. Unlike
+/// padding with `format!`, this counts Chinese and Japanese characters as two
+/// columns wide, which is how terminals show them.
+fn pad(text: &str, width: usize) -> String {
+    format!("{text}{}", " ".repeat(width.saturating_sub(text.width())))
+}
+
+/// The header line printed above results, after `label`
+fn header(context: &Context, label: Message) -> String {
+    let mut line = pad(&message(context.lang, label, &[]), NAME_WIDTH);
+    for attribute in Attribute::ALL {
+        line.push_str("  ");
+        line.push_str(&message(context.lang, attribute.message(), &[]));
     }
-    fn update(&mut self, _result: Guess, _character: &Sinner) {}
+    context.theme.paint(line, Role::Bold).to_string()
 }
 
-pub fn play_game<P: Player>(target: &Sinner, mut player: P) -> u8 {
-    let mut game = Game::new(target);
+/// A row of results after `label`, with each cell right-aligned under its
+/// header from [`header`]
+fn row<T: Display>(lang: Lang, label: &str, cells: &[T; Attribute::COUNT]) -> String {
+    let mut line = pad(label, NAME_WIDTH);
+    for (cell, attribute) in cells.iter().zip(Attribute::ALL) {
+        // Cells are two characters wide, and colors would count towards the
+        // width if they were padded with `format!`
+        line.push_str(&" ".repeat(message(lang, attribute.message(), &[]).width()));
+        line.push_str(&cell.to_string());
+    }
+    line
+}
+
+/// Plays a game with `player` trying to guess `target`, with numbers compared
+/// with the thresholds in `thresholds`, writing the progress of the game to
+/// `out` in the language and theme of `context`. Each result is printed after
+/// the name of the sinner guessed under a header, or on the line after it if
+/// `compact` is set. Returns the number of guesses it took, or `None` if the
+/// player ran out of guesses or couldn't make one.
+pub fn play_game<P: Player>(
+    context: &Context,
+    target: &Sinner,
+    mut player: P,
+    thresholds: &ThresholdConfig,
+    max_guesses: Option<u8>,
+    compact: bool,
+    out: &mut impl Write,
+) -> std::io::Result<Option<u8>> {
+    let mut game = Game::new(target, *thresholds);
+    let target_name = context.display_name(&target.name);
+    if !compact {
+        writeln!(out, "{}", header(context, Message::Sinner))?;
+    }
 
     loop {
         let Some(play) = player.next_guess() else {
-            eprintln!("No possible guesses in this state. There is likely a contradiction.");
-            return 255;
+            if player.gave_up() {
+                writeln!(
+                    out,
+                    "{}\n",
+                    message(context.lang, Message::GaveUp, &[&target_name])
+                )?;
+            } else {
+                writeln!(out, "{}\n", message(context.lang, Message::NoGuesses, &[]))?;
+            }
+            return Ok(None);
         };
-        println!("Guessed {}", play.name);
+        let name = context.display_name(&play.name);
+        if compact {
+            writeln!(out, "{}", message(context.lang, Message::Guessed, &[&name]))?;
+        }
         if let Some(guess) = game.guess(play) {
-            println!("{guess}");
+            if compact {
+                writeln!(out, "{}", guess.paint(&context.theme))?;
+            } else {
+                let cells = guess.cells(&context.theme);
+                writeln!(out, "{}", row(context.lang, name, &cells))?;
+            }
             assert!(
-                play.matches_result(guess, target),
-                "ERROR: Target ({target:?}) does not match its own result ({guess}) based on \
-                 guess ({play:?}). This is a bug."
+                play.matches_result(guess, target, thresholds),
+                "ERROR: Target ({target:?}) does not match its own result ({}) based on guess \
+                 ({play:?}). This is a bug.",
+                guess.to_ascii()
             );
             let c = play.clone();
 
+            if max_guesses.is_some_and(|max| game.guess_num() > max) {
+                let text = message(context.lang, Message::OutOfGuesses, &[&target_name]);
+                writeln!(out, "{text}\n")?;
+                break Ok(None);
+            }
+
             player.update(guess, &c);
         } else {
-            println!("{}", " =  1  1  =  1".green());
-            println!("Won! The sinner was {}!", target.name);
-            println!("Won in {} guesses!\n", game.guess_num());
-            break game.guess_num();
+            if compact {
+                writeln!(out, "{}", winning_row(&context.theme))?;
+            } else {
+                let cells = winning_cells(&context.theme);
+                writeln!(out, "{}", row(context.lang, name, &cells))?;
+            }
+            writeln!(
+                out,
+                "{}",
+                message(context.lang, Message::Won, &[&target_name])
+            )?;
+            writeln!(
+                out,
+                "{}\n",
+                message(context.lang, Message::WonIn, &[&game.guess_num()])
+            )?;
+            break Ok(Some(game.guess_num()));
         }
     }
 }
 
-#[expect(clippy::float_arithmetic, reason = "statistics")]
-#[expect(clippy::unnecessary_wraps, reason = "maybe fallible later")]
-pub fn gather_data(sinners: &[Sinner]) -> eyre::Result<()> {
-    let sinner_data: Vec<(u8, &Sinner)> = sinners
-        .iter()
-        .map(|target| {
-            (
-                play_game(target, OptimalPlayer::new(sinners.to_owned())),
-                target,
-            )
-        })
-        .collect();
-
-    println!(
-        "Goto first sinner to play: {}",
-        OptimalPlayer::new(sinners.to_owned())
-            .next_guess()
-            .unwrap()
-            .name
-    );
-    let (max_rounds, _) = sinner_data
+/// Plays a game with `player` trying to find every sinner in `targets`,
+/// writing a row for each target after every guess to `out`, in the same
+/// format as [`play_game`]. Returns the finished game, which is lost if any
+/// target wasn't found.
+pub fn play_targets<'game, P: Player>(
+    context: &Context,
+    targets: Vec<&'game Sinner>,
+    mut player: P,
+    thresholds: &ThresholdConfig,
+    max_guesses: Option<u8>,
+    compact: bool,
+    out: &mut impl Write,
+) -> std::io::Result<Game<'game>> {
+    let names = targets
         .iter()
-        .max_by_key(|(guesses, _)| *guesses)
-        .unwrap();
-    let max_round_sinners = sinner_data
-        .iter()
-        .filter(|(guesses, _)| guesses == max_rounds);
-    println!("It takes {max_rounds} or less guesses to guess any sinner.");
-
-    #[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
-    for rounds in 1..=*max_rounds {
-        let count = sinner_data.iter().filter(|(v, _)| *v == rounds).count();
-        println!(
-            "    {count} sinners take {rounds} guesses ({:.2}%)",
-            count as f64 * 100. / sinner_data.len() as f64
-        );
+        .map(|x| context.display_name(&x.name))
+        .collect::<Vec<_>>()
+        .join(&message(context.lang, Message::And, &[]));
+    let mut game = Game::with_targets(targets, *thresholds);
+    if !compact {
+        writeln!(out, "{}", header(context, Message::Target))?;
     }
-    println!("The sinners that take the maximum number of guesses are:");
-    for (_, sinner) in max_round_sinners {
-        println!("    {}", sinner.name);
+    loop {
+        let Some(play) = player.next_guess() else {
+            if player.gave_up() {
+                writeln!(
+                    out,
+                    "{}\n",
+                    message(context.lang, Message::GaveUpAll, &[&names])
+                )?;
+            } else {
+                writeln!(out, "{}\n", message(context.lang, Message::NoGuesses, &[]))?;
+            }
+            return Ok(game);
+        };
+        let play = play.clone();
+        let name = context.display_name(&play.name);
+        writeln!(out, "{}", message(context.lang, Message::Guessed, &[&name]))?;
+        let guess_num = game.guess_num();
+        let results = game.guess_all(&play);
+        for (index, (result, found)) in results.iter().zip(game.found()).enumerate() {
+            let number = index + 1;
+            let won = found.is_some_and(|x| x == guess_num);
+            let (lang, theme) = (context.lang, &context.theme);
+            let line = match result {
+                | Some(result) if compact => format!("{number}: {}", result.paint(theme)),
+                | Some(result) => row(lang, &number.to_string(), &result.cells(theme)),
+                | None if won && compact => format!("{number}: {}", winning_row(theme)),
+                | None if won => row(lang, &number.to_string(), &winning_cells(theme)),
+                | None if compact => {
+                    format!("{number}: {}", message(context.lang, Message::Found, &[]))
+                },
+                | None => {
+                    format!(
+                        "{number:<NAME_WIDTH$}  {}",
+                        message(context.lang, Message::Found, &[])
+                    )
+                },
+            };
+            writeln!(out, "{line}")?;
+        }
+        if game.is_won() {
+            writeln!(out, "{}", message(context.lang, Message::WonAll, &[&names]))?;
+            writeln!(
+                out,
+                "{}\n",
+                message(context.lang, Message::WonIn, &[&game.guess_num()])
+            )?;
+            return Ok(game);
+        }
+        if max_guesses.is_some_and(|max| game.guess_num() > max) {
+            let text = message(context.lang, Message::OutOfGuessesAll, &[&names]);
+            writeln!(out, "{text}\n")?;
+            return Ok(game);
+        }
+        player.update_targets(&results, &play);
     }
-    let sum: u32 = sinner_data.iter().map(|(r, _)| u32::from(*r)).sum();
+}
 
-    #[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
-    {
-        println!(
-            "The mean number of guesses is {:.2}",
-            f64::from(sum) / sinner_data.len() as f64
-        );
-    };
+/// Creates a new [`Player`] for a game with the sinners in a [`GuessMatrix`]
+pub type CreatePlayer<'factory> = dyn Fn(&Arc<GuessMatrix>) -> Box<dyn Player> + Sync + 'factory;
 
-    Ok(())
+/// A named way of creating a new [`Player`] for every game played when
+/// gathering data
+pub struct PlayerFactory<'factory> {
+    pub name: String,
+    pub create: Box<CreatePlayer<'factory>>,
 }
 
-pub fn solve(initial_state: &[NameAndGuess], sinners: Vec<Sinner>) -> eyre::Result<()> {
-    println!("======== Welcome to the Path to Nowordle Solver ========");
-    println!(
-        "This solver always wins within 4 guesses from an unknown sinner target, but typically \
-         wins in 3 or less.\n"
-    );
-    println!("======== Instructions ========");
-    println!(
-        "Enter a row as seen on the website when prompted and guess the sinner you are prompted \
-         to play."
-    );
-    println!("Entries in the row are separated by whitespace.");
-    println!("Comparisons are entered as vv/v/~/=/^/^^ and booleans are entered as 0 or 1.");
-    println!("An example input is ^^ 0 0 ~ 1");
-    println!("==============================");
-    let sinners_clone = sinners.clone();
-    let mut player = OptimalPlayer::new(sinners);
-
-    for NameAndGuess { name, guess } in initial_state {
-        let sinner = sinners_clone
-            .iter()
-            .find(|x| x.name.eq_ignore_ascii_case(name))
-            .ok_or_else(|| eyre!("No sinner with name {name} found"))?
-            .clone();
-        player.update(*guess, &sinner);
-    }
-    if !initial_state.is_empty() {
-        let names = player.candidates.iter().map(|x| x.name.as_str());
-        println!("Possible Sinners: {}", names.collect::<Vec<_>>().join(", "));
-    }
-    'outer: loop {
-        let sinner = player
-            .next_guess()
-            .ok_or_else(|| {
-                eyre!("No possible guesses in this state. There is likely a contradiction.")
-            })?
-            .clone();
-        println!("Guess {}", sinner.name);
-        if player.candidates.len() == 1 {
-            println!("GG! You won.");
-            break;
+impl PlayerFactory<'_> {
+    /// Creates a factory for the [`Solver`](crate::solver::Solver)s described
+    /// by `config`, named after its strategy
+    pub fn from_config(config: SolverConfig) -> Self {
+        Self {
+            name: config.strategy.to_string(),
+            create: Box::new(move |matrix| config.solver(Arc::clone(matrix))),
         }
+    }
+}
 
-        let guess;
-        loop {
-            let mut line = <_>::default();
-            print!("Enter row or q to quit: ");
-            stdout().flush()?;
-            stdin().read_line(&mut line)?;
-            if line.trim() == "q" {
-                break 'outer;
-            }
-            if let Ok(g) = line.parse::<Guess>() {
-                guess = g;
-                break;
-            }
-        }
+/// A game played when gathering data
+pub struct GameResult<'data> {
+    pub target: &'data Sinner,
+    /// The number of guesses it took to guess the target, or `None` if the
+    /// player gave up
+    pub guesses: Option<u8>,
+    /// The names of the sinners guessed, in order, including the target
+    pub sequence: Vec<String>,
+}
 
-        player.update(guess, &sinner);
-        let names = player.candidates.iter().map(|x| x.name.as_str());
+impl GameResult<'_> {
+    /// The sinners guessed, in order, with the result each guess got. The
+    /// sinners are looked up in `matrix` by name.
+    pub fn trace<'matrix>(&self, matrix: &'matrix GuessMatrix) -> Vec<(&'matrix Sinner, Guess)> {
+        self.sequence
+            .iter()
+            .filter_map(|name| matrix.sinners().iter().find(|x| x.name == *name))
+            .map(|x| (x, self.target.guess(x, matrix.thresholds())))
+            .collect()
+    }
+}
 
-        println!("Possible Sinners: {}", names.collect::<Vec<_>>().join(", "));
+/// The results of playing every possible game with a single player
+pub struct GatherResults<'data> {
+    pub name: String,
+    /// The first sinner the player guesses
+    pub first_guess: String,
+    pub games: Vec<GameResult<'data>>,
+}
+
+impl<'data> GatherResults<'data> {
+    /// Plays every possible game with players created by `factory`, in
+    /// parallel. The progress of each game is written to `out` in the same
+    /// order as the sinners in `matrix`. Games in `checkpoint` aren't played
+    /// again, and the others are saved to it as they end.
+    pub fn gather(
+        context: &Context,
+        matrix: &'data Arc<GuessMatrix>,
+        factory: &PlayerFactory<'_>,
+        checkpoint: Option<&Checkpoint>,
+        out: &mut impl Write,
+    ) -> std::io::Result<Self> {
+        let progress = Progress::new(format!("gather {}", factory.name), matrix.len(), context);
+        let played = matrix
+            .sinners()
+            .par_iter()
+            .map(|target| {
+                if let Some(game) = checkpoint.and_then(|x| x.get(&target.name)) {
+                    progress.step(&target.name);
+                    let result = GameResult {
+                        target,
+                        guesses: game.guesses,
+                        sequence: game.sequence.clone(),
+                    };
+                    return Ok((result, game.log.clone().into_bytes()));
+                }
+                let mut log = Vec::new();
+                let mut player = RecordingPlayer::new((factory.create)(matrix));
+                let guesses = play_game(
+                    context,
+                    target,
+                    &mut player,
+                    matrix.thresholds(),
+                    None,
+                    true,
+                    &mut log,
+                )?;
+                let mut sequence = player
+                    .guessed
+                    .into_iter()
+                    .map(|(x, _)| x)
+                    .collect::<Vec<_>>();
+                if guesses.is_some() {
+                    sequence.push(target.name.clone());
+                }
+                let game = GameResult {
+                    target,
+                    guesses,
+                    sequence,
+                };
+                if let Some(checkpoint) = checkpoint {
+                    checkpoint.record(&CheckpointGame {
+                        target: target.name.clone(),
+                        guesses: game.guesses,
+                        sequence: game.sequence.clone(),
+                        log: String::from_utf8_lossy(&log).into_owned(),
+                    })?;
+                }
+                progress.step(&target.name);
+                Ok((game, log))
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
+        progress.finish();
+        let mut games = Vec::with_capacity(played.len());
+        for (game, log) in played {
+            out.write_all(&log)?;
+            games.push(game);
+        }
+        let first_guess = (factory.create)(matrix)
+            .next_guess()
+            .map_or_else(|| "-".to_owned(), |x| x.name.clone());
+        Ok(Self {
+            name: factory.name.clone(),
+            first_guess,
+            games,
+        })
+    }
+    /// The maximum number of guesses it takes to guess any sinner the player
+    /// found
+    pub fn max_guesses(&self) -> u8 {
+        self.games
+            .iter()
+            .filter_map(|game| game.guesses)
+            .max()
+            .unwrap_or_default()
+    }
+    /// The number of sinners that take `guesses` guesses
+    pub fn count(&self, guesses: u8) -> usize {
+        self.games
+            .iter()
+            .filter(|x| x.guesses == Some(guesses))
+            .count()
+    }
+    /// The number of games the player gave up, which aren't counted in the
+    /// other statistics
+    pub fn losses(&self) -> usize { self.games.iter().filter(|x| x.guesses.is_none()).count() }
+    /// The games that take the maximum number of guesses
+    pub fn hardest_games(&self) -> impl Iterator<Item = &GameResult<'data>> {
+        let max = self.max_guesses();
+        self.games
+            .iter()
+            .filter(move |game| game.guesses == Some(max))
+    }
+    /// The sinners that take the maximum number of guesses
+    pub fn hardest(&self) -> impl Iterator<Item = &'data Sinner> + '_ {
+        self.hardest_games().map(|game| game.target)
+    }
+    /// The mean number of guesses it takes to guess a sinner the player found
+    #[expect(clippy::float_arithmetic, reason = "statistics")]
+    pub fn mean(&self) -> f64 {
+        let sum: u32 = self
+            .games
+            .iter()
+            .filter_map(|x| x.guesses)
+            .map(u32::from)
+            .sum();
+        let won = self.games.len() - self.losses();
+        #[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
+        {
+            f64::from(sum) / won.max(1) as f64
+        }
     }
-    Ok(())
 }
 
 #[derive(Debug, Facet)]
@@ -393,3 +600,16 @@ impl FromStr for NameAndGuess {
         })
     }
 }
+/// A comma-separated list of [`NameAndGuess`]es
+#[derive(Debug, Default)]
+pub struct NameAndGuesses(pub Vec<NameAndGuess>);
+
+impl FromStr for NameAndGuesses {
+    type Err = NameAndGuessError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|s| s.trim().parse())
+            .collect::<Result<_, Self::Err>>()
+            .map(NameAndGuesses)
+    }
+}
@@ -0,0 +1,70 @@
+//! The progress of long runs, such as gathering data, reported to the
+//! [`Reporter`] of the program using the library, such as the CLI's progress
+//! bar
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use facet::Facet;
+
+use crate::verbosity::Reporter;
+
+/// A step of a run that just ended
+#[derive(Debug, Facet)]
+pub struct ProgressEvent {
+    /// The name of the run, such as `gather mean`
+    pub task: String,
+    pub done: usize,
+    pub total: usize,
+    /// How much of the run is done, rounded down to a whole percent
+    pub percent: usize,
+    /// What the step that just ended was about, such as the target of a game
+    pub current: String,
+}
+
+/// A run of `total` steps, whose progress is reported as each step ends.
+/// Steps may end on any thread.
+pub struct Progress<'reporter> {
+    task: String,
+    total: usize,
+    reporter: &'reporter dyn Reporter,
+    /// The number of steps that ended
+    done: AtomicUsize,
+    /// Held while a step is counted and reported, which keeps the reports in
+    /// order
+    reporting: Mutex<()>,
+}
+
+impl<'reporter> Progress<'reporter> {
+    /// Starts a run named `task`, such as `gather mean`, reported to
+    /// `reporter`
+    pub fn new(task: impl Into<String>, total: usize, reporter: &'reporter dyn Reporter) -> Self {
+        Self {
+            task: task.into(),
+            total,
+            reporter,
+            done: AtomicUsize::new(0),
+            reporting: Mutex::new(()),
+        }
+    }
+
+    /// Reports that the step about `current`, such as the target of a game,
+    /// ended
+    pub fn step(&self, current: &str) {
+        let _reporting = self
+            .reporting
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        self.reporter.step(&ProgressEvent {
+            task: self.task.clone(),
+            done,
+            total: self.total,
+            percent: done * 100 / self.total.max(1),
+            current: current.to_owned(),
+        });
+    }
+
+    /// Ends the run
+    pub fn finish(self) { self.reporter.finish(&self.task); }
+}
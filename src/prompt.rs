@@ -0,0 +1,147 @@
+//! Line editing shared by the interactive shells
+
+use ptndle_core::data::Sinner;
+use ptndle_core::fuzzy::fold;
+use reedline::{default_emacs_keybindings, ColumnarMenu, Completer, DefaultCompleter,
+               DefaultPrompt, DefaultPromptSegment, Emacs, FileBackedHistory, KeyCode,
+               KeyModifiers, MenuBuilder, Reedline, ReedlineEvent, ReedlineMenu, Signal, Span,
+               Suggestion};
+
+use crate::console::warning;
+use crate::settings;
+
+/// How many lines of history are kept for each shell
+const HISTORY_SIZE: usize = 1000;
+
+/// Completes words like [`DefaultCompleter`], falling back to words that
+/// only match what was typed once both are [`fold`]ed, so `hel` and `ＮＯ`
+/// complete to `Hella` and `NOX`
+struct FoldingCompleter {
+    exact: DefaultCompleter,
+    /// The words with their folded forms
+    words: Vec<(String, String)>,
+}
+
+impl Completer for FoldingCompleter {
+    fn complete(&mut self, line: &str, pos: usize) -> Vec<Suggestion> {
+        let exact = self.exact.complete(line, pos);
+        if !exact.is_empty() {
+            return exact;
+        }
+        let Some((line, _)) = line.split_at_checked(pos) else {
+            return Vec::new();
+        };
+        // Like the default completer, the last word is completed, then the
+        // last two words, and so on, for names with spaces
+        let starts = line
+            .match_indices(' ')
+            .map(|(index, _)| index + 1)
+            .rev()
+            .chain([0]);
+        let mut suggestions = Vec::new();
+        for start in starts {
+            let Some((_, typed)) = line.split_at_checked(start) else {
+                continue;
+            };
+            let folded = fold(typed);
+            if folded.is_empty() {
+                continue;
+            }
+            suggestions.extend(
+                self.words
+                    .iter()
+                    .filter(|(word, x)| x.starts_with(&folded) && word != typed)
+                    .map(|(word, _)| {
+                        Suggestion {
+                            value: word.clone(),
+                            span: Span::new(start, pos),
+                            ..Suggestion::default()
+                        }
+                    }),
+            );
+        }
+        suggestions.dedup();
+        suggestions
+    }
+}
+
+/// A line editor with Emacs keybindings that completes `words` when Tab is
+/// pressed
+pub fn completing_editor(words: Vec<String>) -> Reedline {
+    let mut exact = DefaultCompleter::with_inclusions(&['.', '-', '^', '~', '=']);
+    exact.insert(words.clone());
+    let completer = FoldingCompleter {
+        exact,
+        words: words
+            .into_iter()
+            .map(|x| {
+                let folded = fold(&x);
+                (x, folded)
+            })
+            .collect(),
+    };
+    let mut keybindings = default_emacs_keybindings();
+    keybindings.add_binding(
+        KeyModifiers::NONE,
+        KeyCode::Tab,
+        ReedlineEvent::UntilFound(vec![
+            ReedlineEvent::Menu("completion_menu".to_owned()),
+            ReedlineEvent::MenuNext,
+        ]),
+    );
+    let completion_menu = Box::new(ColumnarMenu::default().with_name("completion_menu"));
+    Reedline::create()
+        .with_edit_mode(Box::new(Emacs::new(keybindings)))
+        .with_completer(Box::new(completer))
+        .with_menu(ReedlineMenu::EngineCompleter(completion_menu))
+        .with_ansi_colors(settings::styled())
+}
+
+/// Keeps `editor`'s history in `file` in the data directory, so that lines
+/// entered in earlier sessions can be recalled with the up arrow and Ctrl-R
+pub fn with_history(editor: Reedline, file: &str) -> Reedline {
+    let path = settings::data_dir().join(file);
+    match FileBackedHistory::with_file(HISTORY_SIZE, path) {
+        | Ok(history) => editor.with_history(Box::new(history)),
+        | Err(e) => {
+            warning!("Could not load the history in {file}: {e}");
+            editor
+        },
+    }
+}
+
+/// Writes the lines entered so far to the history file, which otherwise only
+/// happens when the editor is dropped
+pub fn save_history(editor: &mut Reedline) {
+    // History is nice to have, so a failed write isn't worth interrupting for
+    _ = editor.sync_history();
+}
+
+/// The names of `sinners` and all of their aliases and translations, for
+/// completion
+pub fn sinner_names(sinners: &[Sinner]) -> impl Iterator<Item = &str> {
+    let names = settings::names();
+    sinners.iter().flat_map(|x| {
+        names
+            .aliases
+            .of(&x.name)
+            .chain(names.translations.of(&x.name))
+            .chain([x.name.as_str()])
+    })
+}
+
+/// Reads a line with `label` as the prompt. Returns `None` if the user pressed
+/// Ctrl-C or Ctrl-D.
+pub fn read_line(editor: &mut Reedline, label: &str) -> eyre::Result<Option<String>> {
+    let prompt = DefaultPrompt::new(
+        DefaultPromptSegment::Basic(label.to_owned()),
+        DefaultPromptSegment::Empty,
+    );
+    Ok(match editor.read_line(&prompt)? {
+        | Signal::Success(line) => {
+            save_history(editor);
+            Some(line.trim().to_owned())
+        },
+        | Signal::CtrlC | Signal::CtrlD => None,
+    })
+}
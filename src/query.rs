@@ -0,0 +1,180 @@
+//! Picking out and ordering sinners by their data, such as every Fury sinner
+//! from the Syndicate sorted by height
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::data::{Alignment, BirthPlace, Sinner, Tendency};
+
+/// An inclusive range of heights in cm, either end of which may be open
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HeightRange {
+    pub min: Option<u8>,
+    pub max: Option<u8>,
+}
+
+impl HeightRange {
+    pub fn contains(self, height: u8) -> bool {
+        self.min.is_none_or(|min| height >= min) && self.max.is_none_or(|max| height <= max)
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidHeightRangeError(String);
+
+impl FromStr for HeightRange {
+    type Err = InvalidHeightRangeError;
+    /// Parses `160..175`, `160..`, `..175` or a single height like `168`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || InvalidHeightRangeError(s.to_owned());
+        let bound = |x: &str| {
+            let x = x.trim();
+            let x = x.strip_suffix("cm").unwrap_or(x).trim();
+            if x.is_empty() {
+                Ok(None)
+            } else {
+                x.parse().map(Some).map_err(|_| error())
+            }
+        };
+        let (min, max) = match s.split_once("..") {
+            | Some((min, max)) => (bound(min)?, bound(max)?),
+            | None => {
+                let height = bound(s)?.ok_or_else(error)?;
+                (Some(height), Some(height))
+            },
+        };
+        Ok(Self { min, max })
+    }
+}
+impl Display for InvalidHeightRangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Invalid height range: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected a range like `160..175`, `160..`, `..175` or a single height")
+    }
+}
+
+/// What to sort sinners by
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    #[default]
+    Name,
+    Code,
+    Alignment,
+    Tendency,
+    Height,
+    Birthplace,
+}
+
+impl SortKey {
+    /// Compares two sinners by this key, then by name
+    pub fn compare(self, a: &Sinner, b: &Sinner) -> Ordering {
+        let ordering = match self {
+            | Self::Name => Ordering::Equal,
+            // NOX, which has no numeric code, goes last
+            | Self::Code => a.code.unwrap_or(u16::MAX).cmp(&b.code.unwrap_or(u16::MAX)),
+            // Enums are sorted alphabetically, which is how a reader expects them
+            | Self::Alignment => format!("{:?}", a.alignment).cmp(&format!("{:?}", b.alignment)),
+            | Self::Tendency => format!("{:?}", a.tendency).cmp(&format!("{:?}", b.tendency)),
+            | Self::Height => a.height.cmp(&b.height),
+            | Self::Birthplace => format!("{:?}", a.birthplace).cmp(&format!("{:?}", b.birthplace)),
+        };
+        ordering.then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownSortKeyError(String);
+
+impl FromStr for SortKey {
+    type Err = UnknownSortKeyError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().to_ascii_lowercase().as_str() {
+            | "name" => Self::Name,
+            | "code" => Self::Code,
+            | "alignment" => Self::Alignment,
+            | "tendency" => Self::Tendency,
+            | "height" => Self::Height,
+            | "birthplace" => Self::Birthplace,
+            | _ => return Err(UnknownSortKeyError(s.to_owned())),
+        })
+    }
+}
+impl Display for UnknownSortKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Unknown sort key: `")?;
+        f.write_str(&self.0)?;
+        f.write_str(
+            "`. Expected one of `name`, `code`, `alignment`, `tendency`, `height` or `birthplace`",
+        )
+    }
+}
+
+/// Which sinners to pick and how to order them. Every condition that is set
+/// has to match.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Query {
+    pub alignment: Option<Alignment>,
+    pub tendency: Option<Tendency>,
+    pub birthplace: Option<BirthPlace>,
+    pub height: Option<HeightRange>,
+    pub sort: SortKey,
+}
+
+impl Query {
+    pub fn matches(&self, sinner: &Sinner) -> bool {
+        self.alignment.is_none_or(|x| x == sinner.alignment) &&
+            self.tendency.is_none_or(|x| x == sinner.tendency) &&
+            self.birthplace.is_none_or(|x| x == sinner.birthplace) &&
+            self.height.is_none_or(|x| x.contains(sinner.height))
+    }
+
+    /// The sinners that match, in order
+    pub fn run<'sinners>(&self, sinners: &'sinners [Sinner]) -> Vec<&'sinners Sinner> {
+        let mut matching = sinners
+            .iter()
+            .filter(|x| self.matches(x))
+            .collect::<Vec<_>>();
+        matching.sort_by(|a, b| self.sort.compare(a, b));
+        matching
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidQueryError(String);
+
+impl FromStr for Query {
+    type Err = InvalidQueryError;
+    /// Parses comma-separated `key=value` conditions with the same keys and
+    /// values as the flags of `sinners`, such as
+    /// `alignment=Greed,height=170..`. The sinners are sorted by name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut query = Self::default();
+        for condition in s.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            let (key, value) = condition.split_once('=').ok_or_else(|| {
+                InvalidQueryError(format!(
+                    "Expected `key=value` conditions like `alignment=Greed`, found `{condition}`"
+                ))
+            })?;
+            let invalid = |e: &dyn Display| InvalidQueryError(e.to_string());
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                | "alignment" => query.alignment = Some(value.parse().map_err(|e| invalid(&e))?),
+                | "tendency" => query.tendency = Some(value.parse().map_err(|e| invalid(&e))?),
+                | "birthplace" => query.birthplace = Some(value.parse().map_err(|e| invalid(&e))?),
+                | "height" => query.height = Some(value.parse().map_err(|e| invalid(&e))?),
+                | key => {
+                    return Err(InvalidQueryError(format!(
+                        "Unknown condition: `{key}`. Expected one of `alignment`, `tendency`, \
+                         `birthplace` or `height`"
+                    )));
+                },
+            }
+        }
+        Ok(query)
+    }
+}
+impl Display for InvalidQueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str(&self.0) }
+}
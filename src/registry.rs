@@ -0,0 +1,184 @@
+//! The players that can be picked by name, so that commands comparing players
+//! don't need to know how each one is created
+//!
+//! A [`Registry`] starts with the built-in players, and library users can
+//! [`register`](Registry::register) their own
+//! [`Player`](crate::play::Player)s in it. Each player
+//! is a [`PlayerFactory`], which plays every game with
+//! [`GatherResults::gather`](crate::play::GatherResults::gather) and can be
+//! picked by a [`StrategyName`] with [`StrategyName::factory`]. A player named
+//! after a built-in one replaces it, except that the names of the solver's
+//! strategies, such as `mean`, always pick the solver in a [`StrategyName`].
+
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::play::PlayerFactory;
+use crate::rng::Rng;
+use crate::solver::{BlindPlayer, SolverConfig, Strategy};
+
+/// Named ways of creating [`Player`](crate::play::Player)s, in the order they
+/// were registered
+#[derive(Default)]
+pub struct Registry<'factory> {
+    factories: Vec<PlayerFactory<'factory>>,
+}
+
+impl<'factory> Registry<'factory> {
+    /// A registry without any players
+    pub fn new() -> Self { Self::default() }
+
+    /// Every built-in player: the solver with each [`Strategy`], the solver
+    /// with each strategy other than random and probing as `<strategy>+probe`,
+    /// and `blind`, which guesses random sinners without looking at the
+    /// results. The solvers are created from `config` with each strategy and
+    /// probing, and the players that guess randomly use its seed.
+    pub fn builtin(config: SolverConfig) -> Self {
+        let mut registry = Self::new();
+        for probe in [false, true] {
+            // The random strategy doesn't probe, so it would be a copy
+            for strategy in Strategy::ALL
+                .into_iter()
+                .filter(|&x| !probe || x != Strategy::Random)
+            {
+                let config = SolverConfig {
+                    strategy,
+                    probe,
+                    ..config
+                };
+                let mut factory = PlayerFactory::from_config(config);
+                if probe {
+                    factory.name.push_str("+probe");
+                }
+                registry.register(factory);
+            }
+        }
+        registry.register(PlayerFactory {
+            name: "blind".to_owned(),
+            create: Box::new(move |matrix| {
+                Box::new(BlindPlayer::new(Arc::clone(matrix), Rng::new(config.seed)))
+            }),
+        });
+        registry
+    }
+
+    /// Adds `factory`, replacing the player with the same name if there is one
+    pub fn register(&mut self, factory: PlayerFactory<'factory>) {
+        match self.factories.iter_mut().find(|x| x.name == factory.name) {
+            | Some(existing) => *existing = factory,
+            | None => self.factories.push(factory),
+        }
+    }
+
+    /// The player named `name`, if there is one
+    pub fn get(&self, name: &str) -> Option<&PlayerFactory<'factory>> {
+        self.factories.iter().find(|x| x.name == name)
+    }
+
+    /// Removes the player named `name` and returns it, if there is one
+    pub fn take(&mut self, name: &str) -> Option<PlayerFactory<'factory>> {
+        let index = self.factories.iter().position(|x| x.name == name)?;
+        Some(self.factories.remove(index))
+    }
+
+    /// The names of every player
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.iter().map(|x| x.name.as_str())
+    }
+
+    /// Every player, in the order they were registered
+    pub fn iter(&self) -> impl Iterator<Item = &PlayerFactory<'factory>> { self.factories.iter() }
+}
+
+/// A strategy picked with `--strategy`: one of the solver's [`Strategy`]s, or
+/// the name of another player in a [`Registry`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StrategyName {
+    Solver(Strategy),
+    Player(String),
+}
+
+impl Default for StrategyName {
+    fn default() -> Self { Self::Solver(Strategy::default()) }
+}
+
+impl StrategyName {
+    /// The solver's strategy, if this is one
+    pub const fn solver(&self) -> Option<Strategy> {
+        match self {
+            | Self::Solver(strategy) => Some(*strategy),
+            | Self::Player(_) => None,
+        }
+    }
+
+    /// Creates the player with this strategy. A solver is created from
+    /// `config` with this strategy, and any other player is taken from
+    /// `registry`.
+    pub fn factory<'factory>(
+        &self,
+        config: SolverConfig,
+        mut registry: Registry<'factory>,
+    ) -> Result<PlayerFactory<'factory>, UnknownPlayerError> {
+        match self {
+            | Self::Solver(strategy) => {
+                Ok(PlayerFactory::from_config(SolverConfig {
+                    strategy: *strategy,
+                    ..config
+                }))
+            },
+            | Self::Player(name) => {
+                registry.take(name).ok_or_else(|| {
+                    UnknownPlayerError {
+                        name: name.clone(),
+                        expected: registry.names().map(ToOwned::to_owned).collect(),
+                    }
+                })
+            },
+        }
+    }
+}
+
+impl Display for StrategyName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            | Self::Solver(strategy) => write!(f, "{strategy}"),
+            | Self::Player(name) => f.write_str(name),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownPlayerError {
+    name: String,
+    /// The names of every player that could have been picked
+    expected: Vec<String>,
+}
+
+impl FromStr for StrategyName {
+    type Err = UnknownPlayerError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Players can be registered after the flags are read, so the name is
+        // only looked up when the player is created
+        let name = s.trim();
+        if name.is_empty() {
+            return Err(UnknownPlayerError {
+                name: s.to_owned(),
+                expected: Registry::builtin(SolverConfig::default())
+                    .names()
+                    .map(ToOwned::to_owned)
+                    .collect(),
+            });
+        }
+        Ok(name
+            .parse()
+            .map_or_else(|_| Self::Player(name.to_owned()), Self::Solver))
+    }
+}
+impl Display for UnknownPlayerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Unknown strategy: `")?;
+        f.write_str(&self.name)?;
+        write!(f, "`. Expected one of {}", self.expected.join(", "))
+    }
+}
@@ -0,0 +1,35 @@
+use eyre::eyre;
+use getrandom::getrandom;
+
+/// A small seedable pseudo-random number generator (`SplitMix64`).
+///
+/// The numbers generated from a seed never change, so the same seed gives the
+/// same results on every machine.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub const fn new(seed: u64) -> Self { Self(seed) }
+
+    /// Creates a generator seeded by the operating system
+    pub fn from_entropy() -> eyre::Result<Self> {
+        let mut buf = 0u64.to_ne_bytes();
+        getrandom(&mut buf).map_err(|e| eyre!("Failed to get random number: {e}"))?;
+        Ok(Self::new(u64::from_le_bytes(buf)))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Generates a number in `0..bound`
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "the result is less than `bound`"
+    )]
+    pub fn below(&mut self, bound: usize) -> usize { (self.next_u64() % bound as u64) as usize }
+}
@@ -0,0 +1,157 @@
+//! Games of `play` in progress, saved after each guess so that they can be
+//! picked up again with `play --resume`
+
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use facet::Facet;
+use ptndle_core::daily::Day;
+use ptndle_core::data::Sinner;
+use ptndle_core::error::Error;
+
+use crate::settings;
+use crate::stats::{new_id, GameRecord, GuessRecord, Stats};
+
+/// A game that hasn't ended yet
+#[derive(Debug, Clone)]
+pub struct SavedGame {
+    pub target: String,
+    /// The date of the daily puzzle, if the game is the daily
+    pub daily: Option<String>,
+    /// The number of guesses the game allows, or `None` for no limit
+    pub max_guesses: Option<u8>,
+    /// The sinners guessed so far, in order
+    pub guessed: Vec<String>,
+}
+
+/// A [`SavedGame`] as it's written to its file, with the target obfuscated so
+/// that a look at the file doesn't spoil it
+#[derive(Debug, Facet)]
+struct SaveFile {
+    target: String,
+    daily: Option<String>,
+    max_guesses: Option<u8>,
+    guessed: Vec<String>,
+}
+
+/// The key the target's name is xored with. This only keeps the name from
+/// being read at a glance, it isn't meant to be secure.
+const KEY: &[u8] = b"Path-To-Nowordle";
+
+fn obfuscate(name: &str) -> String {
+    let mut hex = String::with_capacity(name.len() * 2);
+    for (byte, key) in name.bytes().zip(KEY.iter().cycle()) {
+        _ = write!(hex, "{:02x}", byte ^ key);
+    }
+    hex
+}
+
+fn deobfuscate(hex: &str) -> Option<String> {
+    let bytes = hex
+        .as_bytes()
+        .chunks(2)
+        .zip(KEY.iter().cycle())
+        .map(|(pair, key)| {
+            let pair = std::str::from_utf8(pair).ok()?;
+            u8::from_str_radix(pair, 16).ok().map(|x| x ^ key)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+/// The file of the saved daily, or of the saved game that isn't the daily.
+/// They're kept apart so that starting another game doesn't lose the daily.
+fn saved_game_path(daily: bool) -> PathBuf {
+    settings::data_dir().join(if daily {
+        "saved_daily.json"
+    } else {
+        "saved_game.json"
+    })
+}
+
+impl SavedGame {
+    /// Loads the saved daily, or the saved game that isn't the daily, if there
+    /// is one
+    pub fn load(daily: bool) -> eyre::Result<Option<Self>> {
+        let path = saved_game_path(daily);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| Error::Io(format!("Failed to read {}: {e}", path.display())))?;
+        let file: SaveFile = facet_json::from_str(&json)
+            .map_err(|e| Error::InvalidInput(format!("Failed to parse {}: {e}", path.display())))?;
+        let target = deobfuscate(&file.target).ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "Failed to parse {}: invalid target",
+                path.display()
+            ))
+        })?;
+        Ok(Some(Self {
+            target,
+            daily: file.daily,
+            max_guesses: file.max_guesses,
+            guessed: file.guessed,
+        }))
+    }
+
+    /// The game `play --resume` picks up: the saved daily if there is one, as
+    /// it can't be played again otherwise, or else the other saved game
+    pub fn load_any() -> eyre::Result<Option<Self>> {
+        match Self::load(true)? {
+            | Some(saved) => Ok(Some(saved)),
+            | None => Self::load(false),
+        }
+    }
+
+    /// Saves the game, replacing the one saved before in its file
+    pub fn save(&self) -> eyre::Result<()> {
+        let file = SaveFile {
+            target: obfuscate(&self.target),
+            daily: self.daily.clone(),
+            max_guesses: self.max_guesses,
+            guessed: self.guessed.clone(),
+        };
+        std::fs::create_dir_all(settings::data_dir())
+            .map_err(|e| Error::Io(format!("Failed to create data directory: {e}")))?;
+        std::fs::write(
+            saved_game_path(self.daily.is_some()),
+            facet_json::to_string(&file),
+        )
+        .map_err(|e| Error::Io(format!("Failed to save the game: {e}")).into())
+    }
+
+    /// Removes the saved daily, or the saved game that isn't the daily, once
+    /// the game has ended
+    pub fn clear(daily: bool) -> eyre::Result<()> {
+        match std::fs::remove_file(saved_game_path(daily)) {
+            | Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(Error::Io(format!("Failed to remove the saved game: {e}")).into())
+            },
+            | _ => Ok(()),
+        }
+    }
+
+    /// Saves the game after it was interrupted, and records it in the stats
+    /// as abandoned until it is resumed
+    pub fn abandon(&self, target: &Sinner, guessed: &[Sinner]) -> eyre::Result<()> {
+        self.save()?;
+        Stats::record(GameRecord {
+            id: new_id()?,
+            date: Day::today()?.to_string(),
+            target: self.target.clone(),
+            guesses: None,
+            daily: self.daily.is_some(),
+            guessed: guessed
+                .iter()
+                .map(|x| {
+                    GuessRecord {
+                        name: x.name.clone(),
+                        result: target.guess(x, settings::thresholds()).to_ascii(),
+                    }
+                })
+                .collect(),
+            abandoned: true,
+        })
+    }
+}
@@ -0,0 +1,361 @@
+//! Players whose guesses are picked by a script, added with `--strategy-script`
+//! so that new heuristics can be tried without recompiling
+//!
+//! A script ending in `.rhai` is run in an embedded [Rhai](https://rhai.rs)
+//! engine. It defines `fn guess(candidates, history)`, which is called before
+//! each guess with the names of the sinners that fit every result so far and
+//! the guesses made so far, as maps with a `name` and a `row`, and returns the
+//! name of the sinner to guess:
+//!
+//! ```rhai
+//! fn guess(candidates, history) {
+//!     candidates[0]
+//! }
+//! ```
+//!
+//! Any other script is a program that can be run, such as a file with a `#!`
+//! line. It's started for every game and sent the same as a line of JSON
+//! before each guess:
+//!
+//! ```json
+//! {"candidates": ["L.L.", "OwO"], "history": [{"name": "Angell", "row": "^^ 0 0 vv 0"}]}
+//! ```
+//!
+//! It answers with a line with the name of the sinner to guess. An empty name,
+//! or a program closing its output, gives up the game.
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::{Arc, PoisonError, RwLock};
+
+use eyre::{eyre, Context};
+use facet::Facet;
+use ptndle_core::data::Sinner;
+use ptndle_core::error::Error;
+use ptndle_core::fuzzy::find_sinner;
+use ptndle_core::guess::Guess;
+use ptndle_core::matrix::{Candidates, GuessMatrix};
+use ptndle_core::play::{Player, PlayerFactory};
+use ptndle_core::registry::Registry;
+use ptndle_core::solver::SolverConfig;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+
+use crate::console::warning;
+use crate::settings;
+
+/// What a script is sent before each guess
+#[derive(Facet)]
+struct ScriptRequest {
+    candidates: Vec<String>,
+    history: Vec<ScriptGuess>,
+}
+
+#[derive(Facet)]
+struct ScriptGuess {
+    name: String,
+    row: String,
+}
+
+/// A Rhai script, compiled once when it's registered and run for every game
+struct RhaiScript {
+    engine: Engine,
+    ast: AST,
+}
+
+impl RhaiScript {
+    /// The name of the function that picks each guess
+    const GUESS: &str = "guess";
+    /// The most operations a guess can take, so that a script stuck in a loop
+    /// gives up the game instead of hanging
+    const MAX_OPERATIONS: u64 = 50_000_000;
+    /// How deep function calls can go, so that a script recursing forever
+    /// gives up the game instead of overflowing the stack
+    const MAX_CALL_LEVELS: usize = 64;
+
+    fn compile(path: &Path) -> eyre::Result<Self> {
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(Self::MAX_OPERATIONS)
+            .set_max_call_levels(Self::MAX_CALL_LEVELS);
+        let ast = engine.compile_file(path.to_owned()).map_err(|e| {
+            Error::InvalidInput(format!("Failed to compile {}: {e}", path.display()))
+        })?;
+        if !ast
+            .iter_functions()
+            .any(|x| x.name == Self::GUESS && x.params.len() == 2)
+        {
+            return Err(Error::InvalidInput(format!(
+                "{} doesn't define `fn {}(candidates, history)`",
+                path.display(),
+                Self::GUESS
+            ))
+            .into());
+        }
+        Ok(Self { engine, ast })
+    }
+
+    /// Calls the script's `guess` with `request`, returning the name it
+    /// returns, or an empty name if it returns nothing
+    fn ask(&self, request: &ScriptRequest) -> eyre::Result<String> {
+        let candidates = request
+            .candidates
+            .iter()
+            .map(|x| Dynamic::from(x.clone()))
+            .collect::<Array>();
+        let history = request
+            .history
+            .iter()
+            .map(|x| {
+                let mut guess = Map::new();
+                guess.insert("name".into(), x.name.clone().into());
+                guess.insert("row".into(), x.row.clone().into());
+                Dynamic::from_map(guess)
+            })
+            .collect::<Array>();
+        let answer = self
+            .engine
+            .call_fn::<Dynamic>(
+                &mut Scope::new(),
+                &self.ast,
+                Self::GUESS,
+                (candidates, history),
+            )
+            .map_err(|e| {
+                match *e {
+                    | EvalAltResult::ErrorTooManyOperations(_) => {
+                        eyre!(
+                            "It took more than {} operations to pick a guess, so it was stopped",
+                            Self::MAX_OPERATIONS
+                        )
+                    },
+                    | EvalAltResult::ErrorStackOverflow(_) => {
+                        eyre!(
+                            "It called functions more than {} deep, so it was stopped",
+                            Self::MAX_CALL_LEVELS
+                        )
+                    },
+                    | e => eyre!("{e}"),
+                }
+            })?;
+        if answer.is_unit() {
+            return Ok(String::new());
+        }
+        answer
+            .into_string()
+            .map(|x| x.trim().to_owned())
+            .map_err(|kind| eyre!("Expected the name of a sinner, but got a value of type {kind}"))
+    }
+}
+
+/// How a script is run
+#[derive(Clone)]
+enum Script {
+    /// A program, started for every game
+    Program(PathBuf),
+    Rhai(Arc<RhaiScript>),
+}
+
+/// A script running for a game
+enum Session {
+    Program(Process),
+    Rhai(Arc<RhaiScript>),
+}
+
+impl Session {
+    fn start(script: &Script) -> std::io::Result<Self> {
+        match script {
+            | Script::Program(path) => Process::spawn(path).map(Self::Program),
+            | Script::Rhai(script) => Ok(Self::Rhai(Arc::clone(script))),
+        }
+    }
+
+    /// Asks the script for the name of the next guess, which is empty if it
+    /// gave up
+    fn ask(&mut self, request: &ScriptRequest) -> eyre::Result<String> {
+        match self {
+            | Self::Program(process) => Ok(process.ask(request)?),
+            | Self::Rhai(script) => script.ask(request),
+        }
+    }
+}
+
+/// A running program and the pipes to talk to it
+struct Process {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Process {
+    fn spawn(path: &Path) -> std::io::Result<Self> {
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(std::io::Error::other("The script's pipes weren't opened"));
+        };
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+        })
+    }
+
+    /// Sends `request` and reads the line the script answers with
+    fn ask(&mut self, request: &ScriptRequest) -> std::io::Result<String> {
+        writeln!(self.stdin, "{}", facet_json::to_string(request))?;
+        self.stdin.flush()?;
+        let mut line = String::new();
+        self.stdout.read_line(&mut line)?;
+        Ok(line.trim().to_owned())
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        // The game is over, so the script isn't waited on to exit by itself
+        _ = self.child.kill();
+        _ = self.child.wait();
+    }
+}
+
+/// A [`Player`] that asks a script for each guess
+pub struct ScriptPlayer {
+    name: String,
+    candidates: Candidates,
+    history: Vec<ScriptGuess>,
+    /// The script, or `None` once it failed or gave up
+    session: Option<Session>,
+    /// The index of the sinner the script picked to guess next
+    guess: Option<usize>,
+}
+
+impl ScriptPlayer {
+    /// Starts `script` for a game with the sinners in `matrix`, and asks it
+    /// for the first guess. If the script can't be started, the player gives
+    /// up right away.
+    fn new(name: String, script: &Script, matrix: Arc<GuessMatrix>) -> Self {
+        let session = Session::start(script)
+            .inspect_err(|e| warning!("Failed to start the script of {name}: {e}"))
+            .ok();
+        let mut player = Self {
+            name,
+            candidates: Candidates::new(matrix),
+            history: Vec::new(),
+            session,
+            guess: None,
+        };
+        player.ask();
+        player
+    }
+
+    /// Asks the script for the next guess, giving up if it doesn't answer
+    /// with a sinner
+    fn ask(&mut self) {
+        let Some(session) = &mut self.session else {
+            return;
+        };
+        let request = ScriptRequest {
+            candidates: self.candidates.sinners().map(|x| x.name.clone()).collect(),
+            history: std::mem::take(&mut self.history),
+        };
+        let answer = session.ask(&request);
+        self.history = request.history;
+        let sinners = self.candidates.matrix().sinners();
+        self.guess = match answer {
+            | Ok(name) if name.is_empty() => None,
+            | Ok(name) => {
+                find_sinner(sinners, &name, settings::names())
+                    .inspect_err(|e| warning!("The script of {} answered: {e}", self.name))
+                    .ok()
+                    .and_then(|x| sinners.iter().position(|sinner| sinner.name == x.name))
+            },
+            | Err(e) => {
+                warning!("Failed to ask the script of {} for a guess: {e}", self.name);
+                None
+            },
+        };
+        if self.guess.is_none() {
+            self.session = None;
+        }
+    }
+}
+
+impl Player for ScriptPlayer {
+    fn update(&mut self, result: Guess, character: &Sinner) {
+        self.candidates.update(result, character);
+        self.history.push(ScriptGuess {
+            name: character.name.clone(),
+            row: result.to_ascii(),
+        });
+        self.ask();
+    }
+    fn next_guess(&self) -> Option<&Sinner> {
+        self.guess
+            .and_then(|x| self.candidates.matrix().sinners().get(x))
+    }
+    fn gave_up(&self) -> bool { self.guess.is_none() }
+}
+
+/// The scripts registered with [`register`], by the name of their player, in
+/// the order they were registered
+static SCRIPTS: RwLock<Vec<(String, Arc<Script>)>> = RwLock::new(Vec::new());
+
+/// Every built-in player, followed by a player for each script registered
+/// with [`register`]. The built-in players are created from `config`, as in
+/// [`Registry::builtin`].
+pub fn registry(config: SolverConfig) -> Registry<'static> {
+    let mut registry = Registry::builtin(config);
+    let scripts = SCRIPTS.read().unwrap_or_else(PoisonError::into_inner);
+    for (name, script) in scripts.iter() {
+        let (player, script) = (name.clone(), Arc::clone(script));
+        registry.register(PlayerFactory {
+            name: name.clone(),
+            create: Box::new(move |matrix| {
+                Box::new(ScriptPlayer::new(
+                    player.clone(),
+                    &script,
+                    Arc::clone(matrix),
+                ))
+            }),
+        });
+    }
+    registry
+}
+
+/// Registers the script at `path` as a player named after the file, without
+/// its extension, so that `my.rhai` is picked with `--strategy my`. A Rhai
+/// script is compiled right away, so that mistakes in it are reported before
+/// any game is played. A script named after a built-in player replaces it.
+pub fn register(path: &Path) -> eyre::Result<String> {
+    if !path.is_file() {
+        return Err(Error::InvalidInput(format!(
+            "The strategy script {} doesn't exist",
+            path.display()
+        ))
+        .into());
+    }
+    let name = path
+        .file_stem()
+        .and_then(|x| x.to_str())
+        .filter(|x| !x.is_empty())
+        .ok_or_else(|| {
+            Error::InvalidInput(format!("Can't name a player after {}", path.display()))
+        })?
+        .to_owned();
+    let script = if path.extension().is_some_and(|x| x == "rhai") {
+        Script::Rhai(Arc::new(RhaiScript::compile(path)?))
+    } else {
+        // A relative path without a folder, like `my.sh`, would be looked up
+        // on PATH instead of in the current folder
+        let path = std::fs::canonicalize(path)
+            .with_context(|| format!("Failed to find {}", path.display()))?;
+        Script::Program(path)
+    };
+    let mut scripts = SCRIPTS.write().unwrap_or_else(PoisonError::into_inner);
+    scripts.retain(|(x, _)| *x != name);
+    scripts.push((name.clone(), Arc::new(script)));
+    Ok(name)
+}
@@ -0,0 +1,90 @@
+//! The `selftest` command, for checking the invariants of the guess logic and
+//! the solvers on random sinners
+
+use eyre::eyre;
+use ptndle_core::error::Error;
+use ptndle_core::invariants::{self, Check, MAX_SINNERS};
+use ptndle_core::rng::Rng;
+use ptndle_core::theme::Role;
+
+use crate::console::status;
+use crate::flags::Selftest;
+use crate::output::{print_json, SelftestCheck, SelftestReport};
+use crate::settings;
+
+const DEFAULT_DATASETS: usize = 20;
+const DEFAULT_SINNERS: usize = 40;
+
+/// Runs every [`Check`], failing if any of them do
+pub fn run(
+    Selftest {
+        datasets,
+        sinners,
+        seed,
+    }: Selftest,
+    json: bool,
+) -> eyre::Result<()> {
+    let datasets = datasets.unwrap_or(DEFAULT_DATASETS);
+    let sinners = sinners.unwrap_or(DEFAULT_SINNERS);
+    if sinners > MAX_SINNERS {
+        return Err(Error::InvalidInput(format!(
+            "--sinners can be at most {MAX_SINNERS}, since the checks go over every pair of \
+             sinners"
+        ))
+        .into());
+    }
+    let seed = match seed {
+        | Some(seed) => seed,
+        | None => Rng::from_entropy()?.next_u64(),
+    };
+    status!("Checking {datasets} datasets of {sinners} sinners with seed {seed}");
+    let checks = invariants::run(seed, datasets, sinners, settings::thresholds())
+        .into_iter()
+        .map(|x| {
+            SelftestCheck {
+                name: x.check.to_str().to_owned(),
+                cases: x.cases,
+                failed: x.failed,
+                failures: x.failures,
+            }
+        })
+        .collect::<Vec<_>>();
+    let failed = checks.iter().filter(|x| x.failed > 0).count();
+    let report = SelftestReport {
+        seed,
+        datasets,
+        sinners,
+        checks,
+    };
+    if json {
+        print_json(&report);
+    } else {
+        let width = Check::ALL
+            .iter()
+            .map(|x| x.to_str().len())
+            .max()
+            .unwrap_or_default();
+        for (check, kind) in report.checks.iter().zip(Check::ALL) {
+            let outcome = if check.failed == 0 {
+                settings::paint(format!("{0} of {0} passed", check.cases), Role::Correct)
+            } else {
+                settings::paint(
+                    format!("{} of {} failed", check.failed, check.cases),
+                    Role::Wrong,
+                )
+            };
+            println!("{:width$}  {outcome}  ({})", check.name, kind.description());
+            for failure in &check.failures {
+                println!("    {failure}");
+            }
+        }
+    }
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "{failed} of {} checks failed with seed {seed}",
+            report.checks.len()
+        ))
+    }
+}
@@ -0,0 +1,284 @@
+//! A tiny HTTP server exposing the solver as a local JSON API
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use eyre::{eyre, Context};
+use facet::Facet;
+use ptndle_core::data::Sinner;
+use ptndle_core::matrix::GuessMatrix;
+use ptndle_core::play::NameAndGuess;
+use ptndle_core::solver::SolverConfig;
+use ptndle_core::tree::DecisionTree;
+
+use crate::console::warning;
+use crate::settings;
+use crate::shell::solve_report;
+
+/// The largest request accepted, headers included
+const MAX_REQUEST_SIZE: usize = 64 * 1024;
+/// The most headers a request may have
+const MAX_HEADERS: usize = 32;
+/// How long a client has to send its whole request, so a slow client can't
+/// hold up a connection for long
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long to wait for a client to take its response
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+/// The most connections handled at once, each on its own thread. More are
+/// turned away until one of them is done.
+const MAX_CONNECTIONS: usize = 32;
+
+/// The body of `POST /solve`
+#[derive(Debug, Facet)]
+struct SolveRequest {
+    /// The rows entered so far, as `name: row` like in `solve`, such as
+    /// `Hella: vv 0 0 ^ 0`
+    guesses: Vec<String>,
+    /// The number of top guesses to rank
+    #[facet(default)]
+    best: Option<usize>,
+}
+
+/// The body of an error response
+#[derive(Debug, Facet)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// A request that has been read from a connection
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+/// A response to send back: the status line and a JSON body
+struct Response {
+    status: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Self {
+        Self {
+            status: "200 OK",
+            body,
+        }
+    }
+    fn error(status: &'static str, error: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: facet_json::to_string(&ErrorResponse {
+                error: error.into(),
+            }),
+        }
+    }
+}
+
+/// The solver state shared by every request
+struct Server {
+    config: SolverConfig,
+    matrix: Arc<GuessMatrix>,
+    tree: Option<DecisionTree>,
+    /// The response to `GET /sinners`, which never changes
+    sinners_json: String,
+}
+
+impl Server {
+    fn route(&self, request: &Request) -> Response {
+        match (request.method.as_str(), request.path.as_str()) {
+            | ("GET", "/sinners") => Response::ok(self.sinners_json.clone()),
+            | ("POST", "/solve") => self.solve(&request.body),
+            | (_, "/sinners" | "/solve") => {
+                Response::error("405 Method Not Allowed", "Method not allowed")
+            },
+            | (_, path) => Response::error("404 Not Found", format!("No endpoint at {path}")),
+        }
+    }
+
+    fn solve(&self, body: &[u8]) -> Response {
+        let Ok(body) = std::str::from_utf8(body) else {
+            return Response::error("400 Bad Request", "The body isn't UTF-8");
+        };
+        let request = match facet_json::from_str::<SolveRequest>(body) {
+            | Ok(request) => request,
+            | Err(e) => {
+                // The message is colored for terminals, which is no use to a client
+                let message = strip_ansi_escapes::strip_str(e.message().to_string());
+                return Response::error("400 Bad Request", format!("Invalid body: {message}"));
+            },
+        };
+        let rows = match request
+            .guesses
+            .iter()
+            .map(|x| x.parse::<NameAndGuess>())
+            .collect::<Result<Vec<_>, _>>()
+        {
+            | Ok(rows) => rows,
+            | Err(e) => return Response::error("400 Bad Request", e.to_string()),
+        };
+        match solve_report(
+            &rows,
+            &self.matrix,
+            self.tree.as_ref(),
+            self.config,
+            request.best,
+        ) {
+            | Ok(report) => Response::ok(facet_json::to_string(&report)),
+            | Err(e) => Response::error("400 Bad Request", e.to_string()),
+        }
+    }
+}
+
+/// Reads a request from `stream` that has been sent in full by `deadline`, or
+/// returns the response to send if the request is malformed or late
+fn read_request(stream: &TcpStream, deadline: Instant) -> Result<Request, Response> {
+    let too_large = || Response::error("413 Payload Too Large", "The request is too large");
+    let bad_request = |e: &dyn std::fmt::Display| Response::error("400 Bad Request", e.to_string());
+    let timed_out = || Response::error("408 Request Timeout", "The request took too long to send");
+    let mut reader = stream.take(MAX_REQUEST_SIZE as u64);
+    // Each read only waits until the deadline, so a client sending a byte at a
+    // time can't keep the connection open
+    let mut read_chunk = |chunk: &mut [u8]| {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(timed_out());
+        }
+        reader
+            .get_ref()
+            .set_read_timeout(Some(left))
+            .map_err(|e| bad_request(&e))?;
+        reader.read(chunk).map_err(|e| {
+            match e.kind() {
+                | ErrorKind::WouldBlock | ErrorKind::TimedOut => timed_out(),
+                | _ => bad_request(&e),
+            }
+        })
+    };
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 4096];
+    loop {
+        let read = read_chunk(&mut chunk)?;
+        if read == 0 {
+            return Err(if buffer.len() >= MAX_REQUEST_SIZE {
+                too_large()
+            } else {
+                bad_request(&"The request ended early")
+            });
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        let mut request = httparse::Request::new(&mut headers);
+        let httparse::Status::Complete(header_len) =
+            request.parse(&buffer).map_err(|e| bad_request(&e))?
+        else {
+            continue;
+        };
+        let content_length = request
+            .headers
+            .iter()
+            .find(|x| x.name.eq_ignore_ascii_case("content-length"))
+            .map(|x| {
+                std::str::from_utf8(x.value)
+                    .ok()
+                    .and_then(|x| x.trim().parse::<usize>().ok())
+                    .ok_or_else(|| bad_request(&"Invalid Content-Length"))
+            })
+            .transpose()?
+            .unwrap_or(0);
+        // A huge Content-Length would overflow the sum
+        if content_length
+            .checked_add(header_len)
+            .is_none_or(|x| x > MAX_REQUEST_SIZE)
+        {
+            return Err(too_large());
+        }
+        let method = request.method.unwrap_or_default().to_owned();
+        let path = request.path.unwrap_or_default().to_owned();
+        let mut body = buffer.split_off(header_len);
+        while body.len() < content_length {
+            let body_read = read_chunk(&mut chunk)?;
+            if body_read == 0 {
+                return Err(bad_request(&"The body ended early"));
+            }
+            body.extend_from_slice(&chunk[..body_read]);
+        }
+        body.truncate(content_length);
+        return Ok(Request { method, path, body });
+    }
+}
+
+fn write_response(mut stream: &TcpStream, response: &Response) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: \
+         close\r\n\r\n{}",
+        response.status,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+/// Sends `response`, warning if the client doesn't take it in time
+fn respond(stream: &TcpStream, response: &Response) {
+    let written = stream
+        .set_write_timeout(Some(WRITE_TIMEOUT))
+        .and_then(|()| write_response(stream, response));
+    if let Err(e) = written {
+        warning!("Failed to respond: {e}");
+    }
+}
+
+/// Reads the request on `stream` and answers it
+fn handle(server: &Server, stream: &TcpStream) {
+    let response = match read_request(stream, Instant::now() + REQUEST_TIMEOUT) {
+        | Ok(request) => server.route(&request),
+        | Err(response) => response,
+    };
+    respond(stream, &response);
+}
+
+/// Serves the solver on `localhost:port` until the process is stopped
+pub fn serve(port: u16, sinners: Vec<Sinner>, config: SolverConfig) -> eyre::Result<()> {
+    let sinners_json = facet_json::to_string(&sinners);
+    let matrix = Arc::new(GuessMatrix::new(sinners, *settings::thresholds()));
+    let tree = DecisionTree::load_or_build(&settings::context(), config, &matrix)?;
+    let server = Server {
+        config,
+        matrix,
+        tree,
+        sinners_json,
+    };
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .with_context(|| format!("Failed to listen on port {port}"))?;
+    println!("Listening on http://localhost:{port}");
+    let connections = AtomicUsize::new(0);
+    let (server, connections) = (&server, &connections);
+    std::thread::scope(|scope| {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                | Ok(stream) => stream,
+                | Err(e) => {
+                    warning!("Failed to accept a connection: {e}");
+                    continue;
+                },
+            };
+            if connections.fetch_add(1, Ordering::Relaxed) >= MAX_CONNECTIONS {
+                connections.fetch_sub(1, Ordering::Relaxed);
+                let busy = Response::error("503 Service Unavailable", "Too many connections");
+                respond(&stream, &busy);
+                continue;
+            }
+            scope.spawn(move || {
+                handle(server, &stream);
+                connections.fetch_sub(1, Ordering::Relaxed);
+            });
+        }
+    });
+    Err(eyre!("The server stopped listening"))
+}
@@ -0,0 +1,111 @@
+//! Settings chosen once on the command line and read throughout the binary.
+//! The library takes each of them as an explicit argument instead, most of
+//! them through the [`Context`] from [`context`].
+
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use owo_colors::Styled;
+use ptndle_core::aliases::Aliases;
+use ptndle_core::compare::ThresholdConfig;
+use ptndle_core::context::Context;
+use ptndle_core::fuzzy::Names;
+use ptndle_core::game::GameProfile;
+use ptndle_core::i18n::{Lang, Translations};
+use ptndle_core::paths::{self, Profile};
+use ptndle_core::solver::TieBreak;
+use ptndle_core::theme::{Role, Theme};
+
+use crate::console::{console, warning};
+
+static THRESHOLDS: OnceLock<ThresholdConfig> = OnceLock::new();
+static TIE_BREAK: OnceLock<TieBreak> = OnceLock::new();
+static GAME: OnceLock<GameProfile> = OnceLock::new();
+static PROFILE: OnceLock<Profile> = OnceLock::new();
+static NAMES: OnceLock<Names> = OnceLock::new();
+static LANG: OnceLock<Lang> = OnceLock::new();
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Sets the formulas returned by [`thresholds`]. Only the first call has an
+/// effect.
+pub fn install_thresholds(config: ThresholdConfig) { _ = THRESHOLDS.set(config); }
+
+/// The installed threshold formulas, or the defaults if none were installed
+pub fn thresholds() -> &'static ThresholdConfig { THRESHOLDS.get_or_init(ThresholdConfig::default) }
+
+/// Sets the rule returned by [`tie_break`]. Only the first call has an effect.
+pub fn install_tie_break(tie_break: TieBreak) { _ = TIE_BREAK.set(tie_break); }
+
+/// The installed tie-break, or the default if none was installed
+pub fn tie_break() -> TieBreak { *TIE_BREAK.get_or_init(TieBreak::default) }
+
+/// Plays `game` from now on. Only the first call has an effect.
+pub fn install_game(game: GameProfile) { _ = GAME.set(game); }
+
+/// The installed game, or the built-in one if none was installed
+pub fn game() -> &'static GameProfile { GAME.get_or_init(GameProfile::builtin) }
+
+/// Uses the files of `profile` from now on. Only the first call has an
+/// effect.
+pub fn install_profile(profile: Profile) { _ = PROFILE.set(profile); }
+
+/// The installed profile, or `None` for the files shared by everyone
+pub fn profile() -> Option<&'static Profile> { PROFILE.get() }
+
+/// Shows text in `lang` from now on. Only the first call has an effect.
+pub fn install_lang(lang: Lang) { _ = LANG.set(lang); }
+
+/// The installed language, or English if none was installed
+pub fn lang() -> Lang { LANG.get().copied().unwrap_or_default() }
+
+/// Styles text with `theme` from now on. Only the first call has an effect.
+pub fn install_theme(theme: Theme) { _ = THEME.set(theme); }
+
+/// The installed theme, or the one from [`Theme::from_env`] if none was
+/// installed
+pub fn theme() -> &'static Theme { THEME.get_or_init(Theme::from_env) }
+
+/// Styles `text` for `role` with the installed [`theme`]
+pub fn paint<T: Display>(text: T, role: Role) -> Styled<T> { theme().paint(text, role) }
+
+/// Whether the installed [`theme`] styles anything, for output that can't be
+/// styled with [`paint`]
+pub fn styled() -> bool { !theme().is_plain() }
+
+/// The user's aliases and translated names, read the first time they are
+/// needed. Files that can't be read are warned about and left out.
+pub fn names() -> &'static Names {
+    NAMES.get_or_init(|| {
+        Names {
+            aliases: Aliases::load().unwrap_or_else(|e| {
+                warning!("Could not load aliases: {e}");
+                Aliases::default()
+            }),
+            translations: Translations::load().unwrap_or_else(|e| {
+                warning!("Could not load translated names: {e}");
+                Translations::default()
+            }),
+        }
+    })
+}
+
+/// The directory the installed game's sinner data is cached in
+pub fn cache_dir() -> PathBuf { paths::cache_dir(game()) }
+
+/// The directory the installed profile's stats, history and saved games are
+/// kept in
+pub fn data_dir() -> PathBuf { paths::data_dir(game(), profile()) }
+
+/// The installed settings, for the library functions that need them, reporting
+/// to the [`console`]
+pub fn context() -> Context<'static> {
+    Context {
+        game: game(),
+        profile: profile(),
+        names: names(),
+        lang: lang(),
+        theme: *theme(),
+        reporter: console(),
+    }
+}
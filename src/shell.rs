@@ -0,0 +1,1502 @@
+//! The interactive shells for `play` and `solve`, and the reports they print
+
+use std::cell::{self, RefCell};
+use std::fmt::Write as _;
+use std::io::{stdin, stdout, IsTerminal, Write};
+use std::sync::Arc;
+use std::time::Instant;
+
+use eyre::eyre;
+use ptndle_core::attribute::{Attribute, Cell};
+use ptndle_core::checkpoint::Checkpoint;
+use ptndle_core::data::Sinner;
+use ptndle_core::error::Error;
+use ptndle_core::fuzzy::find_sinner;
+use ptndle_core::guess::Guess;
+use ptndle_core::i18n::{message, Message};
+use ptndle_core::matrix::{Candidates, GuessMatrix};
+use ptndle_core::play::{GatherResults, NameAndGuess, NameAndGuessError, Player, PlayerFactory};
+use ptndle_core::solver::{rank_candidates, rank_guesses, OptimalPlayer, Solver, SolverConfig,
+                          Strategy};
+use ptndle_core::theme::Role;
+use ptndle_core::tree::DecisionTree;
+use reedline::{DefaultPrompt, ExampleHighlighter, Reedline, Signal};
+
+use crate::chart::{print_bars, terminal_width, Bar};
+use crate::console::{banner, verbose};
+use crate::flags::Format;
+use crate::output::{print_json, GatherReport, PartitionReport, RankedGuessReport, SolveReport};
+use crate::prompt::{completing_editor, read_line, save_history, sinner_names, with_history};
+use crate::settings;
+use crate::sinners::{print_profile, row, HEADERS};
+use crate::stats::format_time;
+
+/// The number of guesses explained when `--best` isn't given
+const EXPLAINED_GUESSES: usize = 3;
+
+/// The number of sinners listed for each result when explaining a guess
+const LISTED_SINNERS: usize = 5;
+
+/// Run with the sinners guessed so far
+type GuessedHook = dyn Fn(&[Sinner]);
+
+/// A [`Player`] connected to the terminal
+pub struct HumanPlayer {
+    line_editor: RefCell<Reedline>,
+    choices: Vec<Sinner>,
+    /// Follows the guesses made so far to give hints, with one for each
+    /// target
+    hinters: Vec<OptimalPlayer>,
+    /// Whether each target has been found
+    found: Vec<bool>,
+    /// When the game started, if the time is shown in the prompt
+    started: Option<Instant>,
+    /// Run before exiting when `quit` is entered
+    on_quit: Option<Box<dyn Fn()>>,
+    /// Run after each guess that didn't win
+    on_guess: Option<Box<GuessedHook>>,
+    /// Run before exiting when Ctrl-C or Ctrl-D is pressed
+    on_abort: Option<Box<GuessedHook>>,
+    /// The guesses of a resumed game as indices into `choices`, made again
+    /// before asking for more
+    resumed: Vec<usize>,
+    /// The number of guesses in `resumed` made so far
+    replayed: cell::Cell<usize>,
+    /// The solver's game when racing it, shown a guess at a time
+    race: Option<Vec<RaceStep>>,
+    /// The number of guesses made that didn't win
+    guesses: usize,
+    /// Whether `giveup` was entered
+    gave_up: cell::Cell<bool>,
+    /// Whether `giveup full` was entered, to show how the solver would have
+    /// found the targets
+    show_line: cell::Cell<bool>,
+    /// Every guess that didn't win, with its result for each target
+    history: Vec<(Sinner, Vec<Option<Guess>>)>,
+    /// Whether guesses that were ruled out are refused instead of confirmed
+    strict: bool,
+    /// Whether the values of the sinner guessed are shown with each result
+    show_values: bool,
+}
+impl HumanPlayer {
+    /// Creates a player for a game with `targets` targets
+    pub fn new(choices: Vec<Sinner>, targets: usize) -> Self {
+        let names = sinner_names(&choices).collect::<Vec<_>>();
+        let commands = names
+            .iter()
+            .map(|x| format!("info {x}"))
+            .chain(names.iter().map(|x| format!("guess {x}")))
+            .chain(names.iter().map(|x| format!("compare {x}")))
+            .chain(
+                [
+                    "hint",
+                    "hint full",
+                    "list",
+                    "board",
+                    "giveup",
+                    "giveup full",
+                    "reveal",
+                    "reveal full",
+                    "quit",
+                ]
+                .map(str::to_owned),
+            )
+            .collect();
+        let highlighter = Box::new(ExampleHighlighter::new(vec![
+            "info".to_owned(),
+            "guess".to_owned(),
+            "compare".to_owned(),
+            "hint".to_owned(),
+            "list".to_owned(),
+            "board".to_owned(),
+            "giveup".to_owned(),
+            "reveal".to_owned(),
+            "quit".to_owned(),
+        ]));
+        let line_editor = RefCell::new(with_history(
+            completing_editor(commands).with_highlighter(highlighter),
+            "play_history.txt",
+        ));
+        let matrix = Arc::new(GuessMatrix::new(choices.clone(), *settings::thresholds()));
+        Self {
+            line_editor,
+            hinters: (0..targets)
+                .map(|_| OptimalPlayer::new(Arc::clone(&matrix), settings::tie_break()))
+                .collect(),
+            found: vec![false; targets],
+            choices,
+            started: None,
+            on_quit: None,
+            on_guess: None,
+            on_abort: None,
+            resumed: Vec::new(),
+            replayed: cell::Cell::new(0),
+            race: None,
+            guesses: 0,
+            gave_up: cell::Cell::new(false),
+            show_line: cell::Cell::new(false),
+            history: Vec::new(),
+            strict: false,
+            show_values: false,
+        }
+    }
+    /// Runs `f` before exiting when `quit` is entered
+    #[must_use]
+    pub fn on_quit(mut self, f: impl Fn() + 'static) -> Self {
+        self.on_quit = Some(Box::new(f));
+        self
+    }
+    /// Runs `f` with the sinners guessed so far after each guess that didn't
+    /// win
+    #[must_use]
+    pub fn on_guess(mut self, f: impl Fn(&[Sinner]) + 'static) -> Self {
+        self.on_guess = Some(Box::new(f));
+        self
+    }
+    /// Runs `f` with the sinners guessed so far before exiting when Ctrl-C or
+    /// Ctrl-D is pressed
+    #[must_use]
+    pub fn on_abort(mut self, f: impl Fn(&[Sinner]) + 'static) -> Self {
+        self.on_abort = Some(Box::new(f));
+        self
+    }
+    /// Guesses the sinners named in `guessed` again before asking for more
+    /// guesses, to resume a saved game
+    #[must_use]
+    pub fn resuming(mut self, guessed: &[String]) -> Self {
+        self.resumed = guessed
+            .iter()
+            .filter_map(|name| self.choices.iter().position(|x| x.name == *name))
+            .collect();
+        self
+    }
+    /// Shows the time since now in the prompt, for timed games
+    #[must_use]
+    pub fn timed(mut self) -> Self {
+        self.started = Some(Instant::now());
+        self
+    }
+    /// Refuses guesses that earlier results ruled out
+    #[must_use]
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+    /// Shows the values of the sinner guessed with each result
+    #[must_use]
+    pub fn show_values(mut self) -> Self {
+        self.show_values = true;
+        self
+    }
+    /// Races the solver's game in `race`, showing its guess after each guess
+    #[must_use]
+    pub fn racing(mut self, race: Vec<RaceStep>) -> Self {
+        self.race = Some(race);
+        self
+    }
+    /// Prints the number of sinners that could still be each target that
+    /// hasn't been found, and the best next guess for it if `full` is set
+    fn hint(&self, full: bool) {
+        for (index, hinter) in self.hinters.iter().enumerate() {
+            if self.found[index] {
+                continue;
+            }
+            let remaining = hinter.candidates().len();
+            if self.hinters.len() > 1 {
+                print!("Target {}: ", index + 1);
+            }
+            println!("{remaining} possible sinners remain");
+            if full {
+                if let Some(best) = hinter.next_guess() {
+                    println!("The best guess is {}", best.name);
+                }
+            }
+        }
+    }
+    /// Prints every sinner that could still be each target that hasn't been
+    /// found, in columns
+    fn list(&self) {
+        for (index, hinter) in self.hinters.iter().enumerate() {
+            if self.found[index] {
+                continue;
+            }
+            let mut names = hinter
+                .candidates()
+                .sinners()
+                .map(|x| x.name.as_str())
+                .collect::<Vec<_>>();
+            names.sort_unstable();
+            if self.hinters.len() > 1 {
+                print!("Target {}: ", index + 1);
+            }
+            println!("{} possible sinners", names.len());
+            print_columns(&names);
+        }
+    }
+    /// Prints every guess that didn't win as a table, with the guessed
+    /// sinner's value and the result for each attribute. With several targets,
+    /// there is a table for each target up to the guess that found it.
+    fn board(&self) {
+        if self.history.is_empty() {
+            println!("No guesses have been made yet");
+            return;
+        }
+        let rows = self
+            .history
+            .iter()
+            .map(|(sinner, _)| row(sinner))
+            .collect::<Vec<_>>();
+        // Each value but the name is followed by a space and a two character
+        // result
+        let mut widths = HEADERS.map(str::len);
+        for row in &rows {
+            for (index, (width, cell)) in widths.iter_mut().zip(row).enumerate() {
+                let result = if index == 0 { 0 } else { 3 };
+                *width = (*width).max(cell.chars().count() + result);
+            }
+        }
+        let mut header = "Guess".to_owned();
+        for (cell, width) in HEADERS.iter().zip(widths) {
+            _ = write!(header, "  {cell:width$}");
+        }
+        for index in 0..self.found.len() {
+            if self.found.len() > 1 {
+                println!("Target {}:", index + 1);
+            }
+            println!("{}", settings::paint(header.trim_end(), Role::Bold));
+            for (number, (cells, (_, results))) in rows.iter().zip(&self.history).enumerate() {
+                let mut line = format!("{:>5}  {:width$}", number + 1, cells[0], width = widths[0]);
+                let Some(result) = results[index] else {
+                    println!("{}  found", line.trim_end());
+                    break;
+                };
+                let columns = cells
+                    .iter()
+                    .zip(widths)
+                    .skip(1)
+                    .zip(result.cells(settings::theme()));
+                for ((value, width), mark) in columns {
+                    _ = write!(line, "  {value:width$} {mark}", width = width - 3);
+                }
+                println!("{line}");
+            }
+        }
+    }
+    /// Finds the two sinners named in `arg`, which are separated by a comma or
+    /// a space
+    fn find_pair(&self, arg: &str) -> Result<(&Sinner, &Sinner), String> {
+        if let Some((a, b)) = arg.split_once(',') {
+            let a = find_sinner(&self.choices, a.trim(), settings::names())
+                .map_err(|e| e.to_string())?;
+            let b = find_sinner(&self.choices, b.trim(), settings::names())
+                .map_err(|e| e.to_string())?;
+            return Ok((a, b));
+        }
+        // Names can have spaces in them, so try every space until both halves
+        // are names
+        arg.match_indices(' ')
+            .find_map(|(index, _)| {
+                let (a, b) = arg.split_at_checked(index)?;
+                let a = find_sinner(&self.choices, a.trim(), settings::names()).ok()?;
+                let b = find_sinner(&self.choices, b.trim(), settings::names()).ok()?;
+                Some((a, b))
+            })
+            .ok_or_else(|| {
+                format!(
+                    "Expected two sinners, like `compare Hella Che` or `compare Countess Chelsea, \
+                     Che`. Found `{arg}`"
+                )
+            })
+    }
+    /// Prints the result of guessing each of the two sinners named in `arg`
+    /// with the other as the target
+    fn compare(&self, arg: &str) {
+        let (a, b) = match self.find_pair(arg) {
+            | Ok(pair) => pair,
+            | Err(e) => {
+                eprintln!("{e}");
+                return;
+            },
+        };
+        if a == b {
+            println!("{} is the same sinner", a.name);
+            return;
+        }
+        for (guess, target) in [(a, b), (b, a)] {
+            println!(
+                "Guessing {} with {} as the target:",
+                guess.name, target.name
+            );
+            let result = target.guess(guess, settings::thresholds());
+            println!("{}", result.paint(settings::theme()));
+        }
+    }
+    /// Describes the earlier guesses that ruled out `sinner` as each target
+    /// that hasn't been found, or `None` if it could still be one of them
+    fn ruled_out_by(&self, sinner: &Sinner) -> Option<String> {
+        let mut reasons = Vec::new();
+        for (index, found) in self.found.iter().enumerate() {
+            if *found {
+                continue;
+            }
+            // A sinner that was guessed without winning can't be the target
+            let by = self.history.iter().position(|(guessed, results)| {
+                results[index].is_some_and(|result| {
+                    guessed == sinner || sinner.guess(guessed, settings::thresholds()) != result
+                })
+            })?;
+            reasons.push(if self.found.len() > 1 {
+                format!("guess {} for target {}", by + 1, index + 1)
+            } else {
+                format!("guess {}", by + 1)
+            });
+        }
+        Some(format!(
+            "{} was already ruled out by {}",
+            sinner.name,
+            reasons.join(" and ")
+        ))
+    }
+    /// Asks whether to make a guess that was ruled out, which defaults to no
+    fn confirm(&self) -> bool {
+        let answer = read_line(&mut self.line_editor.borrow_mut(), "Guess anyway? [y/N]");
+        let answer = answer.ok().flatten().unwrap_or_default().to_lowercase();
+        answer == "y" || answer == "yes"
+    }
+    /// Prints the profile of each target that wasn't found after giving up,
+    /// and the guesses the solver would have found it with from where the game
+    /// stopped if `giveup full` was entered
+    pub fn reveal(&self, targets: &[&Sinner]) {
+        for (index, target) in targets.iter().enumerate() {
+            if self.found[index] {
+                continue;
+            }
+            print_profile(target);
+            if self.show_line.get() {
+                let line = optimal_line(self.hinters[index].clone(), target)
+                    .into_iter()
+                    .map(|x| x.guess)
+                    .collect::<Vec<_>>();
+                let plural = if line.len() == 1 { "guess" } else { "guesses" };
+                println!(
+                    "From here, the solver would have found {} in {} {plural}: {}",
+                    target.name,
+                    line.len(),
+                    line.join(", ")
+                );
+            }
+            println!();
+        }
+    }
+    /// Prints the sinners that could still be the target followed by `hinter`
+    fn print_remaining(hinter: &OptimalPlayer) {
+        let candidates = hinter.candidates();
+        println!(
+            "{}",
+            message(settings::lang(), Message::Remaining, &[&candidates.len()])
+        );
+        if candidates.len() <= 5 {
+            let names = candidates.sinners().map(|x| x.name.as_str());
+            println!("    {}", names.collect::<Vec<_>>().join(", "));
+        }
+    }
+
+    /// The next guess of a resumed game that hasn't been made again yet
+    fn replay(&self) -> Option<&Sinner> {
+        let replayed = self.replayed.get();
+        let &index = self.resumed.get(replayed)?;
+        self.replayed.set(replayed + 1);
+        Some(&self.choices[index])
+    }
+
+    /// The sinners guessed so far that didn't win
+    fn guessed(&self) -> Vec<Sinner> { self.history.iter().map(|(x, _)| x.clone()).collect() }
+
+    /// Exits after Ctrl-C or Ctrl-D, running `on_abort` first
+    fn abort(&self) -> ! {
+        eprintln!("Aborted!");
+        if let Some(on_abort) = &self.on_abort {
+            on_abort(&self.guessed());
+        }
+        std::process::exit(1);
+    }
+}
+impl Player for HumanPlayer {
+    fn next_guess(&self) -> Option<&Sinner> {
+        if let Some(guess) = self.replay() {
+            return Some(guess);
+        }
+        loop {
+            let signal = {
+                let mut line_editor = self.line_editor.borrow_mut();
+                let right = self
+                    .started
+                    .map_or_else(|| "Hella yeah!".to_owned(), |x| format_time(x.elapsed()));
+                let signal = line_editor.read_line(&DefaultPrompt::new(
+                    reedline::DefaultPromptSegment::Basic("ptndle >>".to_owned()),
+                    reedline::DefaultPromptSegment::Basic(right),
+                ));
+                // `quit` exits without dropping the editor, which is when it would save
+                save_history(&mut line_editor);
+                signal
+            };
+            match signal {
+                | Ok(Signal::Success(buffer)) => {
+                    let buffer = buffer.trim();
+                    match buffer {
+                        | "quit" => {
+                            if let Some(on_quit) = &self.on_quit {
+                                on_quit();
+                            }
+                            std::process::exit(0)
+                        },
+                        | "hint" => {
+                            self.hint(false);
+                            continue;
+                        },
+                        | "hint full" => {
+                            self.hint(true);
+                            continue;
+                        },
+                        | "list" => {
+                            self.list();
+                            continue;
+                        },
+                        | "board" => {
+                            self.board();
+                            continue;
+                        },
+                        | "giveup" | "reveal" => {
+                            self.gave_up.set(true);
+                            break None;
+                        },
+                        | "giveup full" | "reveal full" => {
+                            self.gave_up.set(true);
+                            self.show_line.set(true);
+                            break None;
+                        },
+                        | _ => {},
+                    }
+                    let Some((cmd, arg)) = buffer.split_once(' ') else {
+                        eprintln!("Unknown command: `{buffer}`");
+                        continue;
+                    };
+
+                    match cmd {
+                        | "info" => {
+                            let sinner = match find_sinner(&self.choices, arg, settings::names()) {
+                                | Ok(sinner) => sinner,
+                                | Err(e) => {
+                                    eprintln!("{e}");
+                                    continue;
+                                },
+                            };
+                            print_profile(sinner);
+                        },
+                        | "compare" => self.compare(arg),
+                        | "guess" => {
+                            match find_sinner(&self.choices, arg, settings::names()) {
+                                | Ok(to_play) => {
+                                    let Some(note) = self.ruled_out_by(to_play) else {
+                                        break Some(to_play);
+                                    };
+                                    println!("Note: {note}");
+                                    if self.strict {
+                                        eprintln!(
+                                            "Guesses that were ruled out aren't allowed with \
+                                             --strict"
+                                        );
+                                    } else if self.confirm() {
+                                        break Some(to_play);
+                                    }
+                                },
+                                | Err(e) => eprintln!("{e}"),
+                            }
+                        },
+                        | _ => {
+                            eprintln!("Unknown command: `{cmd}`");
+                        },
+                    }
+                },
+                | Ok(Signal::CtrlC | Signal::CtrlD) => self.abort(),
+                | Err(e) => {
+                    eprintln!("Failed to read line of input: {e}");
+                    std::process::exit(1);
+                },
+            }
+        }
+    }
+    fn gave_up(&self) -> bool { self.gave_up.get() }
+    fn update(&mut self, result: Guess, character: &Sinner) {
+        if self.show_values {
+            println!("{}", values_line(character, result));
+        }
+        self.history.push((character.clone(), vec![Some(result)]));
+        if let Some(on_guess) = &self.on_guess {
+            on_guess(&self.guessed());
+        }
+        self.hinters[0].update(result, character);
+        Self::print_remaining(&self.hinters[0]);
+        self.guesses += 1;
+        match self.race.as_ref().and_then(|x| x.get(self.guesses - 1)) {
+            | Some(step) if step.remaining == 0 => {
+                println!("The solver found the target on guess {}!", self.guesses);
+            },
+            | Some(step) => {
+                println!(
+                    "The solver guessed {} and has {} sinners left",
+                    step.guess, step.remaining
+                );
+            },
+            | None => {},
+        }
+    }
+    fn update_targets(&mut self, results: &[Option<Guess>], character: &Sinner) {
+        self.history.push((character.clone(), results.to_vec()));
+        for (index, result) in results.iter().enumerate() {
+            let Some(result) = result else {
+                self.found[index] = true;
+                continue;
+            };
+            if self.show_values {
+                println!("{}: {}", index + 1, values_line(character, *result));
+            }
+            self.hinters[index].update(*result, character);
+            print!("Target {}: ", index + 1);
+            Self::print_remaining(&self.hinters[index]);
+        }
+    }
+}
+
+/// The value of each attribute of `sinner` followed by its result, like
+/// `Height 172cm  ↑`
+fn values_line(sinner: &Sinner, result: Guess) -> String {
+    let values = row(sinner);
+    let mut line = String::new();
+    for ((header, value), cell) in HEADERS
+        .iter()
+        .zip(values)
+        .skip(1)
+        .zip(result.cells(settings::theme()))
+    {
+        if !line.is_empty() {
+            line.push_str(" | ");
+        }
+        _ = write!(line, "{header} {value} {cell}");
+    }
+    line
+}
+
+/// Prints `items` in as many columns as fit in the terminal, filling each
+/// column from top to bottom
+fn print_columns(items: &[&str]) {
+    let longest = items.iter().map(|x| x.chars().count()).max();
+    let width = longest.unwrap_or_default() + 2;
+    // The columns are indented by 4
+    let rows = items
+        .len()
+        .div_ceil((terminal_width().saturating_sub(4) / width).max(1));
+    for row in 0..rows {
+        let mut line = String::new();
+        for item in items.iter().skip(row).step_by(rows) {
+            _ = write!(line, "{item:width$}");
+        }
+        println!("    {}", line.trim_end());
+    }
+}
+
+/// A guess the solver made in its own game in `play --race`
+#[derive(Debug, Clone)]
+pub struct RaceStep {
+    pub guess: String,
+    /// The number of sinners the solver had left after the guess, which is 0
+    /// once it found the target
+    pub remaining: usize,
+}
+
+/// Plays a game guessing `target` with the [`OptimalPlayer`], to race against
+pub fn solver_race(matrix: &Arc<GuessMatrix>, target: &Sinner) -> Vec<RaceStep> {
+    optimal_line(
+        OptimalPlayer::new(Arc::clone(matrix), settings::tie_break()),
+        target,
+    )
+}
+
+/// Plays the rest of a game guessing `target` with `solver`, returning each
+/// guess it makes
+fn optimal_line(mut solver: OptimalPlayer, target: &Sinner) -> Vec<RaceStep> {
+    let sinners = solver.candidates().matrix().len();
+    let mut steps = Vec::new();
+    // Every guess that doesn't win rules out at least the sinner guessed
+    while steps.len() < sinners {
+        let Some(guess) = solver.next_guess().cloned() else {
+            break;
+        };
+        if guess == *target {
+            steps.push(RaceStep {
+                guess: guess.name,
+                remaining: 0,
+            });
+            break;
+        }
+        let thresholds = solver.candidates().matrix().thresholds();
+        solver.update(target.guess(&guess, thresholds), &guess);
+        steps.push(RaceStep {
+            guess: guess.name,
+            remaining: solver.candidates().len(),
+        });
+    }
+    steps
+}
+
+/// Prints the guesses of a race against the solver side by side, with the
+/// number of sinners each had left, and who won. `guessed` has the guesses that
+/// didn't win, and `won_in` is the number of guesses the game took.
+pub fn print_race(
+    matrix: &Arc<GuessMatrix>,
+    target: &Sinner,
+    guessed: &[(String, Guess)],
+    won_in: Option<u8>,
+    race: &[RaceStep],
+) {
+    let mut candidates = Candidates::new(Arc::clone(matrix));
+    let mut rows = Vec::with_capacity(guessed.len() + 1);
+    for (name, result) in guessed {
+        let Some(sinner) = matrix.sinners().iter().find(|x| x.name == *name) else {
+            break;
+        };
+        candidates.update(*result, sinner);
+        rows.push((name.as_str(), candidates.len()));
+    }
+    if won_in.is_some() {
+        rows.push((target.name.as_str(), 0));
+    }
+    let left = |remaining: usize| {
+        if remaining == 0 {
+            "found".to_owned()
+        } else {
+            remaining.to_string()
+        }
+    };
+    let width = rows
+        .iter()
+        .map(|x| x.0.len())
+        .chain(std::iter::once("You".len()))
+        .max()
+        .unwrap_or_default();
+    println!("======== Race ========");
+    println!("Guess  {:width$}   Left  Solver", "You");
+    for i in 0..rows.len().max(race.len()) {
+        let (name, remaining) = rows.get(i).map_or((String::new(), String::new()), |x| {
+            (x.0.to_owned(), left(x.1))
+        });
+        let solver = race.get(i).map_or_else(String::new, |x| {
+            format!("{} ({})", x.guess, left(x.remaining))
+        });
+        let row = format!("{:>5}  {name:width$}  {remaining:>5}  {solver}", i + 1);
+        println!("{}", row.trim_end());
+    }
+    let plural = |n: usize| if n == 1 { "guess" } else { "guesses" };
+    let solver = race.len();
+    match won_in.map(usize::from) {
+        | Some(yours) if yours < solver => {
+            let by = solver - yours;
+            println!("You beat the solver by {by} {}!", plural(by));
+        },
+        | Some(yours) if yours == solver => println!("You tied with the solver!"),
+        | Some(yours) => {
+            let by = yours - solver;
+            println!("The solver beat you by {by} {}", plural(by));
+        },
+        | None => println!("The solver found the target in {solver} {}", plural(solver)),
+    }
+}
+
+/// Compares each guess that didn't win in `guessed` to the guess the
+/// [`OptimalPlayer`] would have made with the same information, and prints how
+/// close the guesses were to the solver's on average
+#[expect(clippy::float_arithmetic, reason = "statistics")]
+pub fn print_analysis(matrix: &Arc<GuessMatrix>, guessed: &[(String, Guess)]) {
+    let mut solver = OptimalPlayer::new(Arc::clone(matrix), settings::tie_break());
+    let mut accuracies = Vec::with_capacity(guessed.len());
+    println!("======== Analysis ========");
+    for (i, (name, result)) in guessed.iter().enumerate() {
+        let Some(sinner) = matrix.sinners().iter().find(|x| x.name == *name) else {
+            break;
+        };
+        let before = solver.candidates().len();
+        let yours = rank_guesses(
+            solver.candidates(),
+            Strategy::Mean,
+            true,
+            settings::tie_break(),
+        )
+        .into_iter()
+        .find(|x| x.sinner.name == *name)
+        .map_or(0., |x| x.expected_remaining);
+        let Some((best_name, best)) = rank_guesses(
+            solver.candidates(),
+            Strategy::Mean,
+            false,
+            settings::tie_break(),
+        )
+        .first()
+        .map(|x| (x.sinner.name.clone(), x.expected_remaining)) else {
+            break;
+        };
+        solver.update(*result, sinner);
+        let after = solver.candidates().len();
+
+        print!(
+            "Guess {}: {name} left {after} of {before} sinners ({yours:.2} expected).",
+            i + 1
+        );
+        if best_name == *name {
+            println!(" This is what the solver would have guessed!");
+        } else {
+            println!(" The solver would have guessed {best_name} ({best:.2} expected).");
+        }
+        // Guessing a sinner that has been ruled out can beat the solver's guess
+        accuracies.push(if yours <= best { 1. } else { best / yours });
+    }
+    if !accuracies.is_empty() {
+        #[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
+        let accuracy = accuracies.iter().sum::<f64>() / accuracies.len() as f64;
+        println!("Accuracy: {:.0}%", accuracy * 100.);
+    }
+}
+
+/// Prints how many guesses it took to guess each sinner in `results`
+#[expect(clippy::float_arithmetic, reason = "statistics")]
+fn print_summary(results: &GatherResults<'_>) {
+    println!("Goto first sinner to play: {}", results.first_guess);
+    let max_rounds = results.max_guesses();
+    match results.losses() {
+        | 0 => println!("It takes {max_rounds} or less guesses to guess any sinner."),
+        | losses => {
+            println!(
+                "It takes {max_rounds} or less guesses to guess any sinner that isn't given up \
+                 on, and {losses} games were given up. They aren't counted below."
+            );
+        },
+    }
+
+    println!("The number of sinners that take each number of guesses:");
+    let bars = (1..=max_rounds)
+        .map(|rounds| {
+            let count = results.count(rounds);
+            #[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
+            let percent = count as f64 * 100. / results.games.len() as f64;
+            Bar {
+                label: rounds.to_string(),
+                value: count,
+                note: format!("{count} ({percent:.2}%)"),
+            }
+        })
+        .collect::<Vec<_>>();
+    print_bars(&bars);
+    println!("The sinners that take the maximum number of guesses, and the guesses made, are:");
+    for game in results.hardest_games() {
+        println!("    {}: {}", game.target.name, game.sequence.join(" > "));
+    }
+    println!("The mean number of guesses is {:.2}", results.mean());
+}
+
+/// Prints a table comparing the results of multiple players side by side
+fn print_comparison(results: &[GatherResults<'_>]) {
+    let name_width = results
+        .iter()
+        .map(|x| x.name.len())
+        .chain(std::iter::once("Strategy".len()))
+        .max()
+        .unwrap_or_default();
+    let first_width = results
+        .iter()
+        .map(|x| x.first_guess.len())
+        .chain(std::iter::once("First".len()))
+        .max()
+        .unwrap_or_default();
+    let max_rounds = results
+        .iter()
+        .map(GatherResults::max_guesses)
+        .max()
+        .unwrap_or_default();
+
+    print!(
+        "{:name_width$}  {:first_width$}  Max  Mean  Lost",
+        "Strategy", "First"
+    );
+    for rounds in 1..=max_rounds {
+        print!("  {rounds:>4}");
+    }
+    println!();
+    for result in results {
+        print!(
+            "{:name_width$}  {:first_width$}  {:>3}  {:.2}  {:>4}",
+            result.name,
+            result.first_guess,
+            result.max_guesses(),
+            result.mean(),
+            result.losses()
+        );
+        for rounds in 1..=max_rounds {
+            print!("  {:>4}", result.count(rounds));
+        }
+        println!();
+    }
+}
+
+/// Plays every possible game with players from each of `factories` and prints a
+/// summary of the results. When there is more than one factory, the results
+/// are compared in a table instead. If `json` is set, only a [`GatherReport`]
+/// for each factory is printed. The games of each factory are saved to the
+/// checkpoint at the same index in `checkpoints`, if it has one. Returns the
+/// results for each factory.
+pub fn gather_data<'data>(
+    matrix: &'data Arc<GuessMatrix>,
+    factories: &[PlayerFactory<'_>],
+    checkpoints: &[Option<Checkpoint>],
+    json: bool,
+) -> eyre::Result<Vec<GatherResults<'data>>> {
+    let checkpoint = |index: usize| checkpoints.get(index).and_then(Option::as_ref);
+    if json {
+        let results = factories
+            .iter()
+            .enumerate()
+            .map(|(index, factory)| {
+                GatherResults::gather(
+                    &settings::context(),
+                    matrix,
+                    factory,
+                    checkpoint(index),
+                    &mut std::io::sink(),
+                )
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
+        print_json(&results.iter().map(GatherReport::from).collect::<Vec<_>>());
+        return Ok(results);
+    }
+    let results = factories
+        .iter()
+        .enumerate()
+        .map(|(index, factory)| {
+            GatherResults::gather(
+                &settings::context(),
+                matrix,
+                factory,
+                checkpoint(index),
+                &mut stdout(),
+            )
+        })
+        .collect::<std::io::Result<Vec<_>>>()?;
+    if let [result] = results.as_slice() {
+        print_summary(result);
+    } else {
+        print_comparison(&results);
+    }
+    Ok(results)
+}
+
+/// Prints the recommendation and remaining sinners after `initial_state`
+/// without prompting for more rows, as JSON if `json` is set. If `explain` is
+/// set, the groups the top guesses split the remaining sinners into are
+/// printed too.
+pub fn solve_once(
+    initial_state: &[NameAndGuess],
+    matrix: Arc<GuessMatrix>,
+    strategy: SolveStrategy,
+    best: Option<usize>,
+    explain: bool,
+    json: bool,
+    format: Format,
+) -> eyre::Result<()> {
+    let tree = strategy.tree(&matrix)?;
+    let mut state = strategy.state(matrix, tree.as_ref());
+    state.push_all(initial_state)?;
+    let best = best.or(explain.then_some(EXPLAINED_GUESSES));
+    let report = state.report(best, explain);
+    if json {
+        print_json(&report);
+    }
+    if let Some(player) = state.gave_up() {
+        return Err(eyre!(
+            "The player `{player}` gave up without recommending a guess"
+        ));
+    }
+    let Some(name) = &report.recommendation else {
+        return Err(Error::Contradiction(
+            "No possible guesses. There is likely a contradiction.".to_owned(),
+        )
+        .into());
+    };
+    if json {
+        return Ok(());
+    }
+    // A code block keeps Discord from reading the tables as markdown
+    let discord = format == Format::Discord;
+    if discord {
+        println!("```");
+    }
+    // A registered player may recommend another sinner than the one left
+    if let (true, [target]) = (report.solved, report.candidates.as_slice()) {
+        println!("Solved: {target}");
+    } else {
+        println!("Guess {name}");
+    }
+    state.print_candidates();
+    if let Some(best) = best.filter(|_| !report.solved) {
+        if explain {
+            state.print_explanation(best);
+        } else {
+            state.print_ranking(best);
+        }
+    }
+    if discord {
+        println!("```");
+    }
+    Ok(())
+}
+
+/// Solves from `rows` with the sinners in `matrix`, following `tree` if
+/// given, and reports the recommendation and the top `best` guesses
+pub fn solve_report(
+    rows: &[NameAndGuess],
+    matrix: &Arc<GuessMatrix>,
+    tree: Option<&DecisionTree>,
+    config: SolverConfig,
+    best: Option<usize>,
+) -> eyre::Result<SolveReport> {
+    let mut state = SolveState::new(config, Arc::clone(matrix), tree);
+    state.push_all(rows)?;
+    Ok(state.report(best, false))
+}
+
+/// What can be entered for `attribute`'s cell
+fn symbols(attribute: Attribute) -> Vec<&'static str> {
+    attribute.cells().into_iter().map(Cell::to_ascii).collect()
+}
+
+/// Row entry for `solve --guided`, which prompts for the sinner guessed and
+/// each cell of the row separately so that mistakes are caught right away
+struct GuidedInput {
+    sinner: Reedline,
+    /// An editor for each attribute's cell, in the order of [`Attribute::ALL`]
+    cells: [Reedline; Attribute::COUNT],
+}
+
+impl GuidedInput {
+    fn new(sinners: &[Sinner]) -> Self {
+        let names = sinner_names(sinners)
+            .chain(["undo", "history", "explain"])
+            .map(str::to_owned)
+            .collect();
+        Self {
+            sinner: completing_editor(names),
+            cells: Attribute::ALL
+                .map(|x| completing_editor(symbols(x).into_iter().map(str::to_owned).collect())),
+        }
+    }
+
+    /// Prompts for a row one cell at a time, checking each cell as it is
+    /// entered. Returns the row as it would be entered on one line, such as
+    /// `Hella: ^^ 0 0 ~ 1`, or a command such as `undo`.
+    fn read_row(
+        &mut self,
+        recommendation: Option<&Sinner>,
+        sinners: &[Sinner],
+    ) -> eyre::Result<String> {
+        let sinner_prompt = recommendation.map_or_else(
+            || "Sinner guessed ".to_owned(),
+            |x| format!("Sinner guessed [{}] ", x.name),
+        );
+        'row: loop {
+            let name = loop {
+                let Some(input) = read_line(&mut self.sinner, &sinner_prompt)? else {
+                    return Ok("q".to_owned());
+                };
+                match input.as_str() {
+                    | "q" | "undo" | "history" | "explain" => return Ok(input),
+                    | "" => {
+                        match recommendation {
+                            | Some(sinner) => break sinner.name.clone(),
+                            | None => eprintln!("There is no recommended sinner. Enter a name"),
+                        }
+                    },
+                    | name => {
+                        match find_sinner(sinners, name, settings::names()) {
+                            | Ok(sinner) => break sinner.name.clone(),
+                            | Err(e) => eprintln!("{e}"),
+                        }
+                    },
+                }
+            };
+            let mut cells = Vec::new();
+            for (attribute, editor) in Attribute::ALL.into_iter().zip(&mut self.cells) {
+                let symbols = symbols(attribute);
+                let label = format!("{} ({}) ", attribute.header(), symbols.join(" "));
+                loop {
+                    // Ctrl-C starts the row over
+                    let Some(input) = read_line(editor, &label)? else {
+                        continue 'row;
+                    };
+                    if Cell::parse(attribute, &input).is_some() {
+                        cells.push(input);
+                        break;
+                    }
+                    eprintln!("Expected one of {}", symbols.join(", "));
+                }
+            }
+            return Ok(format!("{name}: {}", cells.join(" ")));
+        }
+    }
+}
+
+/// Where `solve` reads rows from
+enum RowInput {
+    Guided(Box<GuidedInput>),
+    /// A line editor with completion of names and row symbols
+    Line(Box<Reedline>),
+    /// Plain lines from stdin when it isn't a terminal, such as when rows are
+    /// piped in
+    Piped,
+}
+
+impl RowInput {
+    fn line_editor(sinners: &[Sinner]) -> Reedline {
+        let words = sinner_names(sinners)
+            .chain(symbols(Attribute::Code))
+            .chain(["undo", "history", "explain"])
+            .map(str::to_owned)
+            .collect();
+        with_history(completing_editor(words), "solve_history.txt")
+    }
+
+    /// Reads the next row or command. Returns `q` once there is no more input.
+    fn read(
+        &mut self,
+        recommendation: Option<&Sinner>,
+        sinners: &[Sinner],
+    ) -> eyre::Result<String> {
+        const PROMPT: &str = "Enter row, name: row if you guessed someone else, undo, history, \
+                              explain or q to quit: ";
+        match self {
+            | Self::Guided(guided) => guided.read_row(recommendation, sinners),
+            | Self::Line(editor) => {
+                Ok(read_line(editor, PROMPT.trim_end())?.unwrap_or_else(|| "q".to_owned()))
+            },
+            | Self::Piped => {
+                print!("{PROMPT}");
+                stdout().flush()?;
+                let mut line = String::new();
+                if stdin().read_line(&mut line)? == 0 {
+                    return Ok("q".to_owned());
+                }
+                Ok(line)
+            },
+        }
+    }
+}
+
+/// Prints how to enter rows, which depends on whether `--guided` is used
+fn print_solve_instructions(guided: bool) {
+    banner!("======== Welcome to the Path to Nowordle Solver ========");
+    banner!(
+        "This solver always wins within 4 guesses from an unknown sinner target, but typically \
+         wins in 3 or less.\n"
+    );
+    banner!("======== Instructions ========");
+    if guided {
+        banner!(
+            "Enter the sinner you guessed, or press Enter if it was the one you were prompted to \
+             play, then each entry in the row as seen on the website."
+        );
+        banner!("Press Tab to see what can be entered, or Ctrl-C to start the row over.");
+        banner!(
+            "Enter undo or history instead of a sinner to undo or list rows, explain to see why \
+             the recommendation is best, or q to quit."
+        );
+        banner!("==============================");
+        return;
+    }
+    banner!(
+        "Enter a row as seen on the website when prompted and guess the sinner you are prompted \
+         to play."
+    );
+    banner!("Entries in the row are separated by whitespace.");
+    banner!("Comparisons are entered as vv/v/~/=/^/^^ and booleans are entered as 0 or 1.");
+    banner!("An example input is ^^ 0 0 ~ 1");
+    banner!("If you guessed a different sinner, enter their name first, like Hella: ^^ 0 0 ~ 1");
+    banner!(
+        "Rows from the website's share text, like ⏫🟥🟥🟨🟩, work too. If you played the \
+         recommended sinners, paste the whole share text."
+    );
+    banner!("==============================");
+}
+
+/// Solves a game interactively, starting from `initial_state`. If `best` is
+/// set, the top `best` guesses are listed along with the recommendation. If
+/// `explain` is set, they are explained instead.
+pub fn solve(
+    initial_state: &[NameAndGuess],
+    matrix: Arc<GuessMatrix>,
+    strategy: SolveStrategy,
+    best: Option<usize>,
+    explain: bool,
+    guided: bool,
+) -> eyre::Result<()> {
+    let terminal = stdin().is_terminal();
+    if guided && !terminal {
+        return Err(
+            Error::InvalidInput("--guided needs a terminal to prompt in".to_owned()).into(),
+        );
+    }
+    print_solve_instructions(guided);
+    let mut input = if guided {
+        RowInput::Guided(Box::new(GuidedInput::new(matrix.sinners())))
+    } else if terminal {
+        RowInput::Line(Box::new(RowInput::line_editor(matrix.sinners())))
+    } else {
+        RowInput::Piped
+    };
+    let tree = strategy.tree(&matrix)?;
+    let mut state = strategy.state(matrix, tree.as_ref());
+
+    state.push_all(initial_state)?;
+    if !initial_state.is_empty() {
+        state.print_candidates();
+    }
+    'outer: loop {
+        let recommendation = state.recommendation().cloned();
+        if let Some(sinner) = &recommendation {
+            println!("Guess {}", sinner.name);
+            if state.player.candidates().len() == 1 {
+                println!("GG! You won.");
+                break;
+            }
+            if explain {
+                state.print_explanation(best.unwrap_or(EXPLAINED_GUESSES));
+            } else if let Some(best) = best {
+                state.print_ranking(best);
+            }
+        } else if let Some(player) = state.gave_up() {
+            eprintln!("The player `{player}` gave up without recommending a guess");
+        } else {
+            eprintln!(
+                "No possible guesses in this state. There is likely a contradiction. Use `undo` \
+                 to remove the last row."
+            );
+        }
+
+        // The sinner actually guessed, which may not be the one recommended
+        let (guessed, guess) = loop {
+            let line = input.read(recommendation.as_ref(), state.sinners())?;
+            match line.trim() {
+                | "q" => break 'outer,
+                | "undo" => {
+                    if let Some((sinner, guess)) = state.undo() {
+                        println!("Removed {}: {}", sinner.name, guess.to_ascii());
+                        state.print_candidates();
+                        continue 'outer;
+                    }
+                    eprintln!("There are no rows to undo");
+                    continue;
+                },
+                | "history" => {
+                    for (sinner, guess) in &state.history {
+                        println!("{}: {}", sinner.name, guess.to_ascii());
+                    }
+                    continue;
+                },
+                | "explain" => {
+                    state.print_explanation(best.unwrap_or(EXPLAINED_GUESSES));
+                    continue;
+                },
+                | _ => {},
+            }
+            if let Ok(guess) = line.parse::<Guess>() {
+                if let Some(sinner) = &recommendation {
+                    break (sinner.clone(), guess);
+                }
+                eprintln!("There is no recommended sinner. Enter the row as name: row");
+                continue;
+            }
+            match line.parse::<NameAndGuess>() {
+                | Ok(NameAndGuess { name, guess }) => {
+                    match find_sinner(state.matrix.sinners(), &name, settings::names()) {
+                        | Ok(guessed) => break (guessed.clone(), guess),
+                        | Err(e) => eprintln!("{e}"),
+                    }
+                },
+                | Err(NameAndGuessError::NoColon) => {},
+                | Err(e) => eprintln!("{e}"),
+            }
+        };
+
+        state.push(guessed, guess);
+        state.print_candidates();
+    }
+    Ok(())
+}
+
+/// What `solve` recommends guesses with
+pub struct SolveStrategy {
+    /// The solver, which also ranks the guesses for `--best` and `--explain`
+    pub config: SolverConfig,
+    /// A registered player picked with `--strategy`, which recommends the
+    /// guesses instead of the solver
+    pub player: Option<PlayerFactory<'static>>,
+}
+
+impl SolveStrategy {
+    /// The solver's decision tree, if it has one and recommends the guesses
+    fn tree(&self, matrix: &Arc<GuessMatrix>) -> eyre::Result<Option<DecisionTree>> {
+        match self.player {
+            | Some(_) => Ok(None),
+            | None => DecisionTree::load_or_build(&settings::context(), self.config, matrix),
+        }
+    }
+
+    fn state(self, matrix: Arc<GuessMatrix>, tree: Option<&DecisionTree>) -> SolveState<'_> {
+        let state = SolveState::new(self.config, matrix, tree);
+        match self.player {
+            | Some(factory) => state.with_player(factory),
+            | None => state,
+        }
+    }
+}
+
+/// The rows entered in a `solve` session and the solver state they lead to
+pub struct SolveState<'tree> {
+    config: SolverConfig,
+    matrix: Arc<GuessMatrix>,
+    /// The root of the decision tree, if the strategy has one
+    tree: Option<&'tree DecisionTree>,
+    player: Box<dyn Solver>,
+    /// The decision tree is followed for as long as the guesses match it, after
+    /// which the solver takes over
+    node: Option<&'tree DecisionTree>,
+    /// A registered player that recommends the guesses instead of the solver,
+    /// which still ranks them, along with how to create it again
+    guide: Option<(PlayerFactory<'static>, Box<dyn Player>)>,
+    history: Vec<(Sinner, Guess)>,
+}
+
+impl<'tree> SolveState<'tree> {
+    pub fn new(
+        config: SolverConfig,
+        matrix: Arc<GuessMatrix>,
+        tree: Option<&'tree DecisionTree>,
+    ) -> Self {
+        Self {
+            config,
+            player: config.solver(Arc::clone(&matrix)),
+            matrix,
+            tree,
+            node: tree,
+            guide: None,
+            history: Vec::new(),
+        }
+    }
+    /// Recommends the guesses of a player from `factory` instead of the
+    /// solver's. This has to be done before any rows are pushed.
+    #[must_use]
+    pub fn with_player(mut self, factory: PlayerFactory<'static>) -> Self {
+        let player = (factory.create)(&self.matrix);
+        self.guide = Some((factory, player));
+        self
+    }
+    pub fn push(&mut self, sinner: Sinner, guess: Guess) {
+        self.player.update(guess, &sinner);
+        if let Some((_, guide)) = &mut self.guide {
+            guide.update(guess, &sinner);
+        }
+        self.node = self
+            .node
+            .filter(|node| node.guess == sinner.name)
+            .and_then(|node| node.next(guess));
+        verbose!(
+            "{} possible sinners remain after {}: {}",
+            self.player.candidates().len(),
+            sinner.name,
+            guess.to_ascii()
+        );
+        self.history.push((sinner, guess));
+    }
+    pub fn sinners(&self) -> &[Sinner] { self.matrix.sinners() }
+    fn push_all(&mut self, rows: &[NameAndGuess]) -> eyre::Result<()> {
+        for NameAndGuess { name, guess } in rows {
+            let sinner = find_sinner(self.matrix.sinners(), name, settings::names())?.clone();
+            self.push(sinner, *guess);
+        }
+        Ok(())
+    }
+    /// Removes the last row, replaying the rest into a fresh solver
+    pub fn undo(&mut self) -> Option<(Sinner, Guess)> {
+        let last = self.history.pop()?;
+        let history = std::mem::take(&mut self.history);
+        self.player = self.config.solver(Arc::clone(&self.matrix));
+        if let Some((factory, guide)) = &mut self.guide {
+            *guide = (factory.create)(&self.matrix);
+        }
+        self.node = self.tree;
+        for (sinner, guess) in history {
+            self.push(sinner, guess);
+        }
+        Some(last)
+    }
+    /// The name of the registered player recommending the guesses, if it gave
+    /// up instead of recommending one
+    pub fn gave_up(&self) -> Option<&str> {
+        self.guide
+            .as_ref()
+            .filter(|(_, guide)| guide.gave_up())
+            .map(|(factory, _)| factory.name.as_str())
+    }
+    pub fn recommendation(&self) -> Option<&Sinner> {
+        if let Some((_, guide)) = &self.guide {
+            return guide.next_guess();
+        }
+        self.node
+            .and_then(|node| self.matrix.sinners().iter().find(|x| x.name == node.guess))
+            .or_else(|| self.player.next_guess())
+    }
+    /// Prints the top `count` guesses under the solver's strategy
+    fn print_ranking(&self, count: usize) {
+        let ranked = rank_guesses(
+            self.player.candidates(),
+            self.config.strategy,
+            self.config.probe,
+            self.config.tie_break,
+        );
+        println!("Top guesses:");
+        for (i, guess) in ranked.iter().take(count).enumerate() {
+            println!(
+                "    {}. {} ({:.2} remaining on average, at most {}{})",
+                i + 1,
+                guess.sinner.name,
+                guess.expected_remaining,
+                guess.worst_remaining,
+                if guess.is_candidate {
+                    ""
+                } else {
+                    ", ruled out"
+                }
+            );
+        }
+    }
+    /// The groups of candidates that guessing `guess` splits the candidates
+    /// into, largest first, along with the result that gives each group
+    fn partition(&self, guess: &Sinner) -> Vec<(Guess, Vec<&Sinner>)> {
+        let Some(index) = self.matrix.index_of(guess) else {
+            return Vec::new();
+        };
+        let mut groups = self
+            .player
+            .candidates()
+            .partition(index)
+            .into_iter()
+            .map(|(result, targets)| {
+                let sinners = targets.into_iter().map(|x| &self.matrix.sinners()[x]);
+                (result, sinners.collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>();
+        groups.sort_by_key(|(_, sinners)| std::cmp::Reverse(sinners.len()));
+        groups
+    }
+    /// Prints the top `count` guesses under the solver's strategy with the
+    /// groups each one splits the candidates into, which is why they are
+    /// ranked where they are
+    fn print_explanation(&self, count: usize) {
+        let ranked = rank_guesses(
+            self.player.candidates(),
+            self.config.strategy,
+            self.config.probe,
+            self.config.tie_break,
+        );
+        for guess in ranked.iter().take(count) {
+            println!(
+                "{}: {:.2} remaining on average, at most {}, {:.2} bits of information{}",
+                guess.sinner.name,
+                guess.expected_remaining,
+                guess.worst_remaining,
+                guess.entropy,
+                if guess.is_candidate {
+                    ""
+                } else {
+                    ", ruled out"
+                }
+            );
+            if guess.is_candidate {
+                println!("    {:15} {:>3}  {}", "correct", 1, guess.sinner.name);
+            }
+            for (result, sinners) in self.partition(guess.sinner) {
+                let mut names = sinners
+                    .iter()
+                    .take(LISTED_SINNERS)
+                    .map(|x| x.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if sinners.len() > LISTED_SINNERS {
+                    _ = write!(names, " and {} more", sinners.len() - LISTED_SINNERS);
+                }
+                println!("    {:15} {:>3}  {names}", result.to_ascii(), sinners.len());
+            }
+        }
+    }
+    fn partition_report(&self, guess: &Sinner) -> Vec<PartitionReport> {
+        self.partition(guess)
+            .into_iter()
+            .map(|(result, sinners)| {
+                PartitionReport {
+                    row: result.to_ascii(),
+                    sinners: sinners.iter().map(|x| x.name.clone()).collect(),
+                }
+            })
+            .collect()
+    }
+    /// The recommendation, the candidates and the top `best` guesses, along
+    /// with the groups they split the candidates into if `explain` is set
+    pub fn report(&self, best: Option<usize>, explain: bool) -> SolveReport {
+        let candidates = self.player.candidates();
+        SolveReport {
+            recommendation: self.recommendation().map(|x| x.name.clone()),
+            solved: candidates.len() == 1,
+            candidates: rank_candidates(candidates, self.config.tie_break)
+                .into_iter()
+                .map(|x| x.sinner.name.clone())
+                .collect(),
+            best: rank_guesses(
+                candidates,
+                self.config.strategy,
+                self.config.probe,
+                self.config.tie_break,
+            )
+            .into_iter()
+            .take(best.unwrap_or(0))
+            .map(|x| {
+                RankedGuessReport {
+                    name: x.sinner.name.clone(),
+                    expected_remaining: x.expected_remaining,
+                    worst_remaining: x.worst_remaining,
+                    is_candidate: x.is_candidate,
+                    partition: if explain {
+                        self.partition_report(x.sinner)
+                    } else {
+                        Vec::new()
+                    },
+                }
+            })
+            .collect(),
+        }
+    }
+    /// Prints the candidates from most to least likely, with the chance that
+    /// each one is the target
+    #[expect(clippy::float_arithmetic, reason = "percentages")]
+    fn print_candidates(&self) {
+        let names = rank_candidates(self.player.candidates(), self.config.tie_break)
+            .into_iter()
+            .map(|x| format!("{} ({:.0}%)", x.sinner.name, x.probability * 100.0))
+            .collect::<Vec<_>>();
+        println!("Possible Sinners: {}", names.join(", "));
+    }
+}
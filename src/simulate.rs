@@ -0,0 +1,301 @@
+//! The `simulate` command, for comparing the solver with naive players over
+//! random games
+
+use std::fmt::Display;
+use std::io::sink;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use ptndle_core::data::{load_sinners, DataSource, Filter};
+use ptndle_core::error::Error;
+use ptndle_core::matrix::GuessMatrix;
+use ptndle_core::play::{play_game, Player, DEFAULT_MAX_GUESSES};
+use ptndle_core::registry::{StrategyName, UnknownPlayerError};
+use ptndle_core::rng::Rng;
+use ptndle_core::solver::{BlindPlayer, RandomPlayer, SolverConfig};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::console::status;
+use crate::flags::Simulate;
+use crate::output::{print_json, SimulateReport, SimulatedPlayerReport};
+use crate::{script, settings};
+
+/// The number of games played when `--games` isn't given
+const DEFAULT_GAMES: usize = 1000;
+
+/// A kind of player that `simulate` can play games with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerKind {
+    /// Guesses random sinners, ignoring the results
+    Random,
+    /// Guesses a random sinner that fits every result so far, without
+    /// planning ahead
+    Greedy,
+    /// The solver
+    Optimal,
+}
+
+impl PlayerKind {
+    pub const ALL: [Self; 3] = [Self::Random, Self::Greedy, Self::Optimal];
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            | Self::Random => "random",
+            | Self::Greedy => "greedy",
+            | Self::Optimal => "optimal",
+        }
+    }
+
+    /// Creates a player of this kind for a game on `matrix`, with the seed of
+    /// `config` for the players that guess randomly. The optimal player has
+    /// `strategy`, which can be a registered player instead of the solver.
+    fn create(
+        self,
+        matrix: &Arc<GuessMatrix>,
+        strategy: &StrategyName,
+        config: SolverConfig,
+    ) -> Result<Box<dyn Player>, UnknownPlayerError> {
+        let seed = config.seed;
+        Ok(match self {
+            | Self::Random => Box::new(BlindPlayer::new(Arc::clone(matrix), Rng::new(seed))),
+            | Self::Greedy => Box::new(RandomPlayer::new(Arc::clone(matrix), Rng::new(seed))),
+            | Self::Optimal => {
+                let registry = script::registry(config);
+                (strategy.factory(config, registry)?.create)(matrix)
+            },
+        })
+    }
+}
+
+impl Display for PlayerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownPlayerKindError(String);
+
+impl FromStr for PlayerKind {
+    type Err = UnknownPlayerKindError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.to_str() == s.trim())
+            .ok_or_else(|| UnknownPlayerKindError(s.to_owned()))
+    }
+}
+impl Display for UnknownPlayerKindError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Unknown player: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected one of `random`, `greedy` or `optimal`")
+    }
+}
+
+/// A comma-separated list of players, or `all` for every kind of player
+#[derive(Debug)]
+pub struct PlayerKinds(pub Vec<PlayerKind>);
+
+impl FromStr for PlayerKinds {
+    type Err = UnknownPlayerKindError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim() == "all" {
+            return Ok(PlayerKinds(PlayerKind::ALL.to_vec()));
+        }
+        s.split(',')
+            .map(str::parse)
+            .collect::<Result<_, Self::Err>>()
+            .map(PlayerKinds)
+    }
+}
+
+/// The results of one kind of player over every simulated game
+struct Simulated {
+    kind: PlayerKind,
+    /// The number of guesses each game took, or `None` if it was lost
+    games: Vec<Option<u8>>,
+}
+
+impl Simulated {
+    fn wins(&self) -> usize { self.games.iter().flatten().count() }
+
+    /// The number of won games that took `guesses` guesses
+    fn count(&self, guesses: u8) -> usize {
+        self.games.iter().filter(|&&x| x == Some(guesses)).count()
+    }
+
+    fn longest(&self) -> u8 {
+        self.games
+            .iter()
+            .flatten()
+            .max()
+            .copied()
+            .unwrap_or_default()
+    }
+
+    #[expect(clippy::float_arithmetic, reason = "statistics")]
+    #[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
+    fn win_rate(&self) -> f64 { self.wins() as f64 / self.games.len().max(1) as f64 }
+
+    #[expect(clippy::float_arithmetic, reason = "statistics")]
+    fn win_percent(&self) -> f64 { self.win_rate() * 100.0 }
+
+    /// The mean number of guesses in the won games
+    #[expect(clippy::float_arithmetic, reason = "statistics")]
+    #[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
+    fn mean(&self) -> f64 {
+        let sum = self
+            .games
+            .iter()
+            .flatten()
+            .map(|&x| u32::from(x))
+            .sum::<u32>();
+        f64::from(sum) / self.wins().max(1) as f64
+    }
+}
+
+impl From<&Simulated> for SimulatedPlayerReport {
+    fn from(simulated: &Simulated) -> Self {
+        Self {
+            player: simulated.kind.to_string(),
+            wins: simulated.wins(),
+            win_rate: simulated.win_rate(),
+            mean: simulated.mean(),
+            distribution: (1..=simulated.longest())
+                .map(|x| simulated.count(x))
+                .collect(),
+        }
+    }
+}
+
+/// Prints a table with a column for each kind of player, giving the number of
+/// games won in each number of guesses
+fn print_simulated(simulated: &[Simulated]) {
+    let width = simulated
+        .iter()
+        .map(|x| x.kind.to_str().len())
+        .max()
+        .unwrap_or_default()
+        .max("100.0%".len());
+    let longest = simulated
+        .iter()
+        .map(Simulated::longest)
+        .max()
+        .unwrap_or_default();
+    let row = |label: &str, cells: &mut dyn Iterator<Item = String>| {
+        print!("{label:8}");
+        cells.for_each(|x| print!("  {x:>width$}"));
+        println!();
+    };
+    row("Guesses", &mut simulated.iter().map(|x| x.kind.to_string()));
+    for guesses in 1..=longest {
+        row(
+            &guesses.to_string(),
+            &mut simulated.iter().map(|x| x.count(guesses).to_string()),
+        );
+    }
+    row(
+        "Lost",
+        &mut simulated
+            .iter()
+            .map(|x| (x.games.len() - x.wins()).to_string()),
+    );
+    row(
+        "Win rate",
+        &mut simulated.iter().map(|x| format!("{:.1}%", x.win_percent())),
+    );
+    row(
+        "Mean",
+        &mut simulated.iter().map(|x| format!("{:.2}", x.mean())),
+    );
+}
+
+/// Plays random games with each kind of player in `kinds`, and prints how
+/// often they win and how many guesses they take
+pub fn run(
+    Simulate {
+        games: count,
+        player: kinds,
+        max_guesses,
+        seed,
+        strategy,
+        depth,
+        probe,
+    }: Simulate,
+    source: &DataSource,
+    filter: &Filter,
+    json: bool,
+) -> eyre::Result<()> {
+    let kinds = kinds.map_or_else(|| PlayerKind::ALL.to_vec(), |PlayerKinds(x)| x);
+    let strategy = strategy.unwrap_or_default();
+    // Looked up once before playing, so that an unknown player fails right away
+    strategy
+        .factory(
+            SolverConfig::default(),
+            script::registry(SolverConfig::default()),
+        )
+        .map_err(|e| Error::InvalidInput(e.to_string()))?;
+    let count = count.unwrap_or(DEFAULT_GAMES);
+    let max_guesses = match max_guesses.unwrap_or(DEFAULT_MAX_GUESSES) {
+        | 0 => None,
+        | max => Some(max),
+    };
+    let seed = match seed {
+        | Some(seed) => seed,
+        | None => Rng::from_entropy()?.next_u64(),
+    };
+    let matrix = Arc::new(GuessMatrix::new(
+        load_sinners(&settings::context(), source, filter)?,
+        filter.thresholds,
+    ));
+    // Every kind of player gets the same targets and seeds, so they are
+    // compared on the same games
+    let mut rng = Rng::new(seed);
+    let setups = (0..count)
+        .map(|_| (rng.below(matrix.len()), rng.next_u64()))
+        .collect::<Vec<_>>();
+    status!("Simulating {count} games with seed {seed}");
+    let simulated = kinds
+        .into_iter()
+        .map(|kind| {
+            let games = setups
+                .clone()
+                .into_par_iter()
+                .map(|(target, player_seed)| {
+                    let config = SolverConfig {
+                        depth: depth.unwrap_or(1),
+                        probe,
+                        seed: player_seed,
+                        tie_break: settings::tie_break(),
+                        ..SolverConfig::default()
+                    };
+                    let player = kind
+                        .create(&matrix, &strategy, config)
+                        .map_err(|e| Error::InvalidInput(e.to_string()))?;
+                    play_game(
+                        &settings::context(),
+                        &matrix.sinners()[target],
+                        player,
+                        matrix.thresholds(),
+                        max_guesses,
+                        true,
+                        &mut sink(),
+                    )
+                    .map_err(Into::into)
+                })
+                .collect::<eyre::Result<Vec<_>>>()?;
+            Ok(Simulated { kind, games })
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+    if json {
+        print_json(&SimulateReport {
+            seed,
+            games: count,
+            max_guesses,
+            players: simulated.iter().map(SimulatedPlayerReport::from).collect(),
+        });
+    } else {
+        print_simulated(&simulated);
+    }
+    Ok(())
+}
@@ -0,0 +1,301 @@
+//! The `sinners`, `info` and `bands` commands, for looking up the sinner data
+
+use std::ops::RangeInclusive;
+
+use ptndle_core::attribute::Attribute;
+use ptndle_core::compare::{Comparison, Threshold};
+use ptndle_core::data::{load_sinners, DataSource, Filter, Sinner};
+use ptndle_core::error::Error;
+use ptndle_core::fuzzy::find_sinner;
+use ptndle_core::query::Query;
+use ptndle_core::theme::Role;
+
+use crate::flags::{Bands, Info, Sinners};
+use crate::output::{print_json, BandReport, BandsReport, InfoReport, RangeReport};
+use crate::settings;
+
+pub const HEADERS: [&str; Attribute::COUNT + 1] = {
+    let mut headers = ["Name"; Attribute::COUNT + 1];
+    let mut index = 0;
+    while index < Attribute::COUNT {
+        headers[index + 1] = Attribute::ALL[index].header();
+        index += 1;
+    }
+    headers
+};
+
+/// The cells of `sinner`'s row in the table
+pub fn row(sinner: &Sinner) -> [String; Attribute::COUNT + 1] {
+    std::array::from_fn(|index| {
+        match index.checked_sub(1) {
+            | Some(attribute) => Attribute::ALL[attribute].value(sinner),
+            | None => sinner.name.clone(),
+        }
+    })
+}
+
+/// Prints `sinners` as a table with a column for each field
+fn print_table(sinners: &[&Sinner]) {
+    let rows = sinners.iter().map(|x| row(x)).collect::<Vec<_>>();
+    let mut widths = HEADERS.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_owned()
+    };
+    println!(
+        "{}",
+        settings::paint(line(&HEADERS.map(str::to_owned)), Role::Bold)
+    );
+    for row in &rows {
+        println!("{}", line(row));
+    }
+}
+
+/// Prints the sinners matching the flags, as a table or as JSON
+pub fn list(
+    Sinners {
+        alignment,
+        tendency,
+        birthplace,
+        height,
+        sort,
+    }: Sinners,
+    source: &DataSource,
+    filter: &Filter,
+    json: bool,
+) -> eyre::Result<()> {
+    let sinners = load_sinners(&settings::context(), source, filter)?;
+    let query = Query {
+        alignment,
+        tendency,
+        birthplace,
+        height,
+        sort: sort.unwrap_or_default(),
+    };
+    let matching = query.run(&sinners);
+    if json {
+        print_json(&matching.into_iter().cloned().collect::<Vec<_>>());
+    } else if matching.is_empty() {
+        println!("No sinners match");
+    } else {
+        print_table(&matching);
+        println!(
+            "{}",
+            settings::paint(format!("{} sinners", matching.len()), Role::Dim)
+        );
+    }
+    Ok(())
+}
+
+/// Prints `sinner`'s data, one field per line
+pub fn print_profile(sinner: &Sinner) {
+    let [name, code, alignment, tendency, height, birthplace] = row(sinner);
+    println!("Name: {name}");
+    println!("Code: {code}");
+    println!("Alignment: {alignment}");
+    println!("Tendency: {tendency}");
+    println!("Height: {height}");
+    println!("Birthplace: {birthplace}");
+    if let Some(released) = &sinner.released {
+        println!("Released: {released}");
+    }
+}
+
+/// Writes a number of hundredths as a decimal, such as `5.3` for 530
+fn hundredths(value: u32) -> String {
+    let decimal = format!("{}.{:02}", value / 100, value % 100);
+    decimal
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_owned()
+}
+
+/// Describes when a guess is near or far from a target with `threshold`
+fn describe_threshold(threshold: Threshold, unit: &str) -> String {
+    format!(
+        "≅ within ±{}{unit}, ↑↑/↓↓ more than ±{}{unit} away",
+        hundredths(threshold.near),
+        hundredths(threshold.far)
+    )
+}
+
+/// Prints the profile of the sinner called `name`, along with the thresholds
+/// used for their code and height when they are the target
+pub fn info(Info { name }: Info, source: &DataSource, json: bool) -> eyre::Result<()> {
+    if name.is_empty() {
+        return Err(Error::InvalidInput("Expected the name of a sinner".to_owned()).into());
+    }
+    let sinners = load_sinners(&settings::context(), source, &<_>::default())?;
+    let sinner = find_sinner(&sinners, &name.join(" "), settings::names())?;
+    let aliases = settings::names()
+        .aliases
+        .of(&sinner.name)
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+    let thresholds = sinner.thresholds(settings::thresholds());
+    if json {
+        print_json(&InfoReport {
+            sinner: sinner.clone(),
+            aliases,
+            thresholds,
+        });
+        return Ok(());
+    }
+    print_profile(sinner);
+    if !aliases.is_empty() {
+        println!("Aliases: {}", aliases.join(", "));
+    }
+    println!();
+    println!(
+        "{}",
+        settings::paint("When this sinner is the target:", Role::Bold)
+    );
+    match thresholds.code {
+        | Some(code) => println!("Code:    {}", describe_threshold(code, "")),
+        | None => println!("Code:    only NOX's code matches, and other codes aren't compared"),
+    }
+    println!("Height:  {}", describe_threshold(thresholds.height, "cm"));
+    Ok(())
+}
+
+/// The comparisons in the order of the values of the target that give them,
+/// lowest first
+const BAND_ORDER: [Comparison; 6] = [
+    Comparison::FarLess,
+    Comparison::Less,
+    Comparison::Near,
+    Comparison::Correct,
+    Comparison::Greater,
+    Comparison::FarGreater,
+];
+
+/// The runs of consecutive values in `values` for which `compare` gives
+/// `comparison`
+fn band(
+    values: RangeInclusive<u16>,
+    comparison: Comparison,
+    compare: impl Fn(u16) -> Option<Comparison>,
+) -> Vec<RangeReport> {
+    let mut ranges = Vec::<RangeReport>::new();
+    for value in values.filter(|x| compare(*x) == Some(comparison)) {
+        match ranges.last_mut() {
+            | Some(range) if range.max + 1 == value => range.max = value,
+            | _ => {
+                ranges.push(RangeReport {
+                    min: value,
+                    max: value,
+                });
+            },
+        }
+    }
+    ranges
+}
+
+/// Writes `ranges` of values from `values`, such as `≤ 152cm, 160cm`
+fn describe_ranges(ranges: &[RangeReport], values: &RangeInclusive<u16>, unit: &str) -> String {
+    if ranges.is_empty() {
+        return "-".to_owned();
+    }
+    ranges
+        .iter()
+        .map(|range| {
+            let (min, max) = (range.min, range.max);
+            match (min == *values.start(), max == *values.end()) {
+                | (true, true) => "any".to_owned(),
+                | (true, false) => format!("≤ {max}{unit}"),
+                | (false, true) => format!("≥ {min}{unit}"),
+                | (false, false) if min == max => format!("{min}{unit}"),
+                | (false, false) => format!("{min}–{max}{unit}"),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Prints the codes and heights of targets that give each comparison when
+/// the sinner called `name` is guessed
+pub fn bands(Bands { name }: Bands, source: &DataSource, json: bool) -> eyre::Result<()> {
+    if name.is_empty() {
+        return Err(Error::InvalidInput("Expected the name of a sinner".to_owned()).into());
+    }
+    let sinners = load_sinners(&settings::context(), source, &<_>::default())?;
+    let guess = find_sinner(&sinners, &name.join(" "), settings::names())?;
+    let config = settings::thresholds();
+    // Targets without a numeric code, like NOX, aren't compared by code
+    let codes = 0..=u16::MAX;
+    let heights = 0..=u16::from(u8::MAX);
+    let compare_code = |target: u16| {
+        guess
+            .code
+            .map(|code| config.code(target).compare(target, code))
+    };
+    let compare_height = |target: u16| {
+        let threshold = config.height(u8::try_from(target).unwrap_or(u8::MAX));
+        Some(threshold.compare(target, guess.height.into()))
+    };
+    let report = BandsReport {
+        guess: guess.name.clone(),
+        bands: BAND_ORDER
+            .into_iter()
+            .map(|comparison| {
+                BandReport {
+                    result: comparison.to_ascii().to_owned(),
+                    codes: band(codes.clone(), comparison, compare_code),
+                    heights: band(heights.clone(), comparison, compare_height),
+                }
+            })
+            .collect(),
+    };
+    if json {
+        print_json(&report);
+        return Ok(());
+    }
+    let [_, code, .., height, _] = row(guess);
+    println!(
+        "Guessing {} (code {code}, {height}), the target's code and height give:",
+        guess.name
+    );
+    let cells = report
+        .bands
+        .iter()
+        .map(|x| describe_ranges(&x.codes, &codes, ""))
+        .collect::<Vec<_>>();
+    let width = cells
+        .iter()
+        .map(|x| x.chars().count())
+        .chain(std::iter::once("Code".len()))
+        .max()
+        .unwrap_or_default();
+    println!(
+        "{}",
+        settings::paint(format!("Result   {:width$}  Height", "Code"), Role::Bold)
+    );
+    for ((comparison, band), cell) in BAND_ORDER.into_iter().zip(&report.bands).zip(cells) {
+        println!(
+            "{} {:2}    {cell:width$}  {}",
+            comparison.paint(settings::theme()),
+            band.result,
+            describe_ranges(&band.heights, &heights, "cm")
+        );
+    }
+    if guess.code.is_some() {
+        println!("Targets without a numeric code, like NOX, give x for the code.");
+    } else {
+        println!(
+            "{} has no numeric code, so the code gives x unless the target has none either.",
+            guess.name
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,767 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use facet::Facet;
+
+use crate::data::Sinner;
+use crate::guess::Guess;
+use crate::matrix::{Candidates, GuessMatrix, SinnerSet};
+use crate::play::Player;
+use crate::rng::Rng;
+
+/// A [`Player`] that keeps track of the sinners that could still be the target
+pub trait Solver: Player {
+    /// The sinners that are consistent with every result seen so far
+    fn candidates(&self) -> &Candidates;
+}
+
+/// Summarizes the number of candidates remaining after a guess for every
+/// possible target, with each target counted by its weight
+#[derive(Debug, Clone, Copy)]
+struct Score {
+    /// The maximum number of remaining candidates
+    max: usize,
+    /// The total number of remaining candidates, weighted by how likely each
+    /// target is
+    sum: f64,
+    /// The sum of the logarithms of the weight of the remaining candidates.
+    /// Minimizing this maximizes the expected information gained from a guess.
+    log: f64,
+}
+
+impl Score {
+    /// Scores a guess from the sizes and weights of the groups of candidates
+    /// it splits the candidates into
+    #[expect(clippy::float_arithmetic, reason = "statistics")]
+    fn new(buckets: impl Iterator<Item = (usize, f64)>) -> Self {
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "there are not that many sinners"
+        )]
+        buckets.fold(
+            Self {
+                max: 0,
+                sum: 0.,
+                log: 0.,
+            },
+            |score, (size, mass)| {
+                Self {
+                    max: score.max.max(size),
+                    sum: size as f64 * mass + score.sum,
+                    log: mass * mass.ln() + score.log,
+                }
+            },
+        )
+    }
+}
+
+/// Picks the best of `guesses` under `strategy`, preferring candidates over
+/// sinners that have been ruled out, with `tie_break` deciding between
+/// guesses that are still tied
+fn best_guess(
+    candidates: &Candidates,
+    guesses: impl Iterator<Item = usize>,
+    strategy: Strategy,
+    tie_break: TieBreak,
+) -> Option<&Sinner> {
+    if candidates.len() <= 1 {
+        return candidates.sinners().next();
+    }
+    let sinners = candidates.matrix().sinners();
+    let best = guesses
+        .map(|guess| {
+            let score = Score::new(candidates.bucket_masses(guess));
+            (guess, score, !candidates.contains(guess))
+        })
+        .min_by(|(a, a_score, a_probe), (b, b_score, b_probe)| {
+            strategy
+                .compare_scores(a_score, b_score)
+                .then(a_probe.cmp(b_probe))
+                .then_with(|| tie_break.compare(sinners, *a, *b))
+        })?
+        .0;
+    Some(&candidates.matrix().sinners()[best])
+}
+
+/// A possible guess and how well it narrows down the candidates
+#[derive(Debug, Clone)]
+pub struct RankedGuess<'sinner> {
+    pub sinner: &'sinner Sinner,
+    /// The expected number of candidates remaining after the guess
+    pub expected_remaining: f64,
+    /// The most candidates that could remain after the guess
+    pub worst_remaining: usize,
+    /// The expected information gained from the guess, in bits
+    pub entropy: f64,
+    /// Whether the sinner could still be the target
+    pub is_candidate: bool,
+}
+
+/// Ranks every guess under `strategy` from best to worst, only considering
+/// the candidates unless `probe` is set. Guesses are scored by looking one
+/// guess ahead, and ties are broken by `tie_break`.
+pub fn rank_guesses(
+    candidates: &Candidates,
+    strategy: Strategy,
+    probe: bool,
+    tie_break: TieBreak,
+) -> Vec<RankedGuess<'_>> {
+    let guesses: Box<dyn Iterator<Item = usize>> = if probe {
+        Box::new(0..candidates.matrix().len())
+    } else {
+        Box::new(candidates.iter())
+    };
+    let mass = candidates.mass();
+    let mut scored = guesses
+        .map(|guess| {
+            let score = Score::new(candidates.bucket_masses(guess));
+            (guess, score, !candidates.contains(guess))
+        })
+        .collect::<Vec<_>>();
+    let sinners = candidates.matrix().sinners();
+    scored.sort_by(|(a, a_score, a_probe), (b, b_score, b_probe)| {
+        strategy
+            .compare_scores(a_score, b_score)
+            .then(a_probe.cmp(b_probe))
+            .then_with(|| tie_break.compare(sinners, *a, *b))
+    });
+    scored
+        .into_iter()
+        .map(|(guess, score, is_probe)| {
+            #[expect(clippy::float_arithmetic, reason = "statistics")]
+            let (expected_remaining, entropy) = (
+                score.sum / mass,
+                (mass.ln() - score.log / mass) / std::f64::consts::LN_2,
+            );
+            RankedGuess {
+                sinner: &candidates.matrix().sinners()[guess],
+                expected_remaining,
+                worst_remaining: score.max,
+                entropy,
+                is_candidate: !is_probe,
+            }
+        })
+        .collect()
+}
+
+/// A sinner that could still be the target and how likely it is
+#[derive(Debug, Clone)]
+pub struct RankedCandidate<'sinner> {
+    pub sinner: &'sinner Sinner,
+    /// The chance that the sinner is the target, in proportion to its weight
+    pub probability: f64,
+    /// The fraction of the candidates that would single the sinner out if
+    /// guessed, by being the sinner or by giving a result no other candidate
+    /// gives
+    pub isolation: f64,
+}
+
+/// Ranks the candidates from most to least likely to be the target, which is
+/// only a matter of weights when the matrix has them. Ties are
+/// broken by how many guesses would single each one out, so the sinners that
+/// are easiest to rule in or out come first, and then by `tie_break`.
+pub fn rank_candidates(candidates: &Candidates, tie_break: TieBreak) -> Vec<RankedCandidate<'_>> {
+    let mut isolated = vec![0usize; candidates.matrix().len()];
+    for guess in candidates.iter() {
+        isolated[guess] += 1;
+        for group in candidates.partition(guess).into_values() {
+            if let [target] = group[..] {
+                isolated[target] += 1;
+            }
+        }
+    }
+    let mass = candidates.mass();
+    let mut ranked = candidates
+        .iter()
+        .map(|index| {
+            #[expect(
+                clippy::cast_precision_loss,
+                clippy::float_arithmetic,
+                reason = "there are not that many sinners"
+            )]
+            let (probability, isolation) = (
+                candidates.matrix().weight(index) / mass,
+                isolated[index] as f64 / candidates.len() as f64,
+            );
+            (
+                index,
+                RankedCandidate {
+                    sinner: &candidates.matrix().sinners()[index],
+                    probability,
+                    isolation,
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    let sinners = candidates.matrix().sinners();
+    ranked.sort_by(|(a_index, a), (b_index, b)| {
+        b.probability
+            .total_cmp(&a.probability)
+            .then(b.isolation.total_cmp(&a.isolation))
+            .then_with(|| tie_break.compare(sinners, *a_index, *b_index))
+    });
+    ranked.into_iter().map(|(_, x)| x).collect()
+}
+
+/// A [`Player`] that guesses sinners based on the mean number of sinners
+/// remaining after a guess.
+#[derive(Debug, Clone)]
+pub struct OptimalPlayer {
+    candidates: Candidates,
+    tie_break: TieBreak,
+}
+
+impl Player for OptimalPlayer {
+    fn update(&mut self, result: Guess, character: &Sinner) {
+        self.candidates.update(result, character);
+    }
+    fn next_guess(&self) -> Option<&Sinner> {
+        best_guess(
+            &self.candidates,
+            self.candidates.iter(),
+            Strategy::Mean,
+            self.tie_break,
+        )
+    }
+}
+
+impl Solver for OptimalPlayer {
+    fn candidates(&self) -> &Candidates { &self.candidates }
+}
+
+impl OptimalPlayer {
+    pub fn new(matrix: Arc<GuessMatrix>, tie_break: TieBreak) -> OptimalPlayer {
+        OptimalPlayer {
+            candidates: Candidates::new(matrix),
+            tie_break,
+        }
+    }
+}
+
+/// A [`Player`] that guesses sinners based on the maximum number of sinners
+/// remaining after a guess, using the mean to break ties.
+#[derive(Debug, Clone)]
+pub struct MinimaxPlayer {
+    candidates: Candidates,
+    tie_break: TieBreak,
+}
+
+impl Player for MinimaxPlayer {
+    fn update(&mut self, result: Guess, character: &Sinner) {
+        self.candidates.update(result, character);
+    }
+    fn next_guess(&self) -> Option<&Sinner> {
+        best_guess(
+            &self.candidates,
+            self.candidates.iter(),
+            Strategy::Minimax,
+            self.tie_break,
+        )
+    }
+}
+
+impl Solver for MinimaxPlayer {
+    fn candidates(&self) -> &Candidates { &self.candidates }
+}
+
+impl MinimaxPlayer {
+    pub fn new(matrix: Arc<GuessMatrix>, tie_break: TieBreak) -> MinimaxPlayer {
+        MinimaxPlayer {
+            candidates: Candidates::new(matrix),
+            tie_break,
+        }
+    }
+}
+
+/// A [`Player`] that guesses sinners based on the expected information gained
+/// from a guess.
+#[derive(Debug, Clone)]
+pub struct EntropyPlayer {
+    candidates: Candidates,
+    tie_break: TieBreak,
+}
+
+impl Player for EntropyPlayer {
+    fn update(&mut self, result: Guess, character: &Sinner) {
+        self.candidates.update(result, character);
+    }
+    fn next_guess(&self) -> Option<&Sinner> {
+        best_guess(
+            &self.candidates,
+            self.candidates.iter(),
+            Strategy::Entropy,
+            self.tie_break,
+        )
+    }
+}
+
+impl Solver for EntropyPlayer {
+    fn candidates(&self) -> &Candidates { &self.candidates }
+}
+
+impl EntropyPlayer {
+    pub fn new(matrix: Arc<GuessMatrix>, tie_break: TieBreak) -> EntropyPlayer {
+        EntropyPlayer {
+            candidates: Candidates::new(matrix),
+            tie_break,
+        }
+    }
+}
+
+/// A [`Player`] that guesses a random candidate, used as a baseline for the
+/// other players
+#[derive(Debug)]
+pub struct RandomPlayer {
+    candidates: Candidates,
+    rng: RefCell<Rng>,
+}
+
+impl Player for RandomPlayer {
+    fn update(&mut self, result: Guess, character: &Sinner) {
+        self.candidates.update(result, character);
+    }
+    fn next_guess(&self) -> Option<&Sinner> {
+        if self.candidates.is_empty() {
+            return None;
+        }
+        let index = self.rng.borrow_mut().below(self.candidates.len());
+        self.candidates.sinners().nth(index)
+    }
+}
+
+impl Solver for RandomPlayer {
+    fn candidates(&self) -> &Candidates { &self.candidates }
+}
+
+impl RandomPlayer {
+    pub fn new(matrix: Arc<GuessMatrix>, rng: Rng) -> RandomPlayer {
+        RandomPlayer {
+            candidates: Candidates::new(matrix),
+            rng: RefCell::new(rng),
+        }
+    }
+}
+
+/// A [`Player`] that guesses random sinners it hasn't guessed yet, ignoring
+/// their results, as a baseline for playing without any reasoning
+#[derive(Debug)]
+pub struct BlindPlayer {
+    matrix: Arc<GuessMatrix>,
+    /// The sinners that haven't been guessed yet
+    unguessed: SinnerSet,
+    rng: RefCell<Rng>,
+}
+
+impl Player for BlindPlayer {
+    fn update(&mut self, _: Guess, character: &Sinner) {
+        if let Some(index) = self.matrix.index_of(character) {
+            self.unguessed.remove(index);
+        }
+    }
+    fn next_guess(&self) -> Option<&Sinner> {
+        if self.unguessed.is_empty() {
+            return None;
+        }
+        let index = self.rng.borrow_mut().below(self.unguessed.len());
+        let guess = self.unguessed.iter().nth(index)?;
+        Some(&self.matrix.sinners()[guess])
+    }
+}
+
+impl BlindPlayer {
+    pub fn new(matrix: Arc<GuessMatrix>, rng: Rng) -> BlindPlayer {
+        BlindPlayer {
+            unguessed: SinnerSet::full(matrix.len()),
+            matrix,
+            rng: RefCell::new(rng),
+        }
+    }
+}
+
+/// A [`Player`] that may guess sinners that have already been ruled out when
+/// doing so narrows down the candidates better than guessing a candidate. A
+/// candidate is still guessed when it is at least as good, such as when
+/// confirming the final candidate.
+#[derive(Debug, Clone)]
+pub struct ProbePlayer {
+    candidates: Candidates,
+    strategy: Strategy,
+    tie_break: TieBreak,
+}
+
+impl Player for ProbePlayer {
+    fn update(&mut self, result: Guess, character: &Sinner) {
+        self.candidates.update(result, character);
+    }
+    fn next_guess(&self) -> Option<&Sinner> {
+        best_guess(
+            &self.candidates,
+            0..self.candidates.matrix().len(),
+            self.strategy,
+            self.tie_break,
+        )
+    }
+}
+
+impl Solver for ProbePlayer {
+    fn candidates(&self) -> &Candidates { &self.candidates }
+}
+
+impl ProbePlayer {
+    pub fn new(matrix: Arc<GuessMatrix>, strategy: Strategy, tie_break: TieBreak) -> Self {
+        Self {
+            candidates: Candidates::new(matrix),
+            strategy,
+            tie_break,
+        }
+    }
+}
+
+/// The number of guesses it takes to find the target from a set of candidates
+#[derive(Debug, Clone, Copy)]
+struct Cost {
+    /// The maximum number of guesses
+    worst: u8,
+    /// The expected number of guesses, with each candidate as likely to be
+    /// the target as its weight says
+    expected: f64,
+}
+
+impl Cost {
+    const SOLVED: Self = Self {
+        worst: 1,
+        expected: 1.,
+    };
+
+    /// An optimistic estimate of the cost of `len` candidates, assuming the
+    /// next guess is able to tell every candidate apart
+    #[expect(clippy::float_arithmetic, reason = "statistics")]
+    fn estimate(len: usize) -> Self {
+        if len == 1 {
+            return Self::SOLVED;
+        }
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "there are not that many sinners"
+        )]
+        Self {
+            worst: 2,
+            expected: 2. - 1. / len as f64,
+        }
+    }
+}
+
+/// A [`Player`] that looks multiple guesses ahead, recursively evaluating every
+/// possible result of a guess. Results for sets of candidates are memoized
+/// within each turn.
+#[derive(Debug)]
+pub struct LookaheadPlayer {
+    candidates: Candidates,
+    /// Whether sinners that have been ruled out may be guessed as probes
+    probe: bool,
+    strategy: Strategy,
+    depth: u8,
+    tie_break: TieBreak,
+    /// `memo[depth]` is the cost of a set of candidates at `depth`
+    memo: RefCell<Vec<HashMap<SinnerSet, Cost>>>,
+}
+
+impl LookaheadPlayer {
+    pub fn new(
+        matrix: Arc<GuessMatrix>,
+        strategy: Strategy,
+        depth: u8,
+        probe: bool,
+        tie_break: TieBreak,
+    ) -> Self {
+        Self {
+            candidates: Candidates::new(matrix),
+            probe,
+            strategy,
+            depth,
+            tie_break,
+            memo: RefCell::new(vec![HashMap::new(); usize::from(depth) + 1]),
+        }
+    }
+
+    /// The sinners that may be guessed when `set` are the remaining candidates
+    fn guesses<'set>(&self, set: &'set SinnerSet) -> Box<dyn Iterator<Item = usize> + 'set> {
+        if self.probe {
+            Box::new(0..self.candidates.matrix().len())
+        } else {
+            Box::new(set.iter())
+        }
+    }
+
+    /// The cost of guessing `guess` when `set` are the remaining candidates
+    #[expect(clippy::float_arithmetic, reason = "statistics")]
+    fn cost(&self, guess: usize, set: &SinnerSet, depth: u8) -> Cost {
+        let matrix = self.candidates.matrix();
+        let mut cost = Cost::SOLVED;
+        let mass = matrix.mass(set);
+        for bucket in matrix.buckets(guess) {
+            let bucket_len = bucket.intersection_len(set);
+            let rest = match bucket_len {
+                | 0 => continue,
+                // skip building the bucket when its cost is already known
+                | 1 => Cost::SOLVED,
+                | _ if depth == 1 => Cost::estimate(bucket_len),
+                | _ => self.value(&bucket.intersection(set), depth - 1),
+            };
+            cost.worst = cost.worst.max(rest.worst + 1);
+            cost.expected += matrix.intersection_mass(bucket, set) / mass * rest.expected;
+        }
+        cost
+    }
+
+    /// The cost of the best guess when `set` are the remaining candidates
+    fn value(&self, set: &SinnerSet, depth: u8) -> Cost {
+        let len = set.len();
+        if len == 1 || depth == 0 {
+            return Cost::estimate(len);
+        }
+        if let Some(cost) = self.memo.borrow()[usize::from(depth)].get(set) {
+            return *cost;
+        }
+        let cost = self
+            .guesses(set)
+            .map(|guess| self.cost(guess, set, depth))
+            .min_by(|a, b| self.strategy.compare_costs(*a, *b))
+            .unwrap();
+        self.memo.borrow_mut()[usize::from(depth)].insert(set.clone(), cost);
+        cost
+    }
+}
+
+impl Player for LookaheadPlayer {
+    fn update(&mut self, result: Guess, character: &Sinner) {
+        self.candidates.update(result, character);
+        self.memo.get_mut().iter_mut().for_each(HashMap::clear);
+    }
+    fn next_guess(&self) -> Option<&Sinner> {
+        if self.candidates.len() <= 1 {
+            return self.candidates.sinners().next();
+        }
+        let set = self.candidates.set();
+        let (sinners, tie_break) = (self.candidates.matrix().sinners(), self.tie_break);
+        let best = self
+            .guesses(set)
+            .map(|guess| (guess, self.cost(guess, set, self.depth)))
+            .min_by(|(a, a_cost), (b, b_cost)| {
+                self.strategy
+                    .compare_costs(*a_cost, *b_cost)
+                    .then_with(|| tie_break.compare(sinners, *a, *b))
+            })?
+            .0;
+        Some(&self.candidates.matrix().sinners()[best])
+    }
+}
+
+impl Solver for LookaheadPlayer {
+    fn candidates(&self) -> &Candidates { &self.candidates }
+}
+
+/// The strategy a [`Solver`] uses to pick its next guess
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// Minimize the mean number of remaining sinners
+    #[default]
+    Mean,
+    /// Minimize the maximum number of remaining sinners
+    Minimax,
+    /// Maximize the expected information gained from a guess
+    Entropy,
+    /// Guess a random remaining sinner
+    Random,
+}
+
+impl Strategy {
+    pub const ALL: [Self; 4] = [Self::Mean, Self::Minimax, Self::Entropy, Self::Random];
+
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            | Self::Mean => "mean",
+            | Self::Minimax => "minimax",
+            | Self::Entropy => "entropy",
+            | Self::Random => "random",
+        }
+    }
+
+    /// Compares the costs of 2 guesses, with the lesser cost being the
+    /// better guess under this strategy. Looking ahead minimizes the expected
+    /// number of guesses for every strategy other than minimax.
+    fn compare_costs(self, a: Cost, b: Cost) -> Ordering {
+        match self {
+            | Self::Minimax => {
+                a.worst
+                    .cmp(&b.worst)
+                    .then(a.expected.total_cmp(&b.expected))
+            },
+            | Self::Mean | Self::Entropy | Self::Random => {
+                a.expected
+                    .total_cmp(&b.expected)
+                    .then(a.worst.cmp(&b.worst))
+            },
+        }
+    }
+
+    /// Compares the scores of 2 guesses, with the lesser score being the
+    /// better guess under this strategy. The random strategy doesn't score its
+    /// guesses, so it compares scores like the mean strategy.
+    fn compare_scores(self, a: &Score, b: &Score) -> Ordering {
+        match self {
+            | Self::Mean | Self::Random => a.sum.total_cmp(&b.sum),
+            | Self::Minimax => a.max.cmp(&b.max).then(a.sum.total_cmp(&b.sum)),
+            | Self::Entropy => a.log.total_cmp(&b.log).then(a.sum.total_cmp(&b.sum)),
+        }
+    }
+}
+
+impl Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownStrategyError(String);
+
+impl FromStr for Strategy {
+    type Err = UnknownStrategyError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.to_str() == s)
+            .ok_or_else(|| UnknownStrategyError(s.to_owned()))
+    }
+}
+impl Display for UnknownStrategyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Unknown strategy: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected one of `mean`, `minimax`, `entropy` or `random`")
+    }
+}
+
+/// How the solver picks between guesses that are equally good under its
+/// [`Strategy`], and how ranked guesses and candidates that are tied are
+/// ordered. Breaking ties by name or code keeps the recommendations the same
+/// when the sinner data lists the sinners in another order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Facet)]
+#[repr(u8)]
+pub enum TieBreak {
+    /// The name that comes first, comparing bytes, and then the lowest code
+    #[default]
+    Name,
+    /// The lowest code, with NOX last, and then the name that comes first
+    Code,
+    /// The sinner that comes first in the sinner data
+    Order,
+}
+
+impl TieBreak {
+    pub const ALL: [Self; 3] = [Self::Name, Self::Code, Self::Order];
+
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            | Self::Name => "name",
+            | Self::Code => "code",
+            | Self::Order => "order",
+        }
+    }
+
+    /// Compares the sinners at `a` and `b` in `sinners`, with the lesser one
+    /// being picked
+    pub fn compare(self, sinners: &[Sinner], a: usize, b: usize) -> Ordering {
+        let (first, second) = (&sinners[a], &sinners[b]);
+        // NOX has no code, and `None` would come before every code
+        let code = |x: &Sinner| (x.code.is_none(), x.code);
+        match self {
+            | Self::Name => {
+                first
+                    .name
+                    .cmp(&second.name)
+                    .then_with(|| code(first).cmp(&code(second)))
+            },
+            | Self::Code => {
+                code(first)
+                    .cmp(&code(second))
+                    .then_with(|| first.name.cmp(&second.name))
+            },
+            | Self::Order => a.cmp(&b),
+        }
+    }
+}
+
+impl Display for TieBreak {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownTieBreakError(String);
+
+impl FromStr for TieBreak {
+    type Err = UnknownTieBreakError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.to_str() == s.trim())
+            .ok_or_else(|| UnknownTieBreakError(s.to_owned()))
+    }
+}
+impl Display for UnknownTieBreakError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Unknown tie-break: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected one of `name`, `code` or `order`")
+    }
+}
+
+/// The options used to create a [`Solver`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SolverConfig {
+    pub strategy: Strategy,
+    /// The number of guesses the solver looks ahead. Depths of 0 and 1 both
+    /// only look at the next guess.
+    pub depth: u8,
+    /// Whether the solver may guess sinners that have already been ruled out
+    pub probe: bool,
+    /// The seed used by the random strategy
+    pub seed: u64,
+    /// How guesses that are equally good are picked between
+    pub tie_break: TieBreak,
+}
+
+impl SolverConfig {
+    /// Creates a [`Solver`] using this config with the sinners in `matrix` as
+    /// the initial pool of sinners
+    pub fn solver(self, matrix: Arc<GuessMatrix>) -> Box<dyn Solver> {
+        if self.strategy == Strategy::Random {
+            return Box::new(RandomPlayer::new(matrix, Rng::new(self.seed)));
+        }
+        if self.depth > 1 {
+            return Box::new(LookaheadPlayer::new(
+                matrix,
+                self.strategy,
+                self.depth,
+                self.probe,
+                self.tie_break,
+            ));
+        }
+        if self.probe {
+            return Box::new(ProbePlayer::new(matrix, self.strategy, self.tie_break));
+        }
+        match self.strategy {
+            | Strategy::Mean => Box::new(OptimalPlayer::new(matrix, self.tie_break)),
+            | Strategy::Minimax => Box::new(MinimaxPlayer::new(matrix, self.tie_break)),
+            | Strategy::Entropy => Box::new(EntropyPlayer::new(matrix, self.tie_break)),
+            | Strategy::Random => Box::new(RandomPlayer::new(matrix, Rng::new(self.seed))),
+        }
+    }
+}
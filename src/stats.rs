@@ -0,0 +1,395 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use facet::Facet;
+use ptndle_core::data::Sinner;
+use ptndle_core::error::Error;
+use ptndle_core::guess::Guess;
+use ptndle_core::matrix::GuessMatrix;
+use ptndle_core::play::Player;
+use ptndle_core::rng::Rng;
+use ptndle_core::solver::OptimalPlayer;
+use ptndle_core::theme::Role;
+
+use crate::chart::{print_bars, Bar};
+use crate::flags::{self, Export, Import, StatsCmd};
+use crate::output::print_json;
+use crate::settings;
+
+/// The number of fastest wins shown for each number of targets
+const PERSONAL_BESTS: usize = 5;
+
+/// Formats `time` like a stopwatch, such as `1:05.3`
+pub fn format_time(time: Duration) -> String {
+    let tenths = time.as_millis() / 100;
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+/// A random id for a new record, which tells it apart from another game with
+/// the same results when stats are merged. It is never 0, which is the id of
+/// records saved before they had ids.
+pub fn new_id() -> eyre::Result<u64> { Ok(Rng::from_entropy()?.next_u64().max(1)) }
+
+/// A guess that didn't win in a [`GameRecord`]
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+pub struct GuessRecord {
+    pub name: String,
+    /// The result of the guess as it is entered when solving, such as
+    /// `^^ 0 0 ~ 1`
+    pub result: String,
+}
+
+/// A completed game of `play`
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+pub struct GameRecord {
+    /// From [`new_id`], or 0 for games recorded before games had ids
+    #[facet(default)]
+    pub id: u64,
+    /// The day the game was played on as `YYYY-MM-DD` in UTC
+    pub date: String,
+    pub target: String,
+    /// The number of guesses it took, or `None` if the game was lost
+    pub guesses: Option<u8>,
+    /// Whether the game was the daily puzzle
+    pub daily: bool,
+    /// The guesses that didn't win, in order. Games recorded before guesses
+    /// were saved have none.
+    #[facet(default)]
+    pub guessed: Vec<GuessRecord>,
+    /// Whether the game was interrupted with Ctrl-C. It counts as lost
+    /// unless it's resumed, which replaces this record.
+    #[facet(default)]
+    pub abandoned: bool,
+}
+
+impl GameRecord {
+    /// Whether `self` and `other` record the same game. There is one daily a
+    /// day, so two dailies are the same game if they were played on the same
+    /// day. Other games are the same if they have the same id, and games
+    /// without ids if everything else is the same too.
+    fn is_same_game(&self, other: &Self) -> bool {
+        if self.daily && other.daily {
+            self.date == other.date
+        } else if self.id == 0 {
+            self == other
+        } else {
+            self.id == other.id
+        }
+    }
+
+    /// Prints the game guess by guess. If `sinners` are given, each guess is
+    /// compared to what the [`OptimalPlayer`] would have guessed instead.
+    pub fn replay(&self, sinners: Option<Vec<Sinner>>) {
+        let matrix = sinners.map(|x| Arc::new(GuessMatrix::new(x, *settings::thresholds())));
+        let mut solver = matrix
+            .as_ref()
+            .map(|x| OptimalPlayer::new(Arc::clone(x), settings::tie_break()));
+        for GuessRecord { name, result } in &self.guessed {
+            print_suggestion(solver.as_ref(), name);
+            println!("Guessed {name}");
+            let Ok(result) = result.parse::<Guess>() else {
+                println!("(unreadable result `{result}`)");
+                solver = None;
+                continue;
+            };
+            println!("{}", result.paint(settings::theme()));
+            if let (Some(player), Some(matrix)) = (&mut solver, &matrix) {
+                // The annotations stop if the sinner is no longer in the data
+                match matrix.sinners().iter().find(|x| x.name == *name) {
+                    | Some(sinner) => player.update(result, sinner),
+                    | None => solver = None,
+                }
+            }
+        }
+        if let Some(guesses) = self.guesses {
+            print_suggestion(solver.as_ref(), &self.target);
+            println!("Guessed {}", self.target);
+            println!("{}", settings::paint(" =  1  1  =  1", Role::Correct));
+            println!("Won in {guesses} guesses! The sinner was {}.", self.target);
+        } else if self.abandoned {
+            println!("Abandoned! The sinner was {}.", self.target);
+        } else {
+            println!("Lost! The sinner was {}.", self.target);
+        }
+    }
+}
+
+/// Prints what `solver` would have guessed if it differs from `name`
+fn print_suggestion(solver: Option<&OptimalPlayer>, name: &str) {
+    if let Some(best) = solver.and_then(Player::next_guess) {
+        if best.name != name {
+            println!(
+                "{}",
+                settings::paint(
+                    format!("(The solver would have guessed {})", best.name),
+                    Role::Dim
+                )
+            );
+        }
+    }
+}
+
+/// A completed game of `play --timed`
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+pub struct TimeRecord {
+    /// From [`new_id`], or 0 for games recorded before games had ids
+    #[facet(default)]
+    pub id: u64,
+    /// The day the game was played on as `YYYY-MM-DD` in UTC
+    pub date: String,
+    /// The number of sinners that had to be found
+    pub targets: usize,
+    /// The number of guesses it took, or `None` if the game was lost
+    pub guesses: Option<u8>,
+    /// How long each guess took in milliseconds, including the one that won
+    pub guess_ms: Vec<u64>,
+}
+
+impl TimeRecord {
+    /// How long the whole game took
+    pub fn total(&self) -> Duration { Duration::from_millis(self.guess_ms.iter().sum()) }
+
+    /// Whether `self` and `other` record the same game, which they do if they
+    /// have the same id, or if they have no ids and everything else is the
+    /// same
+    fn is_same_game(&self, other: &Self) -> bool {
+        if self.id == 0 {
+            self == other
+        } else {
+            self.id == other.id
+        }
+    }
+}
+
+/// Every game of `play` completed so far, oldest first
+#[derive(Debug, Default, Facet)]
+pub struct Stats {
+    pub games: Vec<GameRecord>,
+    /// Every timed game, including games with more than one target, which
+    /// aren't in `games`. Stats saved before timed games existed have none.
+    #[facet(default)]
+    pub timed: Vec<TimeRecord>,
+}
+
+fn stats_path() -> PathBuf { settings::data_dir().join("stats.json") }
+
+impl Stats {
+    /// Loads the stats from the data directory, or empty stats if no game has
+    /// been recorded yet
+    pub fn load() -> eyre::Result<Self> {
+        let path = stats_path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::read(&path)
+    }
+
+    /// Reads the stats in `path`, which is in the same format as the saved
+    /// stats
+    pub fn read(path: &Path) -> eyre::Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| Error::Io(format!("Failed to read {}: {e}", path.display())))?;
+        facet_json::from_str(&json).map_err(|e| {
+            Error::InvalidInput(format!("Failed to parse {}: {e}", path.display())).into()
+        })
+    }
+
+    pub fn save(&self) -> eyre::Result<()> {
+        std::fs::create_dir_all(settings::data_dir())
+            .map_err(|e| Error::Io(format!("Failed to create data directory: {e}")))?;
+        std::fs::write(stats_path(), facet_json::to_string(self))
+            .map_err(|e| Error::Io(format!("Failed to save stats: {e}")).into())
+    }
+
+    /// Adds `game` to the saved stats
+    pub fn record(game: GameRecord) -> eyre::Result<()> {
+        let mut stats = Self::load()?;
+        stats.games.push(game);
+        stats.save()
+    }
+
+    /// Removes the last abandoned game with `target` from the saved stats,
+    /// when it is resumed
+    pub fn forget_abandoned(target: &str) -> eyre::Result<()> {
+        let mut stats = Self::load()?;
+        if let Some(index) = stats
+            .games
+            .iter()
+            .rposition(|x| x.abandoned && x.target == target)
+        {
+            stats.games.remove(index);
+        }
+        stats.save()
+    }
+
+    /// Adds the games in `other` that aren't recorded yet, keeping the games
+    /// oldest first. Returns the number of games and of timed games added.
+    pub fn merge(&mut self, other: Self) -> (usize, usize) {
+        let (games, timed) = (self.games.len(), self.timed.len());
+        for game in other.games {
+            if !self.games.iter().any(|x| x.is_same_game(&game)) {
+                self.games.push(game);
+            }
+        }
+        for time in other.timed {
+            if !self.timed.iter().any(|x| x.is_same_game(&time)) {
+                self.timed.push(time);
+            }
+        }
+        // Stable, so games from the same day stay in the order they were played
+        self.games.sort_by(|a, b| a.date.cmp(&b.date));
+        self.timed.sort_by(|a, b| a.date.cmp(&b.date));
+        (self.games.len() - games, self.timed.len() - timed)
+    }
+
+    /// Adds `time` to the saved stats
+    pub fn record_time(time: TimeRecord) -> eyre::Result<()> {
+        let mut stats = Self::load()?;
+        stats.timed.push(time);
+        stats.save()
+    }
+
+    /// The fastest timed wins with `targets` targets, fastest first
+    pub fn personal_bests(&self, targets: usize) -> Vec<&TimeRecord> {
+        let mut wins = self
+            .timed
+            .iter()
+            .filter(|x| x.targets == targets && x.guesses.is_some())
+            .collect::<Vec<_>>();
+        wins.sort_by_key(|x| x.total());
+        wins.truncate(PERSONAL_BESTS);
+        wins
+    }
+
+    /// Prints the fastest timed wins for each number of targets
+    fn print_personal_bests(&self) {
+        let mut counts = self.timed.iter().map(|x| x.targets).collect::<Vec<_>>();
+        counts.sort_unstable();
+        counts.dedup();
+        for targets in counts {
+            let bests = self.personal_bests(targets);
+            if bests.is_empty() {
+                continue;
+            }
+            let plural = if targets == 1 { "" } else { "s" };
+            println!("Personal bests with {targets} target{plural}:");
+            for (rank, time) in (1..).zip(bests) {
+                println!(
+                    "    {rank}. {} in {} guesses on {}",
+                    format_time(time.total()),
+                    time.guesses.unwrap_or_default(),
+                    time.date
+                );
+            }
+        }
+    }
+
+    /// Finds the daily puzzle played on `date`
+    pub fn daily(&self, date: &str) -> Option<&GameRecord> {
+        self.games.iter().find(|x| x.daily && x.date == date)
+    }
+
+    pub fn wins(&self) -> usize { self.games.iter().filter(|x| x.guesses.is_some()).count() }
+
+    /// The number of games won in a row at the end of the history, and the
+    /// most games ever won in a row
+    pub fn streaks(&self) -> (usize, usize) {
+        self.games.iter().fold((0, 0), |(current, max), game| {
+            if game.guesses.is_some() {
+                (current + 1, max.max(current + 1))
+            } else {
+                (0, max)
+            }
+        })
+    }
+
+    #[expect(clippy::float_arithmetic, reason = "statistics")]
+    pub fn print(&self) {
+        if self.games.is_empty() {
+            if self.timed.is_empty() {
+                println!("No games played yet. Play one with `ptndle-cli play`!");
+            }
+            self.print_personal_bests();
+            return;
+        }
+        let (current, max) = self.streaks();
+        println!("Games played:   {}", self.games.len());
+        #[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
+        {
+            println!(
+                "Win rate:       {:.0}%",
+                self.wins() as f64 * 100. / self.games.len() as f64
+            );
+        }
+        println!("Current streak: {current}");
+        println!("Max streak:     {max}");
+
+        println!("Guess distribution:");
+        let max_guesses = self
+            .games
+            .iter()
+            .filter_map(|x| x.guesses)
+            .max()
+            .unwrap_or_default();
+        let bars = (1..=max_guesses)
+            .map(|guesses| {
+                let count = self
+                    .games
+                    .iter()
+                    .filter(|x| x.guesses == Some(guesses))
+                    .count();
+                Bar {
+                    label: guesses.to_string(),
+                    value: count,
+                    note: count.to_string(),
+                }
+            })
+            .collect::<Vec<_>>();
+        print_bars(&bars);
+        let losses = self.games.len() - self.wins();
+        if losses != 0 {
+            println!("    Lost {losses} games");
+        }
+        self.print_personal_bests();
+    }
+}
+
+/// Prints the stats, or exports or imports them
+pub fn run(flags::Stats { subcommand }: flags::Stats, json: bool) -> eyre::Result<()> {
+    let mut stats = Stats::load()?;
+    match subcommand {
+        | StatsCmd::Show(_) => {
+            if json {
+                print_json(&stats);
+            } else {
+                stats.print();
+            }
+        },
+        | StatsCmd::Export(Export { file }) => {
+            std::fs::write(&file, facet_json::to_string(&stats))
+                .map_err(|e| Error::Io(format!("Failed to write {}: {e}", file.display())))?;
+            println!(
+                "Exported {} games and {} timed games to {}",
+                stats.games.len(),
+                stats.timed.len(),
+                file.display()
+            );
+        },
+        | StatsCmd::Import(Import { file }) => {
+            let imported = Stats::read(&file)?;
+            let total = imported.games.len() + imported.timed.len();
+            let (games, timed) = stats.merge(imported);
+            stats.save()?;
+            println!(
+                "Imported {games} games and {timed} timed games from {}",
+                file.display()
+            );
+            let skipped = total - games - timed;
+            if skipped > 0 {
+                println!("Left out {skipped} games that were already recorded");
+            }
+        },
+    }
+    Ok(())
+}
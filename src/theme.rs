@@ -0,0 +1,299 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use owo_colors::{AnsiColors, DynColors, Style, Styled};
+
+/// What a piece of styled text means, which decides how the [`Theme`] styles
+/// it
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// A comparison that matches the target
+    Correct,
+    /// A comparison that is close to the target
+    Near,
+    /// A comparison that doesn't match the target
+    Wrong,
+    /// Less important text, such as hints
+    Dim,
+    /// Headings
+    Bold,
+    /// The selected item in a list
+    Selected,
+}
+
+/// A color of text
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Color {
+    /// One of the 16 colors of the terminal's palette, from 0 for black to 15
+    /// for bright white
+    Ansi(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    pub const RED: Self = Self::Ansi(1);
+    pub const GREEN: Self = Self::Ansi(2);
+    pub const YELLOW: Self = Self::Ansi(3);
+    pub const BLUE: Self = Self::Ansi(4);
+    pub const MAGENTA: Self = Self::Ansi(5);
+
+    const fn to_dyn(self) -> DynColors {
+        const PALETTE: [AnsiColors; 16] = [
+            AnsiColors::Black,
+            AnsiColors::Red,
+            AnsiColors::Green,
+            AnsiColors::Yellow,
+            AnsiColors::Blue,
+            AnsiColors::Magenta,
+            AnsiColors::Cyan,
+            AnsiColors::White,
+            AnsiColors::BrightBlack,
+            AnsiColors::BrightRed,
+            AnsiColors::BrightGreen,
+            AnsiColors::BrightYellow,
+            AnsiColors::BrightBlue,
+            AnsiColors::BrightMagenta,
+            AnsiColors::BrightCyan,
+            AnsiColors::BrightWhite,
+        ];
+        match self {
+            | Self::Ansi(index) => DynColors::Ansi(PALETTE[index as usize % PALETTE.len()]),
+            | Self::Rgb(r, g, b) => DynColors::Rgb(r, g, b),
+        }
+    }
+}
+
+/// How text is styled for a [`Role`], for output that isn't written with
+/// [`paint`], such as a full-screen interface
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "each is an effect of its own"
+)]
+pub struct Look {
+    /// The color of the text, or `None` for the terminal's default
+    pub color: Option<Color>,
+    pub bold: bool,
+    pub dimmed: bool,
+    pub underline: bool,
+    pub reversed: bool,
+}
+
+impl Look {
+    /// Unstyled text
+    pub const fn new() -> Self {
+        Self {
+            color: None,
+            bold: false,
+            dimmed: false,
+            underline: false,
+            reversed: false,
+        }
+    }
+    #[must_use]
+    pub const fn color(self, color: Color) -> Self {
+        Self {
+            color: Some(color),
+            ..self
+        }
+    }
+    #[must_use]
+    pub const fn bold(self) -> Self { Self { bold: true, ..self } }
+    #[must_use]
+    pub const fn dimmed(self) -> Self {
+        Self {
+            dimmed: true,
+            ..self
+        }
+    }
+    #[must_use]
+    pub const fn underline(self) -> Self {
+        Self {
+            underline: true,
+            ..self
+        }
+    }
+    #[must_use]
+    pub const fn reversed(self) -> Self {
+        Self {
+            reversed: true,
+            ..self
+        }
+    }
+
+    const fn is_plain(self) -> bool {
+        self.color.is_none() && !self.bold && !self.dimmed && !self.underline && !self.reversed
+    }
+
+    fn style(self) -> Style {
+        let mut style = Style::new();
+        if let Some(color) = self.color {
+            style = style.color(color.to_dyn());
+        }
+        if self.bold {
+            style = style.bold();
+        }
+        if self.dimmed {
+            style = style.dimmed();
+        }
+        if self.underline {
+            style = style.underline();
+        }
+        if self.reversed {
+            style = style.reversed();
+        }
+        style
+    }
+}
+
+/// The styles used for each [`Role`]
+#[derive(Copy, Clone, Debug)]
+pub struct Theme {
+    correct: Look,
+    near: Look,
+    wrong: Look,
+    dim: Look,
+    bold: Look,
+    selected: Look,
+}
+
+impl Theme {
+    /// Red, yellow and green, like ptndle.com
+    pub const fn standard() -> Self {
+        Self {
+            correct: Look::new().color(Color::GREEN),
+            near: Look::new().color(Color::YELLOW),
+            wrong: Look::new().color(Color::RED),
+            ..Self::mono()
+        }
+    }
+    /// Blue, yellow and magenta, which can be told apart with any kind of
+    /// color blindness
+    pub const fn colorblind() -> Self {
+        Self {
+            correct: Look::new().color(Color::BLUE).bold(),
+            near: Look::new().color(Color::YELLOW),
+            wrong: Look::new().color(Color::MAGENTA),
+            ..Self::mono()
+        }
+    }
+    /// No colors, with comparisons told apart by bold and underlined text
+    pub const fn mono() -> Self {
+        Self {
+            correct: Look::new().bold(),
+            near: Look::new().underline(),
+            wrong: Look::new(),
+            dim: Look::new().dimmed(),
+            bold: Look::new().bold(),
+            selected: Look::new().reversed(),
+        }
+    }
+    /// No styling at all
+    pub const fn plain() -> Self {
+        Self {
+            correct: Look::new(),
+            near: Look::new(),
+            wrong: Look::new(),
+            dim: Look::new(),
+            bold: Look::new(),
+            selected: Look::new(),
+        }
+    }
+
+    /// The theme to use when none is given: [`Theme::plain`] if the
+    /// `NO_COLOR` environment variable is set, or [`Theme::standard`]
+    pub fn from_env() -> Self {
+        match std::env::var_os("NO_COLOR") {
+            | Some(x) if !x.is_empty() => Self::plain(),
+            | _ => Self::standard(),
+        }
+    }
+
+    /// Whether the theme doesn't style anything, for output that can't be
+    /// styled with [`Theme::paint`]
+    pub const fn is_plain(&self) -> bool {
+        self.correct.is_plain() &&
+            self.near.is_plain() &&
+            self.wrong.is_plain() &&
+            self.dim.is_plain() &&
+            self.bold.is_plain() &&
+            self.selected.is_plain()
+    }
+
+    /// How text is styled for `role`
+    pub const fn look(&self, role: Role) -> Look {
+        match role {
+            | Role::Correct => self.correct,
+            | Role::Near => self.near,
+            | Role::Wrong => self.wrong,
+            | Role::Dim => self.dim,
+            | Role::Bold => self.bold,
+            | Role::Selected => self.selected,
+        }
+    }
+
+    /// Styles `text` for `role`
+    pub fn paint<T: Display>(&self, text: T, role: Role) -> Styled<T> {
+        self.look(role).style().style(text)
+    }
+}
+
+/// Parses a color name such as `blue` or `bright red`, or a hex code such as
+/// `#56b4e9`
+fn parse_color(s: &str) -> Option<Color> {
+    match s.parse::<DynColors>().ok()? {
+        | DynColors::Rgb(r, g, b) => Some(Color::Rgb(r, g, b)),
+        | DynColors::Ansi(ansi) => {
+            (0..16)
+                .map(Color::Ansi)
+                .find(|x| matches!(x.to_dyn(), DynColors::Ansi(y) if y == ansi))
+        },
+        | DynColors::Css(_) | DynColors::Xterm(_) => None,
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownThemeError(String);
+
+impl FromStr for Theme {
+    type Err = UnknownThemeError;
+    /// Parses `default`, `colorblind`, `mono`, `plain`, or
+    /// `custom:<correct>,<near>,<wrong>` where each color is a name such as
+    /// `blue` or a hex code such as `#56b4e9`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim() {
+            | "default" => Self::standard(),
+            | "colorblind" => Self::colorblind(),
+            | "mono" => Self::mono(),
+            | "plain" => Self::plain(),
+            | theme => {
+                let colors = theme
+                    .strip_prefix("custom:")
+                    .ok_or_else(|| UnknownThemeError(s.to_owned()))?
+                    .split(',')
+                    .map(|x| parse_color(x.trim()))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| UnknownThemeError(s.to_owned()))?;
+                let [correct, near, wrong] = colors[..] else {
+                    return Err(UnknownThemeError(s.to_owned()));
+                };
+                Self {
+                    correct: Look::new().color(correct),
+                    near: Look::new().color(near),
+                    wrong: Look::new().color(wrong),
+                    ..Self::mono()
+                }
+            },
+        })
+    }
+}
+impl Display for UnknownThemeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Unknown theme: `")?;
+        f.write_str(&self.0)?;
+        f.write_str(
+            "`. Expected one of `default`, `colorblind`, `mono`, `plain` or \
+             `custom:<correct>,<near>,<wrong>`",
+        )
+    }
+}
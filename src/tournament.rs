@@ -0,0 +1,289 @@
+//! The `tournament` command, for ranking every registered player against each
+//! other on the same games
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::io::sink;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use ptndle_core::data::{load_sinners, DataSource, Filter};
+use ptndle_core::error::Error;
+use ptndle_core::matrix::GuessMatrix;
+use ptndle_core::play::{play_game, Player, PlayerFactory, DEFAULT_MAX_GUESSES};
+use ptndle_core::progress::Progress;
+use ptndle_core::rng::Rng;
+use ptndle_core::solver::SolverConfig;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::console::{console, status};
+use crate::flags::Tournament;
+use crate::output::{print_json, write_standings_csv, StandingReport};
+use crate::{script, settings};
+
+/// What players are ranked by first, with the other used to break ties
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RankKey {
+    /// The mean number of guesses
+    #[default]
+    Mean,
+    /// The most guesses taken in any game
+    Worst,
+}
+
+impl RankKey {
+    pub const ALL: [Self; 2] = [Self::Mean, Self::Worst];
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            | Self::Mean => "mean",
+            | Self::Worst => "worst",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownRankKeyError(String);
+
+impl FromStr for RankKey {
+    type Err = UnknownRankKeyError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.to_str() == s.trim())
+            .ok_or_else(|| UnknownRankKeyError(s.to_owned()))
+    }
+}
+impl Display for UnknownRankKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Unknown ranking: `")?;
+        f.write_str(&self.0)?;
+        f.write_str("`. Expected `mean` or `worst`")
+    }
+}
+
+/// A game in the tournament: the target, and the sinner every player is made
+/// to guess first, if any
+#[derive(Debug, Clone, Copy)]
+struct Round {
+    target: usize,
+    start: Option<usize>,
+}
+
+/// How one player did over every round
+struct Standing {
+    name: String,
+    /// The number of guesses taken in each round, or `None` if the player gave
+    /// up
+    games: Vec<Option<u8>>,
+}
+
+impl Standing {
+    /// The number of guesses taken in each round the player didn't give up
+    fn won(&self) -> impl Iterator<Item = u8> + '_ { self.games.iter().filter_map(|&x| x) }
+
+    /// The number of rounds the player gave up
+    fn lost(&self) -> usize { self.games.iter().filter(|x| x.is_none()).count() }
+
+    /// The total number of guesses in the rounds that weren't given up.
+    /// Players that gave up as many rounds won as many, so this ranks them
+    /// the same as the mean without rounding.
+    fn total(&self) -> u32 { self.won().map(u32::from).sum() }
+
+    fn worst(&self) -> u8 { self.won().max().unwrap_or_default() }
+
+    /// The number of rounds that took the most guesses
+    fn at_worst(&self) -> usize {
+        let worst = self.worst();
+        self.won().filter(|&x| x >= worst).count()
+    }
+
+    /// The number of rounds that would have been lost on ptndle.com, not
+    /// counting the ones that were given up
+    fn over_max(&self) -> usize { self.won().filter(|&x| x > DEFAULT_MAX_GUESSES).count() }
+
+    #[expect(clippy::float_arithmetic, reason = "statistics")]
+    #[expect(clippy::cast_precision_loss, reason = "It doesn't matter.")]
+    fn mean(&self) -> f64 {
+        f64::from(self.total()) / (self.games.len() - self.lost()).max(1) as f64
+    }
+
+    /// Compares by the number of rounds given up, then by `key`, then by the
+    /// other key, then by the number of rounds that took the most guesses,
+    /// then by name, with the better player first
+    fn compare(&self, other: &Self, key: RankKey) -> Ordering {
+        let mean = self.total().cmp(&other.total());
+        let worst = self.worst().cmp(&other.worst());
+        self.lost()
+            .cmp(&other.lost())
+            .then(match key {
+                | RankKey::Mean => mean.then(worst),
+                | RankKey::Worst => worst.then(mean),
+            })
+            .then(self.at_worst().cmp(&other.at_worst()))
+            .then_with(|| self.name.cmp(&other.name))
+    }
+}
+
+/// Plays `round` with a player from `factory`, returning the number of guesses
+/// it took, or `None` if the player gave up
+fn play_round(
+    matrix: &Arc<GuessMatrix>,
+    factory: &PlayerFactory<'_>,
+    round: Round,
+) -> std::io::Result<Option<u8>> {
+    let target = &matrix.sinners()[round.target];
+    let mut player = (factory.create)(matrix);
+    let mut forced = 0;
+    if let Some(start) = round.start {
+        let start = &matrix.sinners()[start];
+        if start == target {
+            return Ok(Some(1));
+        }
+        player.update(target.guess(start, matrix.thresholds()), start);
+        forced = 1;
+    }
+    Ok(play_game(
+        &settings::context(),
+        target,
+        player,
+        matrix.thresholds(),
+        None,
+        true,
+        &mut sink(),
+    )?
+    .map(|x| x + forced))
+}
+
+/// Prints the standings as a table, best first
+fn print_standings(reports: &[StandingReport]) {
+    let name_width = reports
+        .iter()
+        .map(|x| x.player.len())
+        .chain(std::iter::once("Player".len()))
+        .max()
+        .unwrap_or_default();
+    println!(
+        "Rank  {:name_width$}    Mean  Worst  At worst  Over {DEFAULT_MAX_GUESSES}  Lost",
+        "Player"
+    );
+    for report in reports {
+        println!(
+            "{:>4}  {:name_width$}  {:>6.2}  {:>5}  {:>8}  {:>6}  {:>4}",
+            report.rank,
+            report.player,
+            report.mean,
+            report.worst,
+            report.at_worst,
+            report.over_max,
+            report.lost
+        );
+    }
+}
+
+/// Plays every round with every player in `players`, or every registered
+/// player, and ranks them
+pub fn run(
+    Tournament {
+        players,
+        depth,
+        random_starts,
+        seed,
+        rank_by,
+        csv,
+    }: Tournament,
+    source: &DataSource,
+    filter: &Filter,
+    json: bool,
+) -> eyre::Result<()> {
+    let seed = seed.unwrap_or_default();
+    let registry = script::registry(SolverConfig {
+        depth: depth.unwrap_or(1),
+        seed,
+        tie_break: settings::tie_break(),
+        ..SolverConfig::default()
+    });
+    let factories = match &players {
+        | Some(names) => {
+            names
+                .split(',')
+                .map(|name| {
+                    registry.get(name.trim()).ok_or_else(|| {
+                        Error::InvalidInput(format!(
+                            "Unknown player: `{}`. Expected one of {}",
+                            name.trim(),
+                            registry.names().collect::<Vec<_>>().join(", ")
+                        ))
+                        .into()
+                    })
+                })
+                .collect::<eyre::Result<Vec<_>>>()?
+        },
+        | None => registry.iter().collect(),
+    };
+    let matrix = Arc::new(GuessMatrix::new(
+        load_sinners(&settings::context(), source, filter)?,
+        filter.thresholds,
+    ));
+    let mut rng = Rng::new(seed);
+    let rounds = (0..matrix.len())
+        .flat_map(|target| {
+            let starts = (0..random_starts.unwrap_or_default())
+                .map(|_| Some(rng.below(matrix.len())))
+                .collect::<Vec<_>>();
+            std::iter::once(None)
+                .chain(starts)
+                .map(move |start| Round { target, start })
+        })
+        .collect::<Vec<_>>();
+    let mut standings = factories
+        .into_iter()
+        .map(|factory| {
+            status!("Playing {} rounds with {}", rounds.len(), factory.name);
+            let task = format!("tournament {}", factory.name);
+            let progress = Progress::new(task, rounds.len(), console());
+            let games = rounds
+                .par_iter()
+                .map(|&round| {
+                    let guesses = play_round(&matrix, factory, round)?;
+                    progress.step(&matrix.sinners()[round.target].name);
+                    Ok(guesses)
+                })
+                .collect::<std::io::Result<Vec<_>>>()?;
+            progress.finish();
+            Ok(Standing {
+                name: factory.name.clone(),
+                games,
+            })
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+    let key = rank_by.unwrap_or_default();
+    standings.sort_by(|a, b| a.compare(b, key));
+    let reports = standings
+        .iter()
+        .enumerate()
+        .map(|(index, standing)| {
+            StandingReport {
+                rank: index + 1,
+                player: standing.name.clone(),
+                games: standing.games.len(),
+                mean: standing.mean(),
+                worst: standing.worst(),
+                at_worst: standing.at_worst(),
+                over_max: standing.over_max(),
+                lost: standing.lost(),
+            }
+        })
+        .collect::<Vec<_>>();
+    if let Some(path) = csv {
+        write_standings_csv(&path, &reports)?;
+        if !json {
+            status!("Wrote the standings to {}", path.display());
+        }
+    }
+    if json {
+        print_json(&reports);
+    } else {
+        print_standings(&reports);
+    }
+    Ok(())
+}
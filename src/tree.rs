@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use eyre::eyre;
+use facet::Facet;
+
+use crate::compare::ThresholdConfig;
+use crate::context::Context;
+use crate::data::{data_hash, make_and_get_cache_dir};
+use crate::error::Error;
+use crate::guess::Guess;
+use crate::matrix::GuessMatrix;
+use crate::solver::{SolverConfig, Strategy, TieBreak};
+use crate::{status, verbose};
+
+/// The guesses a solver makes for every possible target, starting from its
+/// first guess
+#[derive(Debug, Facet)]
+pub struct DecisionTree {
+    /// The sinner to guess
+    pub guess: String,
+    /// The number of sinners that could be the target before guessing
+    pub candidates: usize,
+    /// What to guess next for every possible result of guessing `guess`, other
+    /// than winning
+    pub branches: Vec<Branch>,
+}
+
+/// A possible result of a guess in a [`DecisionTree`]
+#[derive(Debug, Facet)]
+pub struct Branch {
+    /// The result in the same format it is entered when solving, such as
+    /// `^^ 0 0 ~ 1`
+    pub result: String,
+    pub next: DecisionTree,
+}
+
+/// A [`DecisionTree`] stored in the cache along with what it was built from
+#[derive(Facet)]
+struct CachedTree {
+    /// The version of ptndle-cli that built the tree
+    version: String,
+    /// The [`data_hash`] of the sinners the tree was built for
+    data_hash: String,
+    /// The thresholds the tree was built with
+    thresholds: ThresholdConfig,
+    /// How the tree's guesses were picked between ties
+    tie_break: TieBreak,
+    tree: DecisionTree,
+}
+
+impl DecisionTree {
+    /// Loads the decision tree for `config` and the sinners in `matrix` from
+    /// the cache, building and caching it if it is missing or outdated.
+    /// Returns `None` for the random strategy, which has no fixed tree. Trees
+    /// for weighted sinners are always built, since the cache doesn't know
+    /// the weights.
+    pub fn load_or_build(
+        context: &Context,
+        config: SolverConfig,
+        matrix: &Arc<GuessMatrix>,
+    ) -> eyre::Result<Option<Self>> {
+        if config.strategy == Strategy::Random {
+            return Ok(None);
+        }
+        if matrix.weights().is_some() {
+            return Self::build(config, matrix).map(Some);
+        }
+        let path = make_and_get_cache_dir(context.game)?.join(format!(
+            "tree-{}-{}{}.json",
+            config.strategy,
+            config.depth.max(1),
+            if config.probe { "-probe" } else { "" }
+        ));
+        let version = env!("CARGO_PKG_VERSION");
+        let hash = format!("{:016x}", data_hash(matrix.sinners()));
+        let cached = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| facet_json::from_str::<CachedTree>(&json).ok())
+            .filter(|cached| {
+                cached.version == version &&
+                    cached.data_hash == hash &&
+                    cached.thresholds == *matrix.thresholds() &&
+                    cached.tie_break == config.tie_break
+            });
+        if let Some(cached) = cached {
+            verbose!(
+                context,
+                "Using the cached decision tree in {}",
+                path.display()
+            );
+            return Ok(Some(cached.tree));
+        }
+
+        status!(
+            context,
+            "Building the decision tree. This only happens when the sinner data, thresholds or \
+             tie-break change."
+        );
+        let built = CachedTree {
+            version: version.to_owned(),
+            data_hash: hash,
+            thresholds: *matrix.thresholds(),
+            tie_break: config.tie_break,
+            tree: Self::build(config, matrix)?,
+        };
+        // I don't care if the write fails... just try
+        _ = std::fs::write(&path, facet_json::to_string(&built));
+        Ok(Some(built.tree))
+    }
+
+    /// Gets the subtree to follow after guessing [`DecisionTree::guess`] and
+    /// getting `result`
+    pub fn next(&self, result: Guess) -> Option<&Self> {
+        self.branches
+            .iter()
+            .find(|branch| branch.result.parse::<Guess>().ok() == Some(result))
+            .map(|branch| &branch.next)
+    }
+
+    /// Builds the tree of guesses made by the solvers described by `config`
+    /// for every target in `matrix`
+    pub fn build(config: SolverConfig, matrix: &Arc<GuessMatrix>) -> eyre::Result<Self> {
+        Self::build_from(config, matrix, &mut Vec::new())
+    }
+
+    /// Builds the subtree reached by guessing the sinners in `history` and
+    /// getting their results
+    fn build_from(
+        config: SolverConfig,
+        matrix: &Arc<GuessMatrix>,
+        history: &mut Vec<(usize, Guess)>,
+    ) -> eyre::Result<Self> {
+        if history.len() > matrix.len() {
+            return Err(eyre!("The solver does not find the target in this state."));
+        }
+        let mut solver = config.solver(Arc::clone(matrix));
+        for (guess, result) in history.iter() {
+            solver.update(*result, &matrix.sinners()[*guess]);
+        }
+        let guess = solver.next_guess().ok_or_else(|| {
+            Error::Contradiction(
+                "No possible guesses in this state. There is likely a contradiction.".to_owned(),
+            )
+        })?;
+        let guess_index = matrix.index_of(guess).unwrap();
+        let candidates = solver.candidates();
+
+        let mut branches = Vec::new();
+        for bucket in matrix.buckets(guess_index) {
+            let Some(target) = bucket.intersection(candidates.set()).iter().next() else {
+                continue;
+            };
+            let result = matrix.get(guess_index, target);
+            history.push((guess_index, result));
+            let next = Self::build_from(config, matrix, history)?;
+            history.pop();
+            branches.push(Branch {
+                result: result.to_ascii(),
+                next,
+            });
+        }
+        Ok(Self {
+            guess: guess.name.clone(),
+            candidates: candidates.len(),
+            branches,
+        })
+    }
+}
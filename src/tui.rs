@@ -0,0 +1,17 @@
+use ratatui::DefaultTerminal;
+
+mod app;
+mod draw;
+
+pub use app::TuiPlayer;
+
+/// Keeps the terminal in full-screen raw mode until dropped
+pub struct Terminal(DefaultTerminal);
+
+impl Terminal {
+    pub fn enter() -> std::io::Result<Self> { ratatui::try_init().map(Self) }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) { ratatui::restore(); }
+}
@@ -0,0 +1,211 @@
+use std::cell::{Cell, RefCell};
+use std::sync::Arc;
+
+use ptndle_core::data::Sinner;
+use ptndle_core::fuzzy::fold;
+use ptndle_core::guess::Guess;
+use ptndle_core::matrix::{Candidates, GuessMatrix};
+use ptndle_core::play::Player;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+use super::draw::draw;
+use super::Terminal;
+use crate::settings;
+
+/// The state of the interface between guesses
+pub struct App {
+    /// What has been typed into the search box
+    pub search: String,
+    /// The selected row in the sinner list
+    pub selected: usize,
+    /// Whether the sinner list only shows sinners that could be the target
+    pub only_candidates: bool,
+    /// The index of each sinner guessed and its result, or `None` for the
+    /// winning guess
+    pub rows: Vec<(usize, Option<Guess>)>,
+    pub candidates: Candidates,
+    pub max_guesses: Option<u8>,
+    /// A message shown at the bottom of the screen in place of the key help
+    pub message: Option<String>,
+}
+
+impl App {
+    /// The number of the guess being made, or of the winning guess once the
+    /// game is won
+    pub fn guess_number(&self) -> usize {
+        self.rows.iter().filter(|(_, x)| x.is_some()).count() + 1
+    }
+
+    /// The indices of the sinners shown in the sinner list
+    pub fn filtered(&self, sinners: &[Sinner]) -> Vec<usize> {
+        let search = fold(&self.search);
+        (0..sinners.len())
+            .filter(|x| !self.only_candidates || self.candidates.contains(*x))
+            .filter(|x| fold(&sinners[*x].name).contains(&search))
+            .collect()
+    }
+}
+
+/// What to do after a key is pressed
+enum Action {
+    Redraw,
+    Guess(usize),
+    Quit,
+}
+
+/// A [`Player`] that reads guesses from a full-screen terminal interface
+pub struct TuiPlayer {
+    sinners: Vec<Sinner>,
+    app: RefCell<App>,
+    terminal: RefCell<Terminal>,
+    /// Whether Esc or Ctrl-C was pressed
+    aborted: Cell<bool>,
+}
+
+impl TuiPlayer {
+    pub fn new(terminal: Terminal, sinners: Vec<Sinner>, max_guesses: Option<u8>) -> Self {
+        let app = App {
+            search: String::new(),
+            selected: 0,
+            only_candidates: false,
+            rows: Vec::new(),
+            candidates: Candidates::new(Arc::new(GuessMatrix::new(
+                sinners.clone(),
+                *settings::thresholds(),
+            ))),
+            max_guesses,
+            message: None,
+        };
+        Self {
+            sinners,
+            app: RefCell::new(app),
+            terminal: RefCell::new(terminal),
+            aborted: Cell::new(false),
+        }
+    }
+
+    /// Shows the end of the game and waits for a key to be pressed
+    pub fn finish(&self, target: &Sinner, guesses: Option<u8>) -> std::io::Result<()> {
+        let mut app = self.app.borrow_mut();
+        app.message = Some(match guesses {
+            | Some(guesses) => {
+                if let Some(index) = self.sinners.iter().position(|x| x == target) {
+                    app.rows.push((index, None));
+                }
+                format!("Won in {guesses} guesses! Press any key to exit.")
+            },
+            | None => {
+                format!(
+                    "Out of guesses! The sinner was {}. Press any key to exit.",
+                    target.name
+                )
+            },
+        });
+        draw(&mut self.terminal.borrow_mut(), &app, &self.sinners)?;
+        loop {
+            if let Event::Key(KeyEvent {
+                kind: KeyEventKind::Press,
+                ..
+            }) = event::read()?
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    fn handle_key(&self, app: &mut App, key: KeyEvent) -> Action {
+        let filtered = app.filtered(&self.sinners);
+        match key.code {
+            | KeyCode::Esc => return Action::Quit,
+            | KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Action::Quit;
+            },
+            | KeyCode::Enter => {
+                if let Some(index) = filtered.get(app.selected) {
+                    return Action::Guess(*index);
+                }
+                app.message = Some("No sinner selected".to_owned());
+                return Action::Redraw;
+            },
+            | KeyCode::Up => app.selected = app.selected.saturating_sub(1),
+            | KeyCode::Down => {
+                app.selected = (app.selected + 1).min(filtered.len().saturating_sub(1));
+            },
+            | KeyCode::PageUp => app.selected = app.selected.saturating_sub(10),
+            | KeyCode::PageDown => {
+                app.selected = (app.selected + 10).min(filtered.len().saturating_sub(1));
+            },
+            | KeyCode::Tab => {
+                app.only_candidates = !app.only_candidates;
+                app.selected = 0;
+            },
+            | KeyCode::Backspace => {
+                app.search.pop();
+                app.selected = 0;
+            },
+            | KeyCode::Char(c) => {
+                app.search.push(c);
+                app.selected = 0;
+            },
+            | _ => {},
+        }
+        app.message = None;
+        Action::Redraw
+    }
+
+    /// Whether the game was left with Esc or Ctrl-C, which stops
+    /// [`next_guess`](Player::next_guess) from making a guess
+    pub fn aborted(&self) -> bool { self.aborted.get() }
+
+    /// The sinners guessed so far that didn't win
+    pub fn guessed(&self) -> Vec<Sinner> {
+        let app = self.app.borrow();
+        app.rows
+            .iter()
+            .filter(|(_, x)| x.is_some())
+            .map(|(x, _)| self.sinners[*x].clone())
+            .collect()
+    }
+}
+
+impl Player for TuiPlayer {
+    fn next_guess(&self) -> Option<&Sinner> {
+        let mut app = self.app.borrow_mut();
+        let mut terminal = self.terminal.borrow_mut();
+        loop {
+            if let Err(e) = draw(&mut terminal, &app, &self.sinners) {
+                ratatui::restore();
+                eprintln!("Failed to draw the interface: {e}");
+                std::process::exit(1);
+            }
+            let key = match event::read() {
+                | Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+                | Ok(_) => continue,
+                | Err(e) => {
+                    ratatui::restore();
+                    eprintln!("Failed to read input: {e}");
+                    std::process::exit(1);
+                },
+            };
+            match self.handle_key(&mut app, key) {
+                | Action::Redraw => {},
+                | Action::Guess(index) => {
+                    app.search.clear();
+                    app.selected = 0;
+                    return Some(&self.sinners[index]);
+                },
+                | Action::Quit => {
+                    self.aborted.set(true);
+                    return None;
+                },
+            }
+        }
+    }
+    fn update(&mut self, result: Guess, character: &Sinner) {
+        let app = self.app.get_mut();
+        app.candidates.update(result, character);
+        if let Some(index) = self.sinners.iter().position(|x| x == character) {
+            app.rows.push((index, Some(result)));
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use ptndle_core::data::Sinner;
+use ptndle_core::theme::{self, Role};
+use ratatui::layout::{Constraint, Layout, Margin, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+
+use super::app::App;
+use super::Terminal;
+use crate::settings;
+
+/// The width of the sinner list, including the divider
+const SIDEBAR_WIDTH: u16 = 28;
+/// The width of the sinner name column in the guess grid
+const NAME_WIDTH: usize = 18;
+
+/// The ratatui style of `role` in the installed theme
+fn style(role: Role) -> Style {
+    /// The colors of the terminal's palette, as [`theme::Color::Ansi`] numbers
+    /// them
+    const PALETTE: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::Gray,
+        Color::DarkGray,
+        Color::LightRed,
+        Color::LightGreen,
+        Color::LightYellow,
+        Color::LightBlue,
+        Color::LightMagenta,
+        Color::LightCyan,
+        Color::White,
+    ];
+    let look = settings::theme().look(role);
+    let mut style = Style::new();
+    match look.color {
+        | Some(theme::Color::Ansi(index)) => {
+            style = style.fg(PALETTE[usize::from(index) % PALETTE.len()]);
+        },
+        | Some(theme::Color::Rgb(r, g, b)) => style = style.fg(Color::Rgb(r, g, b)),
+        | None => {},
+    }
+    for (set, modifier) in [
+        (look.bold, Modifier::BOLD),
+        (look.dimmed, Modifier::DIM),
+        (look.underline, Modifier::UNDERLINED),
+        (look.reversed, Modifier::REVERSED),
+    ] {
+        if set {
+            style = style.add_modifier(modifier);
+        }
+    }
+    style
+}
+
+/// Redraws the whole interface
+pub fn draw(terminal: &mut Terminal, app: &App, sinners: &[Sinner]) -> std::io::Result<()> {
+    terminal.0.draw(|frame| render(frame, app, sinners))?;
+    Ok(())
+}
+
+fn render(frame: &mut Frame<'_>, app: &App, sinners: &[Sinner]) {
+    let [body, help] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+    let [sidebar, main] =
+        Layout::horizontal([Constraint::Length(SIDEBAR_WIDTH), Constraint::Min(0)]).areas(body);
+    render_sidebar(frame, sidebar, app, sinners);
+    render_main(frame, main, app, sinners);
+
+    let help_text = app.message.as_deref().unwrap_or(
+        "Type to search  Up/Down: select  Enter: guess  Tab: candidates only  Esc: quit",
+    );
+    frame.render_widget(Paragraph::new(help_text).style(style(Role::Dim)), help);
+}
+
+/// The searchable sinner list
+fn render_sidebar(frame: &mut Frame<'_>, area: Rect, app: &App, sinners: &[Sinner]) {
+    let block = Block::new().borders(Borders::RIGHT);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let [search, list] = Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(inner);
+
+    let filter = if app.only_candidates {
+        "candidates"
+    } else {
+        "all"
+    };
+    frame.render_widget(
+        Paragraph::new(format!("Search ({filter}): {}", app.search)),
+        search,
+    );
+    let items = app.filtered(sinners).into_iter().map(|index| {
+        let marker = if app.candidates.contains(index) {
+            "•"
+        } else {
+            " "
+        };
+        format!("{marker} {}", sinners[index].name)
+    });
+    let mut state = ListState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(
+        List::new(items).highlight_style(style(Role::Selected)),
+        list,
+        &mut state,
+    );
+}
+
+/// The guess grid and the candidate tracker
+fn render_main(frame: &mut Frame<'_>, area: Rect, app: &App, sinners: &[Sinner]) {
+    let area = area.inner(Margin::new(1, 0));
+    let grid_height = u16::try_from(app.rows.len() + 3).unwrap_or(u16::MAX);
+    let [grid, tracker] =
+        Layout::vertical([Constraint::Length(grid_height), Constraint::Min(0)]).areas(area);
+
+    let max = app
+        .max_guesses
+        .map_or_else(String::new, |x| format!("/{x}"));
+    let mut lines = vec![
+        Line::styled(
+            format!("Path to Nowordle - guess {}{max}", app.guess_number()),
+            style(Role::Bold),
+        ),
+        Line::default(),
+        Line::raw(format!("{:NAME_WIDTH$} Cd Al Td Ht Bp", "Sinner")),
+    ];
+    for (index, result) in &app.rows {
+        let sinner = &sinners[*index];
+        let name: String = sinner.name.chars().take(NAME_WIDTH).collect();
+        let mut spans = vec![Span::raw(format!("{name:NAME_WIDTH$}"))];
+        // The winning guess is shown as the target compared with itself
+        let result = result.unwrap_or_else(|| sinner.guess(sinner, settings::thresholds()));
+        for (i, cell) in result.all_cells().into_iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::raw(" "));
+            }
+            spans.push(Span::styled(cell.to_str(), style(cell.role())));
+        }
+        lines.push(Line::from(spans));
+    }
+    frame.render_widget(Paragraph::new(lines), grid);
+
+    let names = app
+        .candidates
+        .sinners()
+        .map(|x| x.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::default(),
+            Line::raw(format!("{} possible sinners:", app.candidates.len())),
+            Line::raw(names),
+        ])
+        .wrap(Wrap { trim: true }),
+        tracker,
+    );
+}
@@ -0,0 +1,103 @@
+//! How much is reported besides the results: banners and notes about what the
+//! program is doing can be hidden for scripts, or diagnostics shown for
+//! finding out why something happened
+//!
+//! Results are printed with `println!` as usual. Everything else goes through
+//! [`banner!`](crate::banner), [`status!`](crate::status),
+//! [`verbose!`](crate::verbose) and [`warning!`](crate::warning), which hand
+//! the message to the [`Reporter`] they are given. The library never prints
+//! these itself, so a program using it decides where the messages go and
+//! which of them are shown.
+
+use std::fmt::Arguments;
+
+use crate::progress::ProgressEvent;
+
+/// How much is reported besides the results
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only results, warnings and errors
+    Quiet,
+    /// Banners and notes about what the program is doing too
+    #[default]
+    Normal,
+    /// Diagnostics too, such as where the sinner data came from and why
+    Verbose,
+}
+
+/// The kind of a message that isn't a result, by the macro it was reported
+/// with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Note {
+    /// Welcome text and instructions, from [`banner!`](crate::banner)
+    Banner,
+    /// A note about what the program is doing, from [`status!`](crate::status)
+    Status,
+    /// A diagnostic, from [`verbose!`](crate::verbose)
+    Verbose,
+    /// A warning, from [`warning!`](crate::warning)
+    Warning,
+}
+
+impl Note {
+    /// Whether notes of this kind are shown at `verbosity`. Warnings always
+    /// are.
+    pub fn shown(self, verbosity: Verbosity) -> bool {
+        match self {
+            | Self::Banner | Self::Status => verbosity >= Verbosity::Normal,
+            | Self::Verbose => verbosity >= Verbosity::Verbose,
+            | Self::Warning => true,
+        }
+    }
+}
+
+/// Where the messages and progress the library reports while it works go
+pub trait Reporter: Send + Sync {
+    /// Called with every message that isn't a result
+    fn note(&self, note: Note, message: Arguments<'_>);
+    /// Called after each step of a long run, such as gathering data, in the
+    /// order the steps end
+    fn step(&self, _event: &ProgressEvent) {}
+    /// Called once a run named `task` is over
+    fn finish(&self, _task: &str) {}
+}
+
+/// A [`Reporter`] that drops everything
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Silent;
+
+impl Reporter for Silent {
+    fn note(&self, _: Note, _: Arguments<'_>) {}
+}
+
+/// Reports welcome text or instructions to a [`Reporter`]
+#[macro_export]
+macro_rules! banner {
+    ($to:expr, $($arg:tt)*) => {
+        $crate::verbosity::Reporter::note($to, $crate::verbosity::Note::Banner, format_args!($($arg)*))
+    };
+}
+
+/// Reports a note about what the program is doing to a [`Reporter`]
+#[macro_export]
+macro_rules! status {
+    ($to:expr, $($arg:tt)*) => {
+        $crate::verbosity::Reporter::note($to, $crate::verbosity::Note::Status, format_args!($($arg)*))
+    };
+}
+
+/// Reports a diagnostic to a [`Reporter`]
+#[macro_export]
+macro_rules! verbose {
+    ($to:expr, $($arg:tt)*) => {
+        $crate::verbosity::Reporter::note($to, $crate::verbosity::Note::Verbose, format_args!($($arg)*))
+    };
+}
+
+/// Reports a warning to a [`Reporter`]
+#[macro_export]
+macro_rules! warning {
+    ($to:expr, $($arg:tt)*) => {
+        $crate::verbosity::Reporter::note($to, $crate::verbosity::Note::Warning, format_args!($($arg)*))
+    };
+}
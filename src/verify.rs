@@ -0,0 +1,140 @@
+//! The `verify` command, for checking the guess logic against rows recorded
+//! from the website
+
+use std::path::Path;
+
+use eyre::{eyre, Context};
+use ptndle_core::attribute::Attribute;
+use ptndle_core::data::{load_sinners, DataSource, Sinner};
+use ptndle_core::error::Error;
+use ptndle_core::fuzzy::find_sinner;
+use ptndle_core::guess::Guess;
+use ptndle_core::play::NameAndGuess;
+use ptndle_core::theme::Role;
+
+use crate::flags::Verify;
+use crate::output::{print_json, VerifyMismatch, VerifyReport};
+use crate::settings;
+
+/// A row recorded from the website: guessing `guess` with `target` as the
+/// target gave `expected`
+struct Recorded<'sinners> {
+    /// The line of the file it is on, counting from 1
+    line: usize,
+    target: &'sinners Sinner,
+    guess: &'sinners Sinner,
+    expected: Guess,
+}
+
+/// Reads the recorded rows in `path`, one `target; guess: row` per line
+fn read_recorded<'sinners>(
+    path: &Path,
+    sinners: &'sinners [Sinner],
+) -> eyre::Result<Vec<Recorded<'sinners>>> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| Error::Io(format!("Failed to read {}: {e}", path.display())))?;
+    let mut recorded = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let context = || format!("On line {line_number} of {}", path.display());
+        let (target, rest) = line
+            .split_once(';')
+            .ok_or_else(|| {
+                Error::InvalidInput(
+                    "Expected `target; guess: row`, like `Hella; L.L.: ~ 0 0 v 0`".to_owned(),
+                )
+            })
+            .with_context(context)?;
+        let NameAndGuess { name, guess } = rest
+            .parse::<NameAndGuess>()
+            .map_err(|e| Error::InvalidInput(e.to_string()))
+            .with_context(context)?;
+        recorded.push(Recorded {
+            line: line_number,
+            target: find_sinner(sinners, target.trim(), settings::names()).with_context(context)?,
+            guess: find_sinner(sinners, &name, settings::names()).with_context(context)?,
+            expected: guess,
+        });
+    }
+    Ok(recorded)
+}
+
+/// The names of the columns that differ between `a` and `b`
+fn differing_columns(a: Guess, b: Guess) -> Vec<String> {
+    Attribute::ALL
+        .into_iter()
+        .filter(|x| a.cell(*x) != b.cell(*x))
+        .map(|x| x.to_str().to_owned())
+        .collect()
+}
+
+/// Checks every row in the file against [`Sinner::guess`], failing if any of
+/// them don't match
+pub fn run(Verify { file }: Verify, source: &DataSource, json: bool) -> eyre::Result<()> {
+    let sinners = load_sinners(&settings::context(), source, &<_>::default())?;
+    let recorded = read_recorded(&file, &sinners)?;
+    if recorded.is_empty() {
+        return Err(Error::InvalidInput(format!(
+            "{} doesn't have any rows to check",
+            file.display()
+        ))
+        .into());
+    }
+    let mismatches = recorded
+        .iter()
+        .filter_map(|row| {
+            let actual = row.target.guess(row.guess, settings::thresholds());
+            (actual != row.expected).then(|| {
+                VerifyMismatch {
+                    line: row.line,
+                    target: row.target.name.clone(),
+                    guess: row.guess.name.clone(),
+                    expected: row.expected.to_ascii(),
+                    actual: actual.to_ascii(),
+                    columns: differing_columns(row.expected, actual),
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    let report = VerifyReport {
+        checked: recorded.len(),
+        mismatches,
+    };
+    if json {
+        print_json(&report);
+    } else if report.mismatches.is_empty() {
+        println!(
+            "{}",
+            settings::paint(
+                format!("{0} of {0} rows match", report.checked),
+                Role::Correct
+            )
+        );
+    } else {
+        for mismatch in &report.mismatches {
+            println!(
+                "Line {}: guessing {} with {} as the target gives `{}`, but the website gave \
+                 `{}`. Different: {}",
+                mismatch.line,
+                mismatch.guess,
+                mismatch.target,
+                mismatch.actual,
+                mismatch.expected,
+                mismatch.columns.join(", ")
+            );
+        }
+    }
+    if report.mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "{} of {} rows don't match the website",
+            report.mismatches.len(),
+            report.checked
+        ))
+    }
+}
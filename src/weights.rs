@@ -0,0 +1,84 @@
+//! How likely each sinner is to be picked as the target, for solving with a
+//! prior other than every sinner being equally likely
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use eyre::Context;
+
+use crate::data::Sinner;
+use crate::error::Error;
+use crate::fuzzy::{find_sinner, Names};
+
+/// The relative chance of each sinner being the target. Only the ratios
+/// between weights matter, so they can be counts of how often the website
+/// picked each sinner or made-up numbers.
+#[derive(Debug, Clone)]
+pub struct Weights {
+    /// The weights of the sinners listed in the file, by name
+    listed: HashMap<String, f64>,
+    /// The weight of every sinner that isn't listed
+    default: f64,
+}
+
+/// Parses a weight, which has to be a positive number so that every sinner
+/// stays possible
+fn parse_weight(text: &str) -> eyre::Result<f64> {
+    let weight = text.trim().parse::<f64>().map_err(|_| {
+        Error::InvalidInput(format!(
+            "Expected a number for the weight, found `{}`",
+            text.trim()
+        ))
+    })?;
+    if !weight.is_finite() || weight <= 0. {
+        return Err(Error::InvalidInput(format!(
+            "The weight is {weight}, expected a positive number"
+        ))
+        .into());
+    }
+    Ok(weight)
+}
+
+impl Weights {
+    /// Reads a weights file, with one `name: weight` per line. Sinners that
+    /// aren't listed have a weight of 1, or the weight on a `*: weight` line.
+    /// Names are matched against `sinners` like names entered when solving,
+    /// with the aliases and translations in `names`.
+    pub fn load(path: &Path, sinners: &[Sinner], names: &Names) -> eyre::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| Error::Io(format!("Failed to read {}: {e}", path.display())))?;
+        let mut weights = Self {
+            listed: HashMap::new(),
+            default: 1.,
+        };
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let context = || format!("On line {} of {}", index + 1, path.display());
+            let (name, weight) = line
+                .rsplit_once(':')
+                .ok_or_else(|| {
+                    Error::InvalidInput("Expected `name: weight`, like `Hella: 2.5`".to_owned())
+                })
+                .with_context(context)?;
+            let weight = parse_weight(weight).with_context(context)?;
+            if name.trim() == "*" {
+                weights.default = weight;
+                continue;
+            }
+            let sinner = find_sinner(sinners, name.trim(), names).with_context(context)?;
+            weights.listed.insert(sinner.name.clone(), weight);
+        }
+        Ok(weights)
+    }
+
+    /// The weight of `sinner`
+    pub fn of(&self, sinner: &Sinner) -> f64 {
+        self.listed
+            .get(&sinner.name)
+            .copied()
+            .unwrap_or(self.default)
+    }
+}